@@ -0,0 +1,187 @@
+// Package experimentation wraps the experimentation_client FFI so that Go
+// services can evaluate experiments locally.
+//
+// The package links against libexperimentation_client, built with
+// `cargo build --release -p experimentation_client --features c_exports` at the
+// root of the repository. The c_exports feature, on by default, exports the FFI
+// functions under the unprefixed names used here; without it linking fails
+// with undefined references such as new_client.
+package experimentation
+
+/*
+#cgo CFLAGS: -I${SRCDIR}/../../../headers
+#cgo LDFLAGS: -L${SRCDIR}/../../../target/release -lexperimentation_client
+#include <stdlib.h>
+#include "libexperimentation_client.h"
+*/
+import "C"
+
+import (
+	"encoding/json"
+	"errors"
+	"runtime"
+	"sync"
+	"time"
+	"unsafe"
+)
+
+// ErrClosed is returned by the methods of a Client after Close.
+var ErrClosed = errors.New("experimentation: client is closed")
+
+// Variant is a variant of an experiment along with the overrides it applies.
+type Variant struct {
+	ID          string                 `json:"id"`
+	VariantType string                 `json:"variant_type"`
+	Overrides   map[string]interface{} `json:"overrides"`
+}
+
+// Experiment is a running experiment as known to the client.
+type Experiment struct {
+	ID                string          `json:"id"`
+	Name              string          `json:"name"`
+	TrafficPercentage uint8           `json:"traffic_percentage"`
+	Context           json.RawMessage `json:"context"`
+	Status            string          `json:"status"`
+	Variants          []Variant       `json:"variants"`
+}
+
+// Client evaluates the experiments of a single tenant. It is safe for
+// concurrent use.
+type Client struct {
+	tenant string
+
+	mu  sync.RWMutex
+	ptr *C.struct_Arc_Client
+}
+
+// NewClient creates the client of tenant, which keeps its experiments in sync
+// with hostname every pollFrequency once StartPolling is called. Creating a
+// client for a tenant that already has one returns a handle to the same
+// client.
+func NewClient(tenant string, pollFrequency time.Duration, hostname string) (*Client, error) {
+	cTenant := C.CString(tenant)
+	defer C.free(unsafe.Pointer(cTenant))
+	cHostname := C.CString(hostname)
+	defer C.free(unsafe.Pointer(cHostname))
+
+	// errors are kept in a thread local on the Rust side
+	runtime.LockOSThread()
+	defer runtime.UnlockOSThread()
+
+	seconds := C.ulong(pollFrequency / time.Second)
+	if C.new_client(cTenant, seconds, cHostname) != 0 {
+		return nil, lastError()
+	}
+	ptr := C.get_client(cTenant)
+	if ptr == nil {
+		return nil, lastError()
+	}
+
+	client := &Client{tenant: tenant, ptr: ptr}
+	runtime.SetFinalizer(client, (*Client).Close)
+	return client, nil
+}
+
+// StartPolling starts syncing experiments in the background. The polling
+// loop occupies an OS thread for the lifetime of the process.
+func (c *Client) StartPolling() {
+	go func() {
+		cTenant := C.CString(c.tenant)
+		defer C.free(unsafe.Pointer(cTenant))
+		C.start_polling_update(cTenant)
+	}()
+}
+
+// ApplicableVariants returns the ids of the variants that context falls into
+// for toss, one per matching experiment. A negative toss picks the first
+// experimental variant of every matching experiment.
+func (c *Client) ApplicableVariants(context interface{}, toss int16) ([]string, error) {
+	cContext, err := marshalCString(context)
+	if err != nil {
+		return nil, err
+	}
+	defer C.free(unsafe.Pointer(cContext))
+
+	var variants []string
+	err = c.call(func(ptr *C.struct_Arc_Client) *C.char {
+		return C.get_applicable_variant(ptr, cContext, C.short(toss))
+	}, &variants)
+	return variants, err
+}
+
+// SatisfiedExperiments returns the running experiments whose context matches
+// context.
+func (c *Client) SatisfiedExperiments(context interface{}) ([]Experiment, error) {
+	cContext, err := marshalCString(context)
+	if err != nil {
+		return nil, err
+	}
+	defer C.free(unsafe.Pointer(cContext))
+
+	var experiments []Experiment
+	err = c.call(func(ptr *C.struct_Arc_Client) *C.char {
+		return C.get_satisfied_experiments(ptr, cContext)
+	}, &experiments)
+	return experiments, err
+}
+
+// RunningExperiments returns all experiments currently known to the client.
+func (c *Client) RunningExperiments() ([]Experiment, error) {
+	var experiments []Experiment
+	err := c.call(func(ptr *C.struct_Arc_Client) *C.char {
+		return C.get_running_experiments(ptr)
+	}, &experiments)
+	return experiments, err
+}
+
+// Close releases the handle to the client. The client itself lives on for
+// other handles of the same tenant.
+func (c *Client) Close() error {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	if c.ptr != nil {
+		C.free_client(c.ptr)
+		c.ptr = nil
+		runtime.SetFinalizer(c, nil)
+	}
+	return nil
+}
+
+// call runs f against the client and decodes the JSON string it returns into
+// out, freeing the string afterwards.
+func (c *Client) call(f func(*C.struct_Arc_Client) *C.char, out interface{}) error {
+	c.mu.RLock()
+	defer c.mu.RUnlock()
+	if c.ptr == nil {
+		return ErrClosed
+	}
+
+	runtime.LockOSThread()
+	defer runtime.UnlockOSThread()
+
+	result := f(c.ptr)
+	if result == nil {
+		return lastError()
+	}
+	defer C.free_string(result)
+	return json.Unmarshal([]byte(C.GoString(result)), out)
+}
+
+func marshalCString(value interface{}) (*C.char, error) {
+	data, err := json.Marshal(value)
+	if err != nil {
+		return nil, err
+	}
+	return C.CString(string(data)), nil
+}
+
+// lastError takes the error recorded by the last failed call on this OS
+// thread.
+func lastError() error {
+	message := C.last_error_message()
+	if message == nil {
+		return errors.New("experimentation: unknown error")
+	}
+	defer C.free_string((*C.char)(unsafe.Pointer(message)))
+	return errors.New(C.GoString(message))
+}
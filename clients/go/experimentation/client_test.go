@@ -0,0 +1,162 @@
+package experimentation
+
+import (
+	"errors"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"strings"
+	"testing"
+	"time"
+)
+
+// experimentsResponse is the experiment list served by the mock server, with a
+// single experiment running at 10% traffic for android.
+const experimentsResponse = `{
+	"total_items": 1,
+	"total_pages": 1,
+	"data": [{
+		"id": "go-1",
+		"name": "experiment-go-1",
+		"status": "INPROGRESS",
+		"traffic_percentage": 10,
+		"context": {"==": [{"var": "os"}, "android"]},
+		"variants": [
+			{"id": "go-1-control", "variant_type": "CONTROL", "overrides": {"key": "control"}},
+			{"id": "go-1-experimental", "variant_type": "EXPERIMENTAL", "overrides": {"key": "experimental"}}
+		]
+	}]
+}`
+
+func TestNewClientRejectsInvalidHostname(t *testing.T) {
+	client, err := NewClient("go-invalid-hostname", time.Second, "not a url")
+	if err == nil {
+		client.Close()
+		t.Fatal("expected an error for an invalid hostname")
+	}
+	if !strings.Contains(err.Error(), "hostname") {
+		t.Errorf("unexpected error: %v", err)
+	}
+}
+
+func TestNewClientRejectsZeroPollFrequency(t *testing.T) {
+	client, err := NewClient("go-zero-frequency", 0, "http://localhost:8080")
+	if err == nil {
+		client.Close()
+		t.Fatal("expected an error for a zero poll frequency")
+	}
+}
+
+func TestClientWithoutExperiments(t *testing.T) {
+	client, err := NewClient("go-no-experiments", 10*time.Second, "http://localhost:8080")
+	if err != nil {
+		t.Fatalf("failed to create client: %v", err)
+	}
+	defer client.Close()
+
+	context := map[string]interface{}{"os": "android", "clientId": "go"}
+	variants, err := client.ApplicableVariants(context, 42)
+	if err != nil {
+		t.Fatalf("ApplicableVariants failed: %v", err)
+	}
+	if len(variants) != 0 {
+		t.Errorf("expected no variants, got %v", variants)
+	}
+
+	satisfied, err := client.SatisfiedExperiments(context)
+	if err != nil {
+		t.Fatalf("SatisfiedExperiments failed: %v", err)
+	}
+	if len(satisfied) != 0 {
+		t.Errorf("expected no satisfied experiments, got %v", satisfied)
+	}
+
+	running, err := client.RunningExperiments()
+	if err != nil {
+		t.Fatalf("RunningExperiments failed: %v", err)
+	}
+	if len(running) != 0 {
+		t.Errorf("expected no running experiments, got %v", running)
+	}
+}
+
+func TestApplicableVariantsRejectsUnencodableContext(t *testing.T) {
+	client, err := NewClient("go-bad-context", 10*time.Second, "http://localhost:8080")
+	if err != nil {
+		t.Fatalf("failed to create client: %v", err)
+	}
+	defer client.Close()
+
+	if _, err := client.ApplicableVariants(make(chan int), 0); err == nil {
+		t.Error("expected an error for a context that is not JSON encodable")
+	}
+}
+
+func TestClosedClient(t *testing.T) {
+	client, err := NewClient("go-closed", 10*time.Second, "http://localhost:8080")
+	if err != nil {
+		t.Fatalf("failed to create client: %v", err)
+	}
+	if err := client.Close(); err != nil {
+		t.Fatalf("Close failed: %v", err)
+	}
+	if err := client.Close(); err != nil {
+		t.Fatalf("second Close failed: %v", err)
+	}
+
+	if _, err := client.RunningExperiments(); !errors.Is(err, ErrClosed) {
+		t.Errorf("expected ErrClosed, got %v", err)
+	}
+}
+
+func TestPollingFromMockServer(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/experiments" || r.Header.Get("x-tenant") != "go-mock-server" {
+			http.NotFound(w, r)
+			return
+		}
+		w.Header().Set("Content-Type", "application/json")
+		io.WriteString(w, experimentsResponse)
+	}))
+	defer server.Close()
+
+	client, err := NewClient("go-mock-server", time.Second, server.URL)
+	if err != nil {
+		t.Fatalf("failed to create client: %v", err)
+	}
+	defer client.Close()
+	client.StartPolling()
+
+	// the first poll happens as soon as polling starts
+	deadline := time.Now().Add(5 * time.Second)
+	for {
+		running, err := client.RunningExperiments()
+		if err != nil {
+			t.Fatalf("RunningExperiments failed: %v", err)
+		}
+		if len(running) == 1 {
+			break
+		}
+		if time.Now().After(deadline) {
+			t.Fatal("experiments were not fetched from the mock server")
+		}
+		time.Sleep(50 * time.Millisecond)
+	}
+
+	variants, err := client.ApplicableVariants(map[string]interface{}{"os": "android"}, 15)
+	if err != nil {
+		t.Fatalf("ApplicableVariants failed: %v", err)
+	}
+	if !reflect.DeepEqual(variants, []string{"go-1-experimental"}) {
+		t.Errorf("expected the experimental variant, got %v", variants)
+	}
+
+	satisfied, err := client.SatisfiedExperiments(map[string]interface{}{"os": "ios"})
+	if err != nil {
+		t.Fatalf("SatisfiedExperiments failed: %v", err)
+	}
+	if len(satisfied) != 0 {
+		t.Errorf("expected no experiment for ios, got %v", satisfied)
+	}
+}
@@ -0,0 +1,3 @@
+module github.com/juspay/superposition/clients/go
+
+go 1.21
@@ -0,0 +1,97 @@
+import { ExperimentStore } from "../pkg/experimentation_client_wasm";
+import { ClientConfig, Context, Experiment } from "./types";
+
+export * from "./types";
+
+interface ListExperimentsResponse {
+    total_pages: number;
+    data: Experiment[];
+}
+
+const PAGE_SIZE = 10;
+
+/**
+ * Polls the experiments of a tenant and evaluates them in WebAssembly, the
+ * same way the Rust `experimentation_client` does.
+ */
+export class ExperimentationClient {
+    private readonly store = new ExperimentStore();
+    private lastPolled = new Date(Date.UTC(2023, 0, 1));
+    private timer: ReturnType<typeof setInterval> | undefined;
+
+    constructor(private readonly config: ClientConfig) {}
+
+    /** Fetches experiments right away and then every `pollFrequencyMs`. */
+    async start(): Promise<void> {
+        this.stop();
+        await this.refresh();
+        this.timer = setInterval(() => {
+            this.refresh().catch((err) =>
+                console.error("failed to fetch experiments", err)
+            );
+        }, this.config.pollFrequencyMs);
+    }
+
+    stop(): void {
+        if (this.timer !== undefined) {
+            clearInterval(this.timer);
+            this.timer = undefined;
+        }
+    }
+
+    /**
+     * Fetches experiments changed since the last poll, returning how many were
+     * added to, updated in or removed from the store.
+     */
+    async refresh(): Promise<number> {
+        const now = new Date();
+        let applied = 0;
+        for (let page = 1; ; page++) {
+            const response = await this.fetchPage(this.lastPolled, now, page);
+            applied += this.store.applyExperiments(JSON.stringify(response.data));
+            if (page >= response.total_pages) {
+                break;
+            }
+        }
+        this.lastPolled = now;
+        return applied;
+    }
+
+    getApplicableVariants(context: Context, toss: number): string[] {
+        return JSON.parse(
+            this.store.applicableVariants(JSON.stringify(context), toss)
+        );
+    }
+
+    getSatisfiedExperiments(context: Context): Experiment[] {
+        return JSON.parse(this.store.satisfiedExperiments(JSON.stringify(context)));
+    }
+
+    getRunningExperiments(): Experiment[] {
+        return JSON.parse(this.store.runningExperiments());
+    }
+
+    private async fetchPage(
+        from: Date,
+        to: Date,
+        page: number
+    ): Promise<ListExperimentsResponse> {
+        const query = new URLSearchParams({
+            from_date: from.toISOString(),
+            to_date: to.toISOString(),
+            page: page.toString(),
+            count: PAGE_SIZE.toString(),
+            status: "CREATED,INPROGRESS,CONCLUDED",
+        });
+        const response = await fetch(
+            `${this.config.hostname}/experiments?${query}`,
+            { headers: { "x-tenant": this.config.tenant } }
+        );
+        if (!response.ok) {
+            throw new Error(
+                `experiment server returned ${response.status}: ${await response.text()}`
+            );
+        }
+        return response.json();
+    }
+}
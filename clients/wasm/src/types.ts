@@ -0,0 +1,27 @@
+export type Context = Record<string, unknown>;
+
+export type VariantType = "CONTROL" | "EXPERIMENTAL";
+
+export interface Variant {
+    id: string;
+    variant_type: VariantType;
+    overrides: Record<string, unknown>;
+}
+
+export type ExperimentStatus = "CREATED" | "INPROGRESS" | "CONCLUDED";
+
+export interface Experiment {
+    id: string;
+    name: string;
+    traffic_percentage: number;
+    context: unknown;
+    status: ExperimentStatus;
+    variants: Variant[];
+}
+
+export interface ClientConfig {
+    tenant: string;
+    hostname: string;
+    /** milliseconds between two polls of the experiment server */
+    pollFrequencyMs: number;
+}
@@ -20,6 +20,49 @@ pub fn merge(doc: &mut Value, patch: &Value) {
     }
 }
 
+/// Separates an override key of the form `<config key>#<JSON pointer>` into the
+/// config key and the RFC 6901 pointer it targets inside that key's value.
+pub fn split_pointer_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once("#/") {
+        Some((config_key, _)) => (config_key, Some(&key[config_key.len() + 1..])),
+        None => (key, None),
+    }
+}
+
+/// Sets the value at `pointer` inside `doc`, creating the objects missing on
+/// the way. Array elements can be replaced but not appended.
+pub fn set_pointer(doc: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let mut target = doc;
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        target = match target {
+            Value::Array(items) => {
+                let len = items.len();
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(|| {
+                        format!("index {token} is out of bounds for length {len}")
+                    })?
+            }
+            other => {
+                if !other.is_object() {
+                    *other = Value::Object(Map::new());
+                }
+                // the value was just made an object
+                other
+                    .as_object_mut()
+                    .unwrap()
+                    .entry(token)
+                    .or_insert(Value::Null)
+            }
+        };
+    }
+    *target = value;
+    Ok(())
+}
+
 fn replace_top_level(
     doc: &mut Map<String, Value>,
     patch: &Value,
@@ -84,7 +127,13 @@ fn merge_overrides_on_default_config(
     overrides: Map<String, Value>,
     merge_strategy: &MergeStrategy,
 ) {
-    overrides.into_iter().for_each(|(key, val)| {
+    // whole value overrides of a key go first, the paths targeted inside it
+    // through pointer keys are then set on top of them
+    let (pointer_overrides, key_overrides): (Vec<_>, Vec<_>) = overrides
+        .into_iter()
+        .partition(|(key, _)| split_pointer_key(key).1.is_some());
+
+    key_overrides.into_iter().for_each(|(key, val)| {
         if let Some(og_val) = default_config.get_mut(&key) {
             match merge_strategy {
                 MergeStrategy::REPLACE => {
@@ -95,6 +144,17 @@ fn merge_overrides_on_default_config(
         } else {
             log::error!("CAC: found non-default_config key: {key} in overrides");
         }
+    });
+    pointer_overrides.into_iter().for_each(|(key, val)| {
+        let (config_key, pointer) = split_pointer_key(&key);
+        match (default_config.get_mut(config_key), pointer) {
+            (Some(og_val), Some(pointer)) => {
+                if let Err(err) = set_pointer(og_val, pointer, val) {
+                    log::error!("CAC: failed to apply override {key}: {err}");
+                }
+            }
+            _ => log::error!("CAC: found non-default_config key: {key} in overrides"),
+        }
     })
 }
 
@@ -120,26 +180,23 @@ pub fn eval_cac(
     Ok(overriden_config)
 }
 
-pub fn eval_cac_with_reasoning(
+/// Resolves the config like `eval_cac`, also returning the contexts whose
+/// overrides were applied, in the order they were applied.
+pub fn eval_cac_with_applied_contexts(
     mut default_config: Map<String, Value>,
     contexts: &Vec<Context>,
     overrides: &Map<String, Value>,
     query_data: &Map<String, Value>,
     merge_strategy: MergeStrategy,
-) -> Result<Map<String, Value>, String> {
-    let mut reasoning: Vec<Value> = vec![];
+) -> Result<(Map<String, Value>, Vec<Context>), String> {
+    let mut applied_contexts: Vec<Context> = vec![];
 
     let applied_overrides: Map<String, Value> = get_overrides(
-        &query_data,
-        &contexts,
-        &overrides,
+        query_data,
+        contexts,
+        overrides,
         &merge_strategy,
-        Some(&mut |context| {
-            reasoning.push(json!({
-                "context": context.condition,
-                "override": context.override_with_keys
-            }))
-        }),
+        Some(&mut |context| applied_contexts.push(context)),
     )
     .and_then(serde_json::from_value)
     .map_err_to_string()?;
@@ -149,7 +206,131 @@ pub fn eval_cac_with_reasoning(
         applied_overrides,
         &merge_strategy,
     );
-    let mut overriden_config = default_config;
+    Ok((default_config, applied_contexts))
+}
+
+pub fn eval_cac_with_reasoning(
+    default_config: Map<String, Value>,
+    contexts: &Vec<Context>,
+    overrides: &Map<String, Value>,
+    query_data: &Map<String, Value>,
+    merge_strategy: MergeStrategy,
+) -> Result<Map<String, Value>, String> {
+    let (mut overriden_config, applied_contexts) = eval_cac_with_applied_contexts(
+        default_config,
+        contexts,
+        overrides,
+        query_data,
+        merge_strategy,
+    )?;
+    let reasoning: Vec<Value> = applied_contexts
+        .into_iter()
+        .map(|context| {
+            json!({
+                "context": context.condition,
+                "override": context.override_with_keys
+            })
+        })
+        .collect();
     overriden_config.insert("metadata".into(), json!(reasoning));
     Ok(overriden_config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pointer_key() {
+        assert_eq!(split_pointer_key("ui"), ("ui", None));
+        assert_eq!(
+            split_pointer_key("ui#/theme/primary_color"),
+            ("ui", Some("/theme/primary_color"))
+        );
+    }
+
+    #[test]
+    fn test_pointer_override_merges_path() {
+        let mut default_config = json!({
+            "ui": {"theme": {"primary_color": "red", "font": "serif"}},
+            "timeout": 10
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        let overrides = json!({"ui#/theme/primary_color": "blue"})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        merge_overrides_on_default_config(
+            &mut default_config,
+            overrides,
+            &MergeStrategy::REPLACE,
+        );
+
+        assert_eq!(
+            Value::Object(default_config),
+            json!({
+                "ui": {"theme": {"primary_color": "blue", "font": "serif"}},
+                "timeout": 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_pointer_unescapes_and_creates_objects() {
+        let mut doc = json!({"a": [1, 2]});
+        set_pointer(&mut doc, "/b~1c/d~0e", json!(true)).unwrap();
+        set_pointer(&mut doc, "/a/1", json!(3)).unwrap();
+        assert_eq!(doc, json!({"a": [1, 3], "b/c": {"d~e": true}}));
+        assert!(set_pointer(&mut doc, "/a/5", json!(0)).is_err());
+    }
+
+    #[test]
+    fn test_applied_contexts_follow_resolution_order() {
+        let default_config = json!({"flag": false, "timeout": 10})
+            .as_object()
+            .unwrap()
+            .clone();
+        let context = |condition: Value, override_key: &str| Context {
+            condition,
+            override_with_keys: [override_key.to_string()],
+        };
+        let contexts = vec![
+            context(json!({"==": [{"var": "city"}, "Delhi"]}), "o1"),
+            context(json!({"==": [{"var": "city"}, "Mumbai"]}), "o2"),
+            context(json!({"==": [{"var": "os"}, "android"]}), "o3"),
+        ];
+        let overrides = json!({
+            "o1": {"flag": true},
+            "o2": {"timeout": 5},
+            "o3": {"timeout": 20}
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        let query_data = json!({"city": "Delhi", "os": "android"})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        let (config, applied_contexts) = eval_cac_with_applied_contexts(
+            default_config,
+            &contexts,
+            &overrides,
+            &query_data,
+            MergeStrategy::MERGE,
+        )
+        .unwrap();
+
+        assert_eq!(Value::Object(config), json!({"flag": true, "timeout": 20}));
+        assert_eq!(
+            applied_contexts
+                .iter()
+                .map(|ctx| ctx.override_with_keys[0].as_str())
+                .collect::<Vec<_>>(),
+            vec!["o1", "o3"]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{eval, utils::core::MapError, Client, MergeStrategy};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FlagDetails {
+    pub key: String,
+    pub enabled: bool,
+    pub value: Option<Value>,
+    pub context: Option<Value>,
+    pub override_key: Option<String>,
+}
+
+fn coerce_to_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(flag) => Some(*flag),
+        Value::String(flag) => flag.to_lowercase().parse::<bool>().ok(),
+        _ => None,
+    }
+}
+
+fn to_query_data(context: &Value) -> Map<String, Value> {
+    context.as_object().cloned().unwrap_or_default()
+}
+
+impl Client {
+    /// Returns whether the boolean flag `key` is on for `context`; absent or
+    /// non-boolean values are treated as disabled.
+    pub async fn is_enabled(&self, key: &str, context: &Value) -> bool {
+        match self.get_resolved_config(
+            to_query_data(context),
+            Some(vec![key.to_string()]),
+            MergeStrategy::MERGE,
+        ) {
+            Ok(config) => config.get(key).and_then(coerce_to_bool).unwrap_or(false),
+            Err(err) => {
+                log::error!("{}: failed to resolve flag {key}: {err}", self.tenant);
+                false
+            }
+        }
+    }
+
+    /// Returns the resolved value of `key` along with the context and override
+    /// that set it, if any.
+    pub async fn get_flag_details(
+        &self,
+        key: &str,
+        context: &Value,
+    ) -> Result<FlagDetails, String> {
+        let cac = self.config.read().map_err_to_string()?;
+        let (mut config, applied_contexts) = eval::eval_cac_with_applied_contexts(
+            cac.default_configs.to_owned(),
+            &cac.contexts,
+            &cac.overrides,
+            &to_query_data(context),
+            MergeStrategy::MERGE,
+        )?;
+
+        // contexts are applied in order, so the last one overriding the key,
+        // or a path inside it, is the one that set its value
+        let overrides_key = |override_key: &String| {
+            cac.overrides
+                .get(override_key)
+                .and_then(Value::as_object)
+                .is_some_and(|overrides| {
+                    overrides
+                        .keys()
+                        .any(|config_key| eval::split_pointer_key(config_key).0 == key)
+                })
+        };
+        let (context, override_key) = applied_contexts
+            .into_iter()
+            .rev()
+            .find_map(|ctx| {
+                let [override_key] = ctx.override_with_keys;
+                overrides_key(&override_key).then_some((ctx.condition, override_key))
+            })
+            .unzip();
+        let value = config.remove(key);
+
+        Ok(FlagDetails {
+            key: key.to_string(),
+            enabled: value.as_ref().and_then(coerce_to_bool).unwrap_or(false),
+            value,
+            context,
+            override_key,
+        })
+    }
+}
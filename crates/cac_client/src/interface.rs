@@ -64,7 +64,7 @@ pub fn take_last_error() -> Option<Box<String>> {
     LAST_ERROR.with(|prev| prev.borrow_mut().take())
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn last_error_length() -> c_int {
     LAST_ERROR.with(|prev| match *prev.borrow() {
         Some(ref err) => err.to_string().len() as c_int + 1,
@@ -72,7 +72,7 @@ pub extern "C" fn last_error_length() -> c_int {
     })
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub unsafe extern "C" fn last_error_message() -> *const c_char {
     let last_error = unwrap_safe!(
         take_last_error().ok_or("No error found"),
@@ -84,7 +84,7 @@ pub unsafe extern "C" fn last_error_message() -> *const c_char {
     err.into_raw()
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub unsafe extern "C" fn free_string(s: *mut c_char) {
     if s.is_null() {
         return;
@@ -94,7 +94,7 @@ pub unsafe extern "C" fn free_string(s: *mut c_char) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn new_client(
     tenant: *const c_char,
     update_frequency: c_ulong,
@@ -121,7 +121,7 @@ pub extern "C" fn new_client(
     return 0;
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn start_polling_update(tenant: *const c_char) {
     null_check!(tenant, "NULL pointer provided for tenant", return ());
     unsafe {
@@ -136,7 +136,7 @@ pub extern "C" fn start_polling_update(tenant: *const c_char) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn free_client(ptr: *mut Arc<Client>) {
     if ptr.is_null() {
         return;
@@ -146,7 +146,7 @@ pub extern "C" fn free_client(ptr: *mut Arc<Client>) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_client(tenant: *const c_char) -> *mut Arc<Client> {
     let ten = unwrap_safe!(cstring_to_rstring(tenant), return std::ptr::null_mut());
     // println!("fetching cac client thread for tenant {ten}");
@@ -158,7 +158,7 @@ pub extern "C" fn get_client(tenant: *const c_char) -> *mut Arc<Client> {
     )
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_last_modified(client: *mut Arc<Client>) -> *const c_char {
     null_check!(
         client,
@@ -175,7 +175,7 @@ pub extern "C" fn get_last_modified(client: *mut Arc<Client>) -> *const c_char {
     )
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_config(
     client: *mut Arc<Client>,
     query: *const c_char,
@@ -219,7 +219,7 @@ pub extern "C" fn get_config(
     )
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_resolved_config(
     client: *mut Arc<Client>,
     query: *const c_char,
@@ -274,7 +274,7 @@ pub extern "C" fn get_resolved_config(
     )
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_default_config(
     client: *mut Arc<Client>,
     filter_keys: *const c_char,
@@ -1,5 +1,11 @@
 mod eval;
-mod interface;
+pub mod feature_flags;
+/// The C FFI. Its functions are exported unmangled with the default
+/// `c_exports` feature, `superposition_sdk` turns it off to export them under
+/// prefixed names instead.
+#[allow(clippy::missing_safety_doc, clippy::not_unsafe_ptr_arg_deref)]
+pub mod interface;
+mod typed_config;
 mod utils;
 
 use actix_web::{rt::time::interval, web::Data};
@@ -20,6 +26,14 @@ use utils::core::MapError;
 use service_utils::{
     helpers::extract_dimensions, result as superposition, unexpected_error,
 };
+pub use superposition_macros::superposition_config;
+pub use typed_config::TypedConfig;
+
+/// Re-exports used by the code `superposition_config!` expands to.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Context {
@@ -284,7 +298,7 @@ pub static CLIENT_FACTORY: Lazy<ClientFactory> =
 
 pub use eval::eval_cac;
 pub use eval::eval_cac_with_reasoning;
-pub use eval::merge;
+pub use eval::{merge, set_pointer, split_pointer_key};
 
 pub fn filter_keys_by_prefix(
     keys: Map<String, Value>,
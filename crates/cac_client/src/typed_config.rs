@@ -0,0 +1,28 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{Client, MergeStrategy};
+
+/// Config structs generated by `superposition_config!`.
+pub trait TypedConfig: DeserializeOwned {
+    /// config keys read into the struct, one per field
+    const KEYS: &'static [&'static str];
+    /// JSON schema of every key, keyed by config key
+    const SCHEMA: &'static str;
+}
+
+impl Client {
+    /// Resolves the keys of `T` for `context` and deserializes them into `T`.
+    pub fn get_typed_config<T: TypedConfig>(&self, context: &Value) -> Result<T, String> {
+        let query_data = context.as_object().cloned().unwrap_or_default();
+        let config = self.get_resolved_config(
+            query_data,
+            Some(T::KEYS.iter().map(|key| key.to_string()).collect()),
+            MergeStrategy::MERGE,
+        )?;
+        serde_json::from_value(Value::Object(config)).map_err(|err| {
+            log::error!("{}: failed to read typed config: {err}", self.tenant);
+            err.to_string()
+        })
+    }
+}
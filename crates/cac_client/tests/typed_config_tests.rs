@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use cac_client::{superposition_config, Client, TypedConfig};
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+superposition_config! {
+    CheckoutConfig { enable_upi: bool, max_retries: u8, gateway: String }
+}
+
+#[test]
+fn test_superposition_config_schema() {
+    let schema: Value = serde_json::from_str(CHECKOUT_CONFIG_SCHEMA).unwrap();
+    assert_eq!(
+        schema,
+        json!({
+            "enable_upi": { "type": "boolean" },
+            "max_retries": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "gateway": { "type": "string" }
+        })
+    );
+    assert_eq!(CheckoutConfig::SCHEMA, CHECKOUT_CONFIG_SCHEMA);
+    assert_eq!(
+        CheckoutConfig::KEYS,
+        &["enable_upi", "max_retries", "gateway"]
+    );
+}
+
+#[test]
+fn test_superposition_config_deserializes_resolved_config() {
+    let config: CheckoutConfig = serde_json::from_value(json!({
+        "enable_upi": true,
+        "max_retries": 3,
+        "gateway": "juspay"
+    }))
+    .unwrap();
+    assert!(config.enable_upi);
+    assert_eq!(config.max_retries, 3);
+    assert_eq!(config.gateway, "juspay");
+
+    let out_of_range = serde_json::from_value::<CheckoutConfig>(json!({
+        "enable_upi": true,
+        "max_retries": 300,
+        "gateway": "juspay"
+    }));
+    assert!(out_of_range.is_err());
+}
+
+#[tokio::test]
+async fn test_get_typed_config() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/config"))
+        .and(header("x-tenant", "test"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "contexts": [
+                {
+                    "condition": { "==": [{ "var": "os" }, "android"] },
+                    "override_with_keys": ["android-overrides"]
+                },
+                {
+                    "condition": { "==": [{ "var": "os" }, "ios"] },
+                    "override_with_keys": ["ios-overrides"]
+                }
+            ],
+            "overrides": {
+                "android-overrides": { "enable_upi": true, "gateway": "razorpay" },
+                "ios-overrides": { "max_retries": 300 }
+            },
+            "default_configs": {
+                "enable_upi": false,
+                "max_retries": 3,
+                "gateway": "juspay",
+                "unrelated_key": "ignored"
+            }
+        })))
+        .mount(&server)
+        .await;
+    let client = Client::new("test".to_string(), Duration::from_secs(60), server.uri())
+        .await
+        .unwrap();
+
+    let config: CheckoutConfig = client
+        .get_typed_config(&json!({ "os": "android" }))
+        .unwrap();
+    assert!(config.enable_upi);
+    assert_eq!(config.max_retries, 3);
+    assert_eq!(config.gateway, "razorpay");
+
+    // the ios override does not fit the u8 field
+    let mismatch = client.get_typed_config::<CheckoutConfig>(&json!({ "os": "ios" }));
+    assert!(mismatch.unwrap_err().contains("expected u8"));
+}
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE public.dimensions DROP COLUMN allowed_values;
+
+ALTER TABLE public.dimensions DROP COLUMN value_type;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE public.dimensions ADD COLUMN value_type VARCHAR NULL;
+
+ALTER TABLE public.dimensions ADD COLUMN allowed_values json NULL;
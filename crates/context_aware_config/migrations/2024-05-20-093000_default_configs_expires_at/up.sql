@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE public.default_configs ADD COLUMN expires_at TIMESTAMPTZ NULL;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS default_configs_changelog ON public.default_configs;
+
+DROP TRIGGER IF EXISTS contexts_changelog ON public.contexts;
+
+DROP FUNCTION IF EXISTS public.config_changelogger;
+
+DROP TABLE IF EXISTS public.config_changelog;
@@ -0,0 +1,94 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS public.config_changelog (
+    id uuid DEFAULT uuid_generate_v4() PRIMARY KEY,
+    table_name text NOT NULL,
+    entity_id text NOT NULL,
+    keys text[] NOT NULL,
+    action text NOT NULL,
+    actor text NOT NULL,
+    old_value json,
+    new_value json,
+    old_row json,
+    new_row json,
+    "timestamp" timestamp with time zone DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS config_changelog_timestamp_index ON public.config_changelog USING btree ("timestamp");
+CREATE INDEX IF NOT EXISTS config_changelog_keys_index ON public.config_changelog USING gin (keys);
+
+CREATE OR REPLACE FUNCTION public.config_changelogger() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    row_data json;
+    entity_id text;
+    old_value json;
+    new_value json;
+    old_row json;
+    new_row json;
+    changed_keys text[];
+    actor text;
+BEGIN
+    -- set per connection checkout by the service to the user making the change
+    actor := NULLIF(current_setting('app.actor', true), '');
+    IF (actor IS NULL AND TG_OP = 'INSERT') THEN
+        actor := row_to_json(NEW) ->> 'created_by';
+    END IF;
+    actor := COALESCE(actor, session_user::TEXT);
+
+    IF (TG_OP = 'DELETE') THEN
+        row_data := row_to_json(OLD);
+    ELSE
+        row_data := row_to_json(NEW);
+    END IF;
+
+    IF (TG_TABLE_NAME = 'contexts') THEN
+        -- moves change the id of a context, so changes are keyed by the old id
+        -- and carry both rows for the config preview to rewind
+        IF (TG_OP <> 'INSERT') THEN
+            old_row := row_to_json(OLD);
+            old_value := old_row -> 'override';
+        END IF;
+        IF (TG_OP <> 'DELETE') THEN
+            new_row := row_to_json(NEW);
+            new_value := new_row -> 'override';
+        END IF;
+        entity_id := COALESCE(old_row, new_row) ->> 'id';
+        SELECT COALESCE(array_agg(DISTINCT override_key), ARRAY[]::text[])
+            INTO changed_keys
+            FROM (
+                SELECT json_object_keys(COALESCE(old_value, '{}'::json)) AS override_key
+                UNION
+                SELECT json_object_keys(COALESCE(new_value, '{}'::json)) AS override_key
+            ) AS override_keys;
+    ELSE
+        entity_id := row_data ->> 'key';
+        IF (TG_OP <> 'INSERT') THEN
+            old_value := row_to_json(OLD) -> 'value';
+        END IF;
+        IF (TG_OP <> 'DELETE') THEN
+            new_value := row_to_json(NEW) -> 'value';
+        END IF;
+        changed_keys := ARRAY[entity_id];
+    END IF;
+
+    INSERT INTO public.config_changelog
+        (table_name, entity_id, keys, action, actor, old_value, new_value, old_row, new_row)
+        VALUES (
+            TG_TABLE_NAME::TEXT,
+            entity_id,
+            changed_keys,
+            TG_OP,
+            actor,
+            old_value,
+            new_value,
+            old_row,
+            new_row
+        );
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER contexts_changelog AFTER INSERT OR DELETE OR UPDATE ON public.contexts FOR EACH ROW EXECUTE FUNCTION public.config_changelogger();
+
+CREATE TRIGGER default_configs_changelog AFTER INSERT OR DELETE OR UPDATE ON public.default_configs FOR EACH ROW EXECUTE FUNCTION public.config_changelogger();
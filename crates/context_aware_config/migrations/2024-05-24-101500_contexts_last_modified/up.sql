@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE public.contexts ADD COLUMN last_modified TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
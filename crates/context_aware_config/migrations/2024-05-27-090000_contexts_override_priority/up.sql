@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE public.contexts ADD COLUMN override_priority INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS composite_dimensions_audit ON public.composite_dimensions;
+DROP TABLE public.composite_dimensions;
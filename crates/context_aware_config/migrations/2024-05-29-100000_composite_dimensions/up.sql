@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE public.composite_dimensions (
+    name TEXT PRIMARY KEY,
+    component_dimensions TEXT[] NOT NULL,
+    priority INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by TEXT NOT NULL
+);
+CREATE TRIGGER composite_dimensions_audit AFTER INSERT OR DELETE OR UPDATE ON public.composite_dimensions FOR EACH ROW EXECUTE FUNCTION public.event_logger();
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- not audited, last_active_at is bumped by regular traffic
+CREATE TABLE public.tenants (
+    id TEXT PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_active_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
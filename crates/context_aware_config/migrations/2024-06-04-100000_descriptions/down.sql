@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE public.default_configs DROP COLUMN IF EXISTS description;
+ALTER TABLE public.contexts DROP COLUMN IF EXISTS description;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE public.contexts ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE public.default_configs ADD COLUMN IF NOT EXISTS description TEXT;
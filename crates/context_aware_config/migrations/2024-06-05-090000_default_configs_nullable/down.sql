@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE public.default_configs DROP COLUMN IF EXISTS nullable;
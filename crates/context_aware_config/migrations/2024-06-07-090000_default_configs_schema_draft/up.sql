@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE public.default_configs ADD COLUMN IF NOT EXISTS schema_draft VARCHAR NOT NULL DEFAULT 'Draft7';
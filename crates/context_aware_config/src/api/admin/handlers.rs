@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashMap};
+
+use actix_web::{
+    get, patch, post,
+    web::{Data, Json},
+    Scope,
+};
+use chrono::Utc;
+use diesel::{
+    dsl::sum, upsert::excluded, Connection, ExpressionMethods, OptionalExtension,
+    QueryDsl, RunQueryDsl,
+};
+use experimentation_platform::{
+    api::experimentation_config::{
+        helpers::{fetch_flag_overrides, invalidate_cached_flags, merge_flag_overrides},
+        types::ExperimentationFlagsPatch,
+    },
+    db::{
+        models::ExperimentationConfig, schema::experimentation_config::dsl as exp_config,
+        MIGRATIONS_DIR as EXPERIMENTATION_MIGRATIONS_DIR,
+    },
+};
+use include_dir::Dir;
+use service_utils::{
+    bad_argument,
+    db::{
+        migrations::{run_pending_migrations, SchemaMigrations},
+        pgschema_manager::PoolStats,
+    },
+    helpers::validate_admin,
+    result as superposition,
+    service::types::{
+        AppExecutionNamespace, AppScope, AppState, DbConnection, ExperimentationFlags,
+        Tenant,
+    },
+    unexpected_error,
+};
+use superposition_types::User;
+
+use crate::{
+    api::admin::types::TenantSizeReport,
+    db::{
+        models::TenantActivity,
+        schema::{contexts, default_configs, tenants},
+        MIGRATIONS_DIR,
+    },
+};
+
+diesel::sql_function! {
+    /// Bytes used to store `value`, after compression.
+    fn pg_column_size(value: diesel::sql_types::Json) -> diesel::sql_types::Integer;
+}
+
+pub fn endpoints() -> Scope {
+    Scope::new("")
+        .service(migrate)
+        .service(db_stats)
+        .service(get_experimentation_config)
+        .service(update_experimentation_config)
+        .service(list_tenants)
+        .service(config_size_report)
+}
+
+#[post("/migrate")]
+async fn migrate(
+    state: Data<AppState>,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<Vec<String>>> {
+    validate_admin(&state, &user)?;
+
+    let sources: [(AppScope, &'static Dir<'static>); 2] = [
+        (AppScope::CAC, &MIGRATIONS_DIR),
+        (AppScope::EXPERIMENTATION, &EXPERIMENTATION_MIGRATIONS_DIR),
+    ];
+
+    let mut applied = Vec::new();
+    for (scope, dir) in sources {
+        let AppExecutionNamespace(schema) =
+            AppExecutionNamespace::for_scope(&state, &tenant, scope);
+        let DbConnection(mut conn) = DbConnection::for_scope(&state, &tenant, scope)?;
+        let migrations = SchemaMigrations::new(dir, schema.clone());
+        let names = run_pending_migrations(&mut conn, migrations).map_err(|e| {
+            log::error!("migrations failed for {schema}: {e}");
+            unexpected_error!("Failed to run migrations for {}: {}", schema, e)
+        })?;
+        applied.extend(names);
+    }
+
+    log::info!(
+        "{} applied migrations for {}: {:?}",
+        user.get_recorded_email(),
+        tenant.as_str(),
+        applied
+    );
+    Ok(Json(applied))
+}
+
+#[get("/db_stats")]
+async fn db_stats(
+    state: Data<AppState>,
+    user: User,
+) -> superposition::Result<Json<HashMap<String, PoolStats>>> {
+    validate_admin(&state, &user)?;
+    Ok(Json(state.db_pool.get_stats()))
+}
+
+#[get("/experimentation_config")]
+async fn get_experimentation_config(
+    state: Data<AppState>,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<ExperimentationFlags>> {
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+    let overrides = fetch_flag_overrides(&mut conn, &tenant)?;
+    Ok(Json(merge_flag_overrides(
+        &state.experimentation_flags,
+        &overrides,
+    )))
+}
+
+#[patch("/experimentation_config")]
+async fn update_experimentation_config(
+    state: Data<AppState>,
+    req: Json<ExperimentationFlagsPatch>,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<ExperimentationFlags>> {
+    validate_admin(&state, &user)?;
+    if req.flags().is_empty() {
+        return Err(bad_argument!(
+            "at least one experimentation flag must be set"
+        ));
+    }
+    let DbConnection(mut conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+
+    let rows = req
+        .flags()
+        .into_iter()
+        .map(|(flag_name, flag_value)| ExperimentationConfig {
+            tenant_id: tenant.to_string(),
+            flag_name: flag_name.to_string(),
+            flag_value,
+            last_modified_at: Utc::now(),
+            last_modified_by: user.get_recorded_email(),
+        })
+        .collect::<Vec<ExperimentationConfig>>();
+
+    let overrides = conn.transaction::<_, superposition::AppError, _>(|conn| {
+        diesel::insert_into(exp_config::experimentation_config)
+            .values(&rows)
+            .on_conflict((exp_config::tenant_id, exp_config::flag_name))
+            .do_update()
+            .set((
+                exp_config::flag_value.eq(excluded(exp_config::flag_value)),
+                exp_config::last_modified_at.eq(excluded(exp_config::last_modified_at)),
+                exp_config::last_modified_by.eq(excluded(exp_config::last_modified_by)),
+            ))
+            .execute(conn)?;
+        fetch_flag_overrides(conn, &tenant)
+    })?;
+    invalidate_cached_flags(&tenant);
+
+    Ok(Json(merge_flag_overrides(
+        &state.experimentation_flags,
+        &overrides,
+    )))
+}
+
+/// Lists the configured tenants that have served at least one request, with
+/// when they were first and last seen.
+#[get("/tenants")]
+async fn list_tenants(
+    state: Data<AppState>,
+    user: User,
+) -> superposition::Result<Json<Vec<TenantActivity>>> {
+    validate_admin(&state, &user)?;
+    let mut tenant_names = state.tenants.iter().cloned().collect::<Vec<String>>();
+    tenant_names.sort();
+
+    let mut activity = Vec::new();
+    for tenant_name in tenant_names {
+        let tenant = Tenant(tenant_name);
+        let DbConnection(mut conn) =
+            DbConnection::for_scope(&state, &tenant, AppScope::CAC)?;
+        let row = tenants::table
+            .filter(tenants::id.eq(tenant.as_str()))
+            .get_result::<TenantActivity>(&mut conn)
+            .optional()?;
+        activity.extend(row);
+    }
+    Ok(Json(activity))
+}
+
+/// Reports how much storage the contexts, overrides and default configs of
+/// each tenant take, to spot tenants whose configs have grown out of hand.
+#[get("/config/size_report")]
+async fn config_size_report(
+    state: Data<AppState>,
+    user: User,
+) -> superposition::Result<Json<BTreeMap<String, TenantSizeReport>>> {
+    validate_admin(&state, &user)?;
+
+    let mut report = BTreeMap::new();
+    for tenant_name in state.tenants.iter() {
+        let tenant = Tenant(tenant_name.clone());
+        let DbConnection(mut conn) =
+            DbConnection::for_scope(&state, &tenant, AppScope::CAC)?;
+        let (contexts_bytes, overrides_bytes): (Option<i64>, Option<i64>) =
+            contexts::table
+                .select((
+                    sum(pg_column_size(contexts::value)),
+                    sum(pg_column_size(contexts::override_)),
+                ))
+                .get_result(&mut conn)?;
+        let default_configs_bytes: Option<i64> = default_configs::table
+            .select(sum(pg_column_size(default_configs::value)
+                + pg_column_size(default_configs::schema)))
+            .get_result(&mut conn)?;
+
+        let contexts_bytes = contexts_bytes.unwrap_or_default();
+        let overrides_bytes = overrides_bytes.unwrap_or_default();
+        let default_configs_bytes = default_configs_bytes.unwrap_or_default();
+        report.insert(
+            tenant_name.clone(),
+            TenantSizeReport {
+                contexts_bytes,
+                overrides_bytes,
+                default_configs_bytes,
+                total_bytes: contexts_bytes + overrides_bytes + default_configs_bytes,
+            },
+        );
+    }
+    Ok(Json(report))
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// Bytes taken by the configuration of a tenant, as reported by
+/// `pg_column_size`, so compression of large values is accounted for.
+#[derive(Serialize)]
+pub struct TenantSizeReport {
+    /// conditions of the tenant's contexts
+    pub contexts_bytes: i64,
+    pub overrides_bytes: i64,
+    /// values and schemas of the tenant's default configs
+    pub default_configs_bytes: i64,
+    pub total_bytes: i64,
+}
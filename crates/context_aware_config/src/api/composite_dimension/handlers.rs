@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use crate::{
+    api::composite_dimension::types::CreateReq,
+    db::{
+        models::CompositeDimension,
+        schema::{composite_dimensions::dsl, dimensions},
+    },
+};
+use actix_web::{
+    delete, get, put,
+    web::{Json, Path},
+    HttpResponse, Scope,
+};
+use chrono::Utc;
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use service_utils::{
+    bad_argument, not_found, result as superposition, service::types::DbConnection,
+};
+use superposition_types::User;
+
+pub fn endpoints() -> Scope {
+    Scope::new("")
+        .service(create)
+        .service(list)
+        .service(get)
+        .service(delete_composite)
+}
+
+#[put("")]
+async fn create(
+    req: Json<CreateReq>,
+    user: User,
+    db_conn: DbConnection,
+) -> superposition::Result<HttpResponse> {
+    let DbConnection(mut conn) = db_conn;
+    let req = req.into_inner();
+
+    if req.priority == 0 {
+        return Err(bad_argument!("Priority should be greater than 0"));
+    }
+    let unique_components: HashSet<String> =
+        req.component_dimensions.iter().cloned().collect();
+    if unique_components.len() != req.component_dimensions.len() {
+        return Err(bad_argument!("component_dimensions should be unique"));
+    }
+    if unique_components.len() < 2 {
+        return Err(bad_argument!(
+            "A composite dimension should group at least 2 dimensions"
+        ));
+    }
+
+    let composite_dimension = CompositeDimension {
+        name: req.name,
+        component_dimensions: req.component_dimensions,
+        priority: i32::from(req.priority),
+        created_at: Utc::now(),
+        created_by: user.get_recorded_email(),
+    };
+
+    let upserted = conn.transaction::<_, superposition::AppError, _>(|conn| {
+        let is_dimension = diesel::select(diesel::dsl::exists(
+            dimensions::table.filter(dimensions::dimension.eq(&composite_dimension.name)),
+        ))
+        .get_result::<bool>(conn)?;
+        if is_dimension {
+            return Err(bad_argument!(
+                "{} is already a dimension",
+                composite_dimension.name
+            ));
+        }
+
+        let known_dimensions: Vec<String> = dimensions::table
+            .filter(
+                dimensions::dimension.eq_any(&composite_dimension.component_dimensions),
+            )
+            .select(dimensions::dimension)
+            .load(conn)?;
+        if let Some(unknown) = composite_dimension
+            .component_dimensions
+            .iter()
+            .find(|component| !known_dimensions.contains(component))
+        {
+            return Err(bad_argument!("No matching dimension ({}) found", unknown));
+        }
+
+        // a dimension can only be folded into one composite, otherwise grouping
+        // a context's dimensions would be ambiguous
+        let other_composites: Vec<CompositeDimension> = dsl::composite_dimensions
+            .filter(dsl::name.ne(&composite_dimension.name))
+            .load(conn)?;
+        for other in other_composites.iter() {
+            if let Some(shared) = other
+                .component_dimensions
+                .iter()
+                .find(|component| unique_components.contains(*component))
+            {
+                return Err(bad_argument!(
+                    "Dimension {} is already part of composite dimension {}",
+                    shared,
+                    other.name
+                ));
+            }
+        }
+
+        Ok(diesel::insert_into(dsl::composite_dimensions)
+            .values(&composite_dimension)
+            .on_conflict(dsl::name)
+            .do_update()
+            .set(&composite_dimension)
+            .get_result::<CompositeDimension>(conn)?)
+    })?;
+
+    Ok(HttpResponse::Created().json(upserted))
+}
+
+#[get("")]
+async fn list(
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Vec<CompositeDimension>>> {
+    let DbConnection(mut conn) = db_conn;
+    let result = dsl::composite_dimensions
+        .order((dsl::priority.asc(), dsl::name.asc()))
+        .load::<CompositeDimension>(&mut conn)?;
+    Ok(Json(result))
+}
+
+#[get("/{name}")]
+async fn get(
+    path: Path<String>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<CompositeDimension>> {
+    let DbConnection(mut conn) = db_conn;
+    let name = path.into_inner();
+    let result = dsl::composite_dimensions
+        .filter(dsl::name.eq(&name))
+        .get_result::<CompositeDimension>(&mut conn)
+        .map_err(|e| match e {
+            diesel::NotFound => {
+                not_found!("Composite dimension `{}` doesn't exists", name)
+            }
+            e => e.into(),
+        })?;
+    Ok(Json(result))
+}
+
+#[delete("/{name}")]
+async fn delete_composite(
+    path: Path<String>,
+    user: User,
+    db_conn: DbConnection,
+) -> superposition::Result<HttpResponse> {
+    let DbConnection(mut conn) = db_conn;
+    let name = path.into_inner();
+    let deleted = diesel::delete(dsl::composite_dimensions.filter(dsl::name.eq(&name)))
+        .execute(&mut conn)?;
+    if deleted == 0 {
+        return Err(not_found!("Composite dimension `{}` doesn't exists", name));
+    }
+    log::info!(
+        "composite dimension {name} deleted by {}",
+        user.get_recorded_email()
+    );
+    Ok(HttpResponse::NoContent().finish())
+}
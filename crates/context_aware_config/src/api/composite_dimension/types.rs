@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReq {
+    pub name: String,
+    pub component_dimensions: Vec<String>,
+    pub priority: u16,
+}
@@ -2,27 +2,48 @@ use std::collections::HashSet;
 use std::{collections::HashMap, str::FromStr};
 
 use super::helpers::{
-    filter_config_by_dimensions, filter_config_by_prefix, filter_context,
+    diff_default_configs, filter_config_by_dimensions, filter_config_by_prefix,
+    filter_context, get_context_ancestors, get_override_provenance,
 };
 
-use super::types::Config;
+use super::types::{
+    AncestorsQuery, ChangelogQuery, Config, ConfigDiffResponse, ConfigWithMetadata,
+    ContextSnapshot, DiffEntry, DiffQuery, PreviewQuery, PromoteReq, PromoteResponse,
+    PromotionAction, PromotionDiff,
+};
+use crate::api::context::helpers::hash;
+#[cfg(feature = "redis-cache")]
+use crate::config_cache::ConfigCache;
+use crate::db::models::{ConfigChangelog, DefaultConfig};
 use crate::db::schema::{
-    contexts::dsl as ctxt, default_configs::dsl as def_conf, event_log::dsl as event_log,
+    config_changelog::dsl as changelog, contexts::dsl as ctxt,
+    default_configs::dsl as def_conf, event_log::dsl as event_log,
+    functions::dsl as functions,
 };
 use actix_http::header::{HeaderName, HeaderValue};
-use actix_web::{get, web::Query, HttpRequest, HttpResponse, Scope};
+use actix_web::{
+    get, post,
+    web::{Data, Json, Query},
+    HttpRequest, HttpResponse, Scope,
+};
 use cac_client::{eval_cac, eval_cac_with_reasoning, MergeStrategy};
 use chrono::{DateTime, NaiveDateTime, TimeZone, Timelike, Utc};
 use diesel::{
     dsl::max,
     r2d2::{ConnectionManager, PooledConnection},
-    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+    Connection, ExpressionMethods, PgArrayExpressionMethods, PgConnection, QueryDsl,
+    RunQueryDsl,
 };
 use serde_json::{json, Map, Value};
-use service_utils::service::types::DbConnection;
+use service_utils::db::pgschema_manager::set_actor;
+use service_utils::helpers::validate_admin;
+#[cfg(feature = "redis-cache")]
+use service_utils::service::types::AppExecutionNamespace;
+use service_utils::service::types::{AppScope, AppState, DbConnection, Tenant};
 use service_utils::{bad_argument, db_error, unexpected_error};
 
 use service_utils::result as superposition;
+use superposition_types::User;
 use uuid::Uuid;
 
 pub fn endpoints() -> Scope {
@@ -30,6 +51,12 @@ pub fn endpoints() -> Scope {
         .service(get)
         .service(get_resolved_config)
         .service(get_filtered_config)
+        .service(get_config_ancestors)
+        .service(promote)
+        .service(get_diff)
+        .service(get_changelog)
+        .service(get_config_preview)
+        .service(get_keys)
 }
 
 pub fn add_audit_header(
@@ -98,25 +125,37 @@ fn is_not_modified(max_created_at: Option<NaiveDateTime>, req: &HttpRequest) ->
     max_created_at.is_some() && parsed_max <= last_modified
 }
 
-async fn generate_cac(
+pub(crate) async fn generate_cac(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
 ) -> superposition::Result<Config> {
     let contexts_vec = ctxt::contexts
-        .select((ctxt::id, ctxt::value, ctxt::override_id, ctxt::override_))
-        .order_by((ctxt::priority.asc(), ctxt::created_at.asc()))
-        .load::<(String, Value, String, Value)>(conn)
+        .select((
+            ctxt::id,
+            ctxt::value,
+            ctxt::override_id,
+            ctxt::override_,
+            ctxt::description,
+        ))
+        .order_by((
+            ctxt::priority.asc(),
+            ctxt::override_priority.asc(),
+            ctxt::created_at.asc(),
+        ))
+        .load::<(String, Value, String, Value, Option<String>)>(conn)
         .map_err(|err| {
             log::error!("failed to fetch contexts with error: {}", err);
             db_error!(err)
         })?;
 
-    let (contexts, overrides) = contexts_vec.into_iter().fold(
+    let (contexts, mut overrides) = contexts_vec.into_iter().fold(
         (Vec::new(), Map::new()),
-        |(mut ctxts, mut overrides), (id, condition, override_id, override_)| {
+        |(mut ctxts, mut overrides),
+         (id, condition, override_id, override_, description)| {
             let ctxt = super::types::Context {
                 id,
                 condition,
                 override_with_keys: [override_id.to_owned()],
+                description,
             };
             ctxts.push(ctxt);
             overrides.insert(override_id, override_);
@@ -125,13 +164,18 @@ async fn generate_cac(
     );
 
     let default_config_vec = def_conf::default_configs
-        .select((def_conf::key, def_conf::value))
-        .load::<(String, Value)>(conn)
+        .select((def_conf::key, def_conf::value, def_conf::expires_at))
+        .load::<(String, Value, Option<DateTime<Utc>>)>(conn)
         .map_err(|err| {
             log::error!("failed to fetch default_configs with error: {}", err);
             db_error!(err)
         })?;
 
+    let now = Utc::now();
+    let (default_config_vec, expired_configs): (Vec<_>, Vec<_>) = default_config_vec
+        .into_iter()
+        .partition(|(_, _, expires_at)| expires_at.map_or(true, |at| at > now));
+
     let default_configs =
         default_config_vec
             .into_iter()
@@ -140,6 +184,17 @@ async fn generate_cac(
                 acc
             });
 
+    // overrides of expired keys are dropped along with the keys themselves
+    if !expired_configs.is_empty() {
+        overrides.values_mut().for_each(|override_| {
+            if let Some(override_map) = override_.as_object_mut() {
+                expired_configs.iter().for_each(|(key, _, _)| {
+                    override_map.remove(key);
+                });
+            }
+        });
+    }
+
     Ok(Config {
         contexts,
         overrides,
@@ -147,10 +202,30 @@ async fn generate_cac(
     })
 }
 
+#[cfg(feature = "redis-cache")]
+async fn get_cached_cac(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    cache: Option<&ConfigCache>,
+    namespace: &str,
+) -> superposition::Result<Config> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return generate_cac(conn).await,
+    };
+    if let Some(config) = cache.get(namespace).await {
+        return Ok(config);
+    }
+    let config = generate_cac(conn).await?;
+    cache.set(namespace, &config).await;
+    Ok(config)
+}
+
 #[get("")]
 async fn get(
     req: HttpRequest,
     db_conn: DbConnection,
+    #[cfg(feature = "redis-cache")] cache: Option<Data<ConfigCache>>,
+    #[cfg(feature = "redis-cache")] namespace: AppExecutionNamespace,
 ) -> superposition::Result<HttpResponse> {
     let DbConnection(mut conn) = db_conn;
 
@@ -182,6 +257,11 @@ async fn get(
         );
     }
 
+    #[cfg(feature = "redis-cache")]
+    let mut config =
+        get_cached_cac(&mut conn, cache.as_ref().map(|c| c.get_ref()), &namespace.0)
+            .await?;
+    #[cfg(not(feature = "redis-cache"))]
     let mut config = generate_cac(&mut conn).await?;
     if let Some(prefix) = query_params_map.get("prefix") {
         let prefix_list: HashSet<&str> = prefix
@@ -196,12 +276,23 @@ async fn get(
     }
 
     query_params_map.remove("prefix");
+    let include_metadata = query_params_map
+        .remove("include_metadata")
+        .map_or(false, |value| value == json!("true"));
 
     if !query_params_map.is_empty() {
         config = filter_config_by_dimensions(&config, &query_params_map)?
     }
 
-    let resp = HttpResponse::Ok().json(config);
+    let resp = if include_metadata {
+        let override_metadata = get_override_provenance(&config);
+        HttpResponse::Ok().json(ConfigWithMetadata {
+            config,
+            override_metadata,
+        })
+    } else {
+        HttpResponse::Ok().json(config)
+    };
     let audit_resp = add_audit_header(&mut conn, resp)?;
 
     add_last_modified_header(max_created_at, audit_resp)
@@ -341,3 +432,408 @@ async fn get_filtered_config(
 
     add_audit_header(&mut conn, HttpResponse::Ok().json(filtered_config))
 }
+
+#[get("/ancestors")]
+async fn get_config_ancestors(
+    query: Query<AncestorsQuery>,
+    db_conn: DbConnection,
+) -> superposition::Result<HttpResponse> {
+    let DbConnection(mut conn) = db_conn;
+    let query_data =
+        serde_json::from_str::<Map<String, Value>>(&query.context).map_err(|err| {
+            log::error!("failed to parse context query param with err: {}", err);
+            bad_argument!("context should be a JSON object of dimension values")
+        })?;
+
+    let config = generate_cac(&mut conn).await?;
+    let ancestors = get_context_ancestors(&config, &query_data)?;
+
+    add_audit_header(&mut conn, HttpResponse::Ok().json(ancestors))
+}
+
+#[post("/promote")]
+async fn promote(
+    state: Data<AppState>,
+    req: Json<PromoteReq>,
+    user: User,
+    #[cfg(feature = "redis-cache")] cache: Option<Data<ConfigCache>>,
+) -> superposition::Result<Json<PromoteResponse>> {
+    validate_admin(&state, &user)?;
+    let req = req.into_inner();
+
+    for tenant in [&req.source_tenant, &req.target_tenant] {
+        if state.enable_tenant_and_scope && !state.tenants.contains(tenant) {
+            return Err(bad_argument!("Unknown tenant {}", tenant));
+        }
+    }
+    if req.source_tenant == req.target_tenant {
+        return Err(bad_argument!(
+            "source and target tenants should be different"
+        ));
+    }
+
+    let DbConnection(mut source_conn) = DbConnection::for_scope(
+        &state,
+        &Tenant(req.source_tenant.clone()),
+        AppScope::CAC,
+    )?;
+    let DbConnection(mut target_conn) = DbConnection::for_scope(
+        &state,
+        &Tenant(req.target_tenant.clone()),
+        AppScope::CAC,
+    )?;
+
+    let mut source_query = def_conf::default_configs.into_boxed();
+    if let Some(keys) = &req.keys {
+        source_query = source_query.filter(def_conf::key.eq_any(keys));
+    }
+    let source_configs = source_query.get_results::<DefaultConfig>(&mut source_conn)?;
+
+    if let Some(keys) = &req.keys {
+        if let Some(missing) = keys
+            .iter()
+            .find(|key| !source_configs.iter().any(|config| &config.key == *key))
+        {
+            return Err(bad_argument!(
+                "{} not found in tenant {}",
+                missing,
+                req.source_tenant
+            ));
+        }
+    }
+
+    let source_keys: Vec<String> = source_configs
+        .iter()
+        .map(|config| config.key.clone())
+        .collect();
+    let target_configs: HashMap<String, DefaultConfig> = def_conf::default_configs
+        .filter(def_conf::key.eq_any(source_keys))
+        .get_results::<DefaultConfig>(&mut target_conn)?
+        .into_iter()
+        .map(|config| (config.key.clone(), config))
+        .collect();
+
+    let target_functions: HashSet<String> = functions::functions
+        .select(functions::function_name)
+        .get_results::<String>(&mut target_conn)?
+        .into_iter()
+        .collect();
+
+    let mut diff = Vec::new();
+    let mut promoted = Vec::new();
+    for config in source_configs {
+        if let Some(f_name) = &config.function_name {
+            if !target_functions.contains(f_name) {
+                return Err(bad_argument!(
+                    "Function {} used by {} doesn't exist in tenant {}",
+                    f_name,
+                    config.key,
+                    req.target_tenant
+                ));
+            }
+        }
+
+        let existing = target_configs.get(&config.key);
+        let (action, old_value, schema_changed) = match existing {
+            Some(old) if old.value == config.value && old.schema == config.schema => {
+                continue;
+            }
+            Some(old) => (
+                PromotionAction::Update,
+                Some(old.value.clone()),
+                old.schema != config.schema,
+            ),
+            None => (PromotionAction::Create, None, true),
+        };
+
+        diff.push(PromotionDiff {
+            key: config.key.clone(),
+            action,
+            old_value,
+            new_value: config.value.clone(),
+            schema_changed,
+        });
+        promoted.push(DefaultConfig {
+            created_by: user.get_recorded_email(),
+            created_at: Utc::now(),
+            ..config
+        });
+    }
+
+    let mut stored = Vec::new();
+    if !req.dry_run && !promoted.is_empty() {
+        stored = target_conn.transaction::<_, superposition::AppError, _>(|conn| {
+            // the connection is checked out for another tenant, without the user
+            set_actor(conn, &user.get_recorded_email())?;
+            promoted
+                .iter()
+                .map(|config| {
+                    Ok(diesel::insert_into(def_conf::default_configs)
+                        .values(config)
+                        .on_conflict(def_conf::key)
+                        .do_update()
+                        .set(config)
+                        .get_result::<DefaultConfig>(conn)?)
+                })
+                .collect::<superposition::Result<Vec<DefaultConfig>>>()
+        })?;
+        log::info!(
+            "{} promoted default configs {:?} from {} to {}",
+            user.get_recorded_email(),
+            promoted
+                .iter()
+                .map(|config| &config.key)
+                .collect::<Vec<_>>(),
+            req.source_tenant,
+            req.target_tenant
+        );
+        #[cfg(feature = "redis-cache")]
+        if let Some(cache) = cache {
+            let AppExecutionNamespace(namespace) = AppExecutionNamespace::for_scope(
+                &state,
+                &Tenant(req.target_tenant.clone()),
+                AppScope::CAC,
+            );
+            cache.invalidate(&namespace).await;
+        }
+    }
+
+    Ok(Json(PromoteResponse {
+        dry_run: req.dry_run,
+        diff,
+        promoted: stored,
+    }))
+}
+
+#[get("/diff")]
+async fn get_diff(
+    state: Data<AppState>,
+    tenant: Tenant,
+    query: Query<DiffQuery>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<ConfigDiffResponse>> {
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+    let Tenant(tenant) = tenant;
+    let baseline_tenant = query.into_inner().baseline_tenant;
+
+    if state.enable_tenant_and_scope && !state.tenants.contains(&baseline_tenant) {
+        return Err(bad_argument!("Unknown tenant {}", baseline_tenant));
+    }
+    if baseline_tenant == tenant {
+        return Err(bad_argument!(
+            "baseline_tenant should be different from the current tenant"
+        ));
+    }
+
+    let DbConnection(mut baseline_conn) =
+        DbConnection::for_scope(&state, &Tenant(baseline_tenant.clone()), AppScope::CAC)?;
+
+    let load = |conn: &mut PooledConnection<ConnectionManager<PgConnection>>| {
+        def_conf::default_configs
+            .select((
+                def_conf::key,
+                def_conf::value,
+                def_conf::schema,
+                def_conf::schema_draft,
+            ))
+            .get_results::<(String, Value, Value, String)>(conn)
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|(key, value, schema, schema_draft)| DiffEntry {
+                        key,
+                        value,
+                        schema,
+                        schema_draft,
+                    })
+                    .collect::<Vec<_>>()
+            })
+    };
+    let baseline_configs = load(&mut baseline_conn)?;
+    let configs = load(&mut conn)?;
+    let (added, removed, changed) = diff_default_configs(baseline_configs, configs);
+
+    Ok(Json(ConfigDiffResponse {
+        tenant,
+        baseline_tenant,
+        added,
+        removed,
+        changed,
+    }))
+}
+
+#[get("/changelog")]
+async fn get_changelog(
+    filters: Query<ChangelogQuery>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Vec<ConfigChangelog>>> {
+    let DbConnection(mut conn) = db_conn;
+    let filters = filters.into_inner();
+
+    let mut query = changelog::config_changelog.into_boxed();
+    if let Some(from) = filters.from {
+        query = query.filter(changelog::timestamp.ge(from));
+    }
+    if let Some(to) = filters.to {
+        query = query.filter(changelog::timestamp.le(to));
+    }
+    if let Some(actor) = filters.actor {
+        query = query.filter(changelog::actor.eq(actor));
+    }
+    if let Some(key) = filters.key {
+        query = query.filter(changelog::keys.contains(vec![key]));
+    }
+
+    let events = query
+        .order(changelog::timestamp.asc())
+        .load::<ConfigChangelog>(&mut conn)?;
+    Ok(Json(events))
+}
+
+/// Rebuilds the config as it was at `as_of` by rewinding the current contexts
+/// and default configs through every changelog entry recorded after it. Context
+/// entries carry the whole row, so conditions, priorities and moves are
+/// rewound along with overrides.
+#[get("/preview")]
+async fn get_config_preview(
+    query: Query<PreviewQuery>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Config>> {
+    let DbConnection(mut conn) = db_conn;
+    let as_of = query.into_inner().as_of;
+    if as_of > Utc::now() {
+        return Err(bad_argument!("as_of cannot be in the future"));
+    }
+
+    let mut contexts = ctxt::contexts
+        .select((
+            ctxt::id,
+            ctxt::value,
+            ctxt::override_,
+            ctxt::priority,
+            ctxt::override_priority,
+            ctxt::created_at,
+            ctxt::description,
+        ))
+        .load::<(
+            String,
+            Value,
+            Value,
+            i32,
+            i32,
+            DateTime<Utc>,
+            Option<String>,
+        )>(&mut conn)?
+        .into_iter()
+        .map(
+            |(
+                id,
+                value,
+                override_,
+                priority,
+                override_priority,
+                created_at,
+                description,
+            )| {
+                let snapshot = ContextSnapshot {
+                    id: id.to_owned(),
+                    value,
+                    override_,
+                    priority,
+                    override_priority,
+                    created_at,
+                    description,
+                };
+                (id, snapshot)
+            },
+        )
+        .collect::<HashMap<String, ContextSnapshot>>();
+
+    let mut default_configs = def_conf::default_configs
+        .select((def_conf::key, def_conf::value))
+        .load::<(String, Value)>(&mut conn)?
+        .into_iter()
+        .collect::<Map<String, Value>>();
+
+    let newer_changes = changelog::config_changelog
+        .filter(changelog::timestamp.gt(as_of))
+        .order(changelog::timestamp.desc())
+        .load::<ConfigChangelog>(&mut conn)?;
+
+    for change in newer_changes {
+        match change.table_name.as_str() {
+            "default_configs" => match change.old_value {
+                Some(old_value) => {
+                    default_configs.insert(change.entity_id, old_value);
+                }
+                None => {
+                    default_configs.remove(&change.entity_id);
+                }
+            },
+            "contexts" => {
+                // the new row is taken out by its own id, a move gave it a new one
+                let new_id = change
+                    .new_row
+                    .as_ref()
+                    .and_then(|row| row.get("id"))
+                    .and_then(Value::as_str);
+                if let Some(new_id) = new_id {
+                    contexts.remove(new_id);
+                }
+                if let Some(old_row) = change.old_row {
+                    match serde_json::from_value::<ContextSnapshot>(old_row) {
+                        Ok(snapshot) => {
+                            contexts.insert(snapshot.id.to_owned(), snapshot);
+                        }
+                        Err(e) => log::warn!(
+                            "context {} could not be restored for config preview: {e}",
+                            change.entity_id
+                        ),
+                    }
+                }
+            }
+            table => log::warn!("unexpected table {table} in config changelog"),
+        }
+    }
+
+    let mut contexts = contexts.into_values().collect::<Vec<ContextSnapshot>>();
+    contexts.sort_by(|a, b| {
+        (a.priority, a.override_priority, a.created_at).cmp(&(
+            b.priority,
+            b.override_priority,
+            b.created_at,
+        ))
+    });
+
+    let (contexts, overrides) = contexts.into_iter().fold(
+        (Vec::new(), Map::new()),
+        |(mut ctxts, mut overrides), snapshot| {
+            let override_id = hash(&snapshot.override_);
+            ctxts.push(super::types::Context {
+                id: snapshot.id,
+                condition: snapshot.value,
+                override_with_keys: [override_id.to_owned()],
+                description: snapshot.description,
+            });
+            overrides.insert(override_id, snapshot.override_);
+            (ctxts, overrides)
+        },
+    );
+
+    Ok(Json(Config {
+        contexts,
+        overrides,
+        default_configs,
+    }))
+}
+
+#[get("/keys")]
+async fn get_keys(db_conn: DbConnection) -> superposition::Result<Json<Vec<String>>> {
+    let DbConnection(mut conn) = db_conn;
+    let keys = def_conf::default_configs
+        .select(def_conf::key)
+        .order(def_conf::key.asc())
+        .load::<String>(&mut conn)?;
+    Ok(Json(keys))
+}
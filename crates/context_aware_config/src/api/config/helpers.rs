@@ -1,8 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use super::types::{Config, Context};
+use super::types::{
+    ChangedEntry, Config, Context, ContextAncestor, DiffEntry, OverrideProvenance,
+};
 
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
 use service_utils::{
     helpers::extract_dimensions, result as superposition, unexpected_error,
 };
@@ -108,3 +110,173 @@ pub fn filter_config_by_dimensions(
 
     Ok(filtered_config)
 }
+
+pub fn get_context_ancestors(
+    config: &Config,
+    query_data: &Map<String, Value>,
+) -> superposition::Result<Vec<ContextAncestor>> {
+    let query_data = json!(query_data);
+    let mut ancestors: Vec<ContextAncestor> = Vec::new();
+    for context in config.contexts.iter() {
+        if let Ok(Value::Bool(true)) = jsonlogic::apply(&context.condition, &query_data) {
+            let override_key = &context.override_with_keys[0];
+            let keys_overridden = config
+                .overrides
+                .get(override_key)
+                .and_then(Value::as_object)
+                .ok_or_else(|| {
+                    log::error!("failed to decode overrides for {override_key}");
+                    unexpected_error!("failed to decode overrides.")
+                })?
+                .keys()
+                .cloned()
+                .collect();
+            ancestors.push(ContextAncestor {
+                context_id: context.id.clone(),
+                condition: context.condition.clone(),
+                keys_overridden,
+            });
+        }
+    }
+    Ok(ancestors)
+}
+
+pub fn get_override_provenance(
+    config: &Config,
+) -> HashMap<String, Vec<OverrideProvenance>> {
+    let mut provenance: HashMap<String, Vec<OverrideProvenance>> = HashMap::new();
+    for (selection_order, context) in config.contexts.iter().enumerate() {
+        for override_key in context.override_with_keys.iter() {
+            provenance.entry(override_key.clone()).or_default().push(
+                OverrideProvenance {
+                    context_id: context.id.clone(),
+                    condition: context.condition.clone(),
+                    selection_order,
+                },
+            );
+        }
+    }
+    provenance
+}
+
+/// Compares the default configs of a tenant against those of a baseline
+/// tenant, returning the added, removed and changed keys sorted by key.  A key
+/// has changed when its value, schema or schema draft differ.
+pub fn diff_default_configs(
+    baseline_configs: Vec<DiffEntry>,
+    configs: Vec<DiffEntry>,
+) -> (Vec<DiffEntry>, Vec<DiffEntry>, Vec<ChangedEntry>) {
+    let mut baseline_configs = baseline_configs
+        .into_iter()
+        .map(|entry| (entry.key.clone(), entry))
+        .collect::<BTreeMap<_, _>>();
+    let configs = configs
+        .into_iter()
+        .map(|entry| (entry.key.clone(), entry))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (key, entry) in configs {
+        let Some(baseline) = baseline_configs.remove(&key) else {
+            added.push(entry);
+            continue;
+        };
+        let schema_changed = baseline.schema != entry.schema
+            || baseline.schema_draft != entry.schema_draft;
+        if baseline.value == entry.value && !schema_changed {
+            continue;
+        }
+        let (baseline_schema, schema, baseline_schema_draft, schema_draft) =
+            if schema_changed {
+                (
+                    Some(baseline.schema),
+                    Some(entry.schema),
+                    Some(baseline.schema_draft),
+                    Some(entry.schema_draft),
+                )
+            } else {
+                (None, None, None, None)
+            };
+        changed.push(ChangedEntry {
+            key,
+            baseline_value: baseline.value,
+            value: entry.value,
+            baseline_schema,
+            schema,
+            baseline_schema_draft,
+            schema_draft,
+        });
+    }
+    let removed = baseline_configs.into_values().collect();
+
+    (added, removed, changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, value: Value, schema_draft: &str) -> DiffEntry {
+        DiffEntry {
+            key: key.to_string(),
+            value,
+            schema: json!({"type": "string"}),
+            schema_draft: schema_draft.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_default_configs() {
+        let baseline = vec![
+            entry("key1", json!("value1"), "Draft7"),
+            entry("key2", json!("value2"), "Draft7"),
+            entry("key3", json!("value3"), "Draft7"),
+        ];
+        let configs = vec![
+            entry("key4", json!("value4"), "Draft7"),
+            entry("key2", json!("value2"), "Draft7"),
+            entry("key1", json!("value5"), "Draft7"),
+        ];
+
+        let (added, removed, changed) = diff_default_configs(baseline, configs);
+
+        assert_eq!(added, vec![entry("key4", json!("value4"), "Draft7")]);
+        assert_eq!(removed, vec![entry("key3", json!("value3"), "Draft7")]);
+        assert_eq!(
+            changed,
+            vec![ChangedEntry {
+                key: "key1".to_string(),
+                baseline_value: json!("value1"),
+                value: json!("value5"),
+                baseline_schema: None,
+                schema: None,
+                baseline_schema_draft: None,
+                schema_draft: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_default_configs_with_differing_schema_draft() {
+        let baseline = vec![entry("key1", json!("value1"), "Draft7")];
+        let configs = vec![entry("key1", json!("value1"), "Draft202012")];
+
+        let (added, removed, changed) = diff_default_configs(baseline, configs);
+
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+        assert_eq!(
+            changed,
+            vec![ChangedEntry {
+                key: "key1".to_string(),
+                baseline_value: json!("value1"),
+                value: json!("value1"),
+                baseline_schema: Some(json!({"type": "string"})),
+                schema: Some(json!({"type": "string"})),
+                baseline_schema_draft: Some("Draft7".to_string()),
+                schema_draft: Some("Draft202012".to_string()),
+            }]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 mod handlers;
 mod types;
 pub use handlers::endpoints;
+pub(crate) use handlers::generate_cac;
+pub use types::Config;
 mod helpers;
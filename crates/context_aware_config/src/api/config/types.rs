@@ -1,16 +1,152 @@
-use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::db::models::DefaultConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct Config {
     pub contexts: Vec<Context>,
     pub overrides: Map<String, Value>,
     pub default_configs: Map<String, Value>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize)]
+pub struct OverrideProvenance {
+    pub context_id: String,
+    pub condition: Value,
+    /// position of the context in the order overrides are applied, later wins
+    pub selection_order: usize,
+}
+
+#[derive(Serialize)]
+pub struct ConfigWithMetadata {
+    #[serde(flatten)]
+    pub config: Config,
+    pub override_metadata: HashMap<String, Vec<OverrideProvenance>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Context {
     pub id: String,
     pub condition: Value,
     pub override_with_keys: [String; 1],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AncestorsQuery {
+    pub context: String,
+}
+
+#[derive(Serialize)]
+pub struct ContextAncestor {
+    pub context_id: String,
+    pub condition: Value,
+    pub keys_overridden: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PromoteReq {
+    pub source_tenant: String,
+    pub target_tenant: String,
+    pub keys: Option<Vec<String>>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromotionAction {
+    Create,
+    Update,
+}
+
+#[derive(Serialize)]
+pub struct PromotionDiff {
+    pub key: String,
+    pub action: PromotionAction,
+    pub old_value: Option<Value>,
+    pub new_value: Value,
+    pub schema_changed: bool,
+}
+
+#[derive(Serialize)]
+pub struct PromoteResponse {
+    pub dry_run: bool,
+    pub diff: Vec<PromotionDiff>,
+    /// the keys as stored in the target tenant, empty on a dry run
+    pub promoted: Vec<DefaultConfig>,
+}
+
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    pub baseline_tenant: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct DiffEntry {
+    pub key: String,
+    pub value: Value,
+    pub schema: Value,
+    pub schema_draft: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ChangedEntry {
+    pub key: String,
+    pub baseline_value: Value,
+    pub value: Value,
+    /// schema and draft are only set when either of them differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_schema: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_schema_draft: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_draft: Option<String>,
+}
+
+/// Default configs of the requested tenant compared against `baseline_tenant`.
+#[derive(Serialize)]
+pub struct ConfigDiffResponse {
+    pub tenant: String,
+    pub baseline_tenant: String,
+    /// keys missing from the baseline tenant
+    pub added: Vec<DiffEntry>,
+    /// keys only present in the baseline tenant
+    pub removed: Vec<DiffEntry>,
+    pub changed: Vec<ChangedEntry>,
+}
+
+#[derive(Deserialize)]
+pub struct ChangelogQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub actor: Option<String>,
+    pub key: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PreviewQuery {
+    pub as_of: DateTime<Utc>,
+}
+
+/// A contexts row as recorded by the config changelog, used to bring back
+/// contexts as they were before the preview timestamp.
+#[derive(Deserialize)]
+pub struct ContextSnapshot {
+    pub id: String,
+    pub value: Value,
+    #[serde(rename = "override")]
+    pub override_: Value,
+    pub priority: i32,
+    #[serde(default)]
+    pub override_priority: i32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
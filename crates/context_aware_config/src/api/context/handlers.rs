@@ -2,14 +2,19 @@ extern crate base64;
 use std::str;
 
 use crate::helpers::{
-    calculate_context_priority, json_to_sorted_string, validate_context_jsonschema,
+    parse_duration, recompute_context_priorities, validate_context_jsonschema,
+    validate_nullability,
 };
 use crate::{
     api::{
         context::types::{
-            ContextAction, ContextBulkResponse, DimensionCondition, MoveReq,
-            PaginationParams, PriorityRecomputeResponse, PutReq, PutResp,
+            BulkCreateItem, ConditionUpdateReq, ContextAction, ContextBulkResponse,
+            ContextExperimentsResponse, ContextFilters, ContextListResponse,
+            ContextTestReq, ContextTestResponse, DimensionCondition, ListFilters,
+            MoveReq, PatchReq, PriorityRecomputeResponse, PutReq, PutResp,
+            StaleContextsQuery, StaleContextsResponse,
         },
+        default_config::JsonSchemaDraft,
         dimension::get_all_dimension_schema_map,
     },
     db::{
@@ -21,28 +26,38 @@ use crate::{
     },
 };
 use actix_web::{
-    delete, get, put,
-    web::{Json, Path, Query},
-    HttpResponse, Responder, Scope,
+    delete, get, patch, post, put,
+    web::{Data, Json, Path, Query},
+    HttpRequest, HttpResponse, Responder, Scope,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::{
     delete,
+    dsl::sql,
+    pg::Pg,
     r2d2::{ConnectionManager, PooledConnection},
     result::{DatabaseErrorKind::*, Error::DatabaseError},
+    sql_types::{Bool, Text},
     upsert::excluded,
-    Connection, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+    BoxableExpression, Connection, ExpressionMethods, OptionalExtension, PgConnection,
+    QueryDsl, RunQueryDsl,
 };
-use jsonschema::{Draft, JSONSchema, ValidationError};
+use experimentation_platform::api::experiments::helpers::are_overlapping_contexts;
+use jsonschema::{JSONSchema, ValidationError};
 use serde_json::{from_value, json, Map, Value};
-use service_utils::helpers::validation_err_to_str;
-use service_utils::service::types::DbConnection;
+use service_utils::helpers::{
+    validate_admin, validation_err_to_str, CompositeDimensions,
+};
+use service_utils::service::types::{AppState, DbConnection, Tenant};
 use service_utils::{db_error, not_found, unexpected_error, validation_error};
 use std::collections::HashMap;
-use superposition_types::{SuperpositionUser, User};
+use superposition_types::User;
 
 use super::helpers::{
-    validate_condition_with_functions, validate_override_with_functions,
+    apply_override_pointer, ensure_not_used_by_experiments,
+    get_active_experiment_context_ids, get_overlapping_experiment_ids, hash,
+    resolve_pointer_overrides, validate_condition_with_functions,
+    validate_override_with_functions,
 };
 
 use service_utils::{bad_argument, result as superposition};
@@ -51,10 +66,17 @@ pub fn endpoints() -> Scope {
     Scope::new("")
         .service(put_handler)
         .service(move_handler)
+        .service(patch_handler)
+        .service(update_condition_handler)
+        .service(delete_stale_contexts)
         .service(delete_context)
         .service(bulk_operations)
+        .service(bulk_create)
         .service(list_contexts)
+        .service(get_contexts)
         .service(get_context)
+        .service(get_context_experiments)
+        .service(test_context)
         .service(priority_recompute)
 }
 
@@ -139,26 +161,29 @@ fn validate_override_with_default_configs(
     override_: &Map<String, Value>,
 ) -> superposition::Result<()> {
     let keys_array: Vec<&String> = override_.keys().collect();
-    let res: Vec<(String, Value)> = dsl::default_configs
+    let res: Vec<(String, Value, String, bool)> = dsl::default_configs
         .filter(dsl::key.eq_any(keys_array))
-        .select((dsl::key, dsl::schema))
-        .get_results::<(String, Value)>(conn)?;
+        .select((dsl::key, dsl::schema, dsl::schema_draft, dsl::nullable))
+        .get_results::<(String, Value, String, bool)>(conn)?;
 
-    let map = Map::from_iter(res);
+    let map: HashMap<String, (Value, String, bool)> = res
+        .into_iter()
+        .map(|(key, schema, draft, nullable)| (key, (schema, draft, nullable)))
+        .collect();
 
     for (key, value) in override_.iter() {
-        let schema = map
+        let (schema, draft, nullable) = map
             .get(key)
             // .map(|resp| resp)
             .ok_or(bad_argument!("failed to get schema for config key {}", key))?;
+        validate_nullability(key, value, *nullable)?;
         let instance = value;
-        let schema_compile_result = JSONSchema::options()
-            .with_draft(Draft::Draft7)
-            .compile(schema);
+        let draft = JsonSchemaDraft::from_stored(draft);
+        let schema_compile_result = draft.compile(schema);
         let jschema = match schema_compile_result {
             Ok(jschema) => jschema,
             Err(e) => {
-                log::info!("Failed to compile as a Draft-7 JSON schema: {e}");
+                log::info!("Failed to compile as a {draft} JSON schema: {e}");
                 return Err(bad_argument!(
                     "failed to compile ({}) config key schema",
                     key
@@ -186,10 +211,12 @@ fn create_ctx_from_put_req(
     user: &User,
 ) -> superposition::Result<Context> {
     let ctx_condition = Value::Object(req.context.to_owned());
-    let ctx_override: Value = req.r#override.to_owned().into();
-    validate_override_with_default_configs(conn, &req.r#override)?;
+    let req_override = apply_override_pointer(&req.r#override, req.pointer.as_deref())?;
+    let resolved_override = resolve_pointer_overrides(conn, &req_override)?;
+    let ctx_override: Value = req_override.into();
+    validate_override_with_default_configs(conn, &resolved_override)?;
     validate_condition_with_functions(conn, &ctx_condition)?;
-    validate_override_with_functions(conn, &req.r#override)?;
+    validate_override_with_functions(conn, &resolved_override)?;
 
     let dimension_schema_map = get_all_dimension_schema_map(conn)?;
 
@@ -212,29 +239,29 @@ fn create_ctx_from_put_req(
         override_id: override_id.to_owned(),
         override_: ctx_override.to_owned(),
         created_at: Utc::now(),
-        created_by: user.get_email(),
+        created_by: user.get_recorded_email(),
+        last_modified: Utc::now(),
+        override_priority: req.override_priority.unwrap_or_default(),
+        description: req.description.clone(),
     })
 }
 
-fn hash(val: &Value) -> String {
-    let sorted_str: String = json_to_sorted_string(val);
-    blake3::hash(sorted_str.as_bytes()).to_string()
-}
-
 fn update_override_of_existing_ctx(
     conn: &mut PgConnection,
     ctx: Context,
+    override_priority: Option<i32>,
 ) -> superposition::Result<PutResp> {
     use contexts::dsl;
-    let mut new_override: Value = dsl::contexts
+    let (mut new_override, existing_override_priority): (Value, i32) = dsl::contexts
         .filter(dsl::id.eq(&ctx.id))
-        .select(dsl::override_)
+        .select((dsl::override_, dsl::override_priority))
         .first(conn)?;
     cac_client::merge(&mut new_override, &ctx.override_);
     let new_override_id = hash(&new_override);
     let new_ctx = Context {
         override_: new_override,
         override_id: new_override_id,
+        override_priority: override_priority.unwrap_or(existing_override_priority),
         ..ctx
     };
     diesel::update(dsl::contexts)
@@ -249,6 +276,7 @@ fn get_put_resp(ctx: Context) -> PutResp {
         context_id: ctx.id,
         override_id: ctx.override_id,
         priority: ctx.priority,
+        override_priority: ctx.override_priority,
     }
 }
 
@@ -259,20 +287,23 @@ fn put(
     user: &User,
 ) -> superposition::Result<PutResp> {
     use contexts::dsl::contexts;
+    let override_priority = req.override_priority;
     let new_ctx = create_ctx_from_put_req(req, conn, user)?;
 
     if already_under_txn {
         diesel::sql_query("SAVEPOINT put_ctx_savepoint").execute(conn)?;
     }
-    let insert = diesel::insert_into(contexts).values(&new_ctx).execute(conn);
+    let insert = diesel::insert_into(contexts)
+        .values(&new_ctx)
+        .get_result::<Context>(conn);
 
     match insert {
-        Ok(_) => Ok(get_put_resp(new_ctx)),
+        Ok(inserted_ctx) => Ok(get_put_resp(inserted_ctx)),
         Err(DatabaseError(UniqueViolation, _)) => {
             if already_under_txn {
                 diesel::sql_query("ROLLBACK TO put_ctx_savepoint").execute(conn)?;
             }
-            update_override_of_existing_ctx(conn, new_ctx)
+            update_override_of_existing_ctx(conn, new_ctx, override_priority)
         }
         Err(e) => {
             log::error!("failed to update context with db error: {:?}", e);
@@ -281,18 +312,79 @@ fn put(
     }
 }
 
+fn get_if_unmodified_since(
+    req: &HttpRequest,
+) -> superposition::Result<Option<DateTime<Utc>>> {
+    req.headers()
+        .get("If-Unmodified-Since")
+        .map(|header_val| {
+            header_val
+                .to_str()
+                .ok()
+                .and_then(|header_str| DateTime::parse_from_rfc2822(header_str).ok())
+                .map(|datetime| datetime.with_timezone(&Utc))
+                .ok_or(bad_argument!("Invalid If-Unmodified-Since header"))
+        })
+        .transpose()
+}
+
+/// Runs `update` against the context `ctx_id`, unless the context has been
+/// modified after `unmodified_since`, in which case a 412 carrying the current
+/// `last_modified` is returned. The check and the update share a transaction
+/// with the context row locked, so concurrent writers cannot slip in between.
+fn update_if_unmodified_since<F>(
+    conn: &mut DBConnection,
+    ctx_id: &str,
+    unmodified_since: Option<DateTime<Utc>>,
+    update: F,
+) -> superposition::Result<HttpResponse>
+where
+    F: FnOnce(&mut DBConnection, bool) -> superposition::Result<PutResp>,
+{
+    use contexts::dsl;
+    let since = match unmodified_since {
+        Some(since) => since,
+        None => return update(conn, false).map(|resp| HttpResponse::Ok().json(resp)),
+    };
+    conn.transaction::<_, superposition::AppError, _>(|transaction_conn| {
+        let last_modified: Option<DateTime<Utc>> = dsl::contexts
+            .filter(dsl::id.eq(ctx_id))
+            .select(dsl::last_modified)
+            .for_update()
+            .first(transaction_conn)
+            .optional()?;
+        match last_modified {
+            // HTTP dates only carry second precision
+            Some(last_modified) if last_modified.timestamp() > since.timestamp() => {
+                Ok(HttpResponse::PreconditionFailed().json(json!({
+                    "message": format!("context {ctx_id} has been modified since {since}"),
+                    "last_modified": last_modified,
+                })))
+            }
+            _ => update(transaction_conn, true).map(|resp| HttpResponse::Ok().json(resp)),
+        }
+    })
+}
+
 #[put("")]
 async fn put_handler(
+    http_req: HttpRequest,
     req: Json<PutReq>,
     mut db_conn: DbConnection,
     user: User,
-) -> superposition::Result<Json<PutResp>> {
-    put(req, &mut db_conn, false, &user)
-        .map(|resp| Json(resp))
-        .map_err(|err: superposition::AppError| {
-            log::info!("context put failed with error: {:?}", err);
-            err
-        })
+) -> superposition::Result<HttpResponse> {
+    let unmodified_since = get_if_unmodified_since(&http_req)?;
+    let ctx_id = hash(&Value::Object(req.context.to_owned()));
+    update_if_unmodified_since(
+        &mut db_conn,
+        &ctx_id,
+        unmodified_since,
+        |conn, already_under_txn| put(req, conn, already_under_txn, &user),
+    )
+    .map_err(|err: superposition::AppError| {
+        log::info!("context put failed with error: {:?}", err);
+        err
+    })
 }
 
 fn r#move(
@@ -327,6 +419,7 @@ fn r#move(
             dsl::id.eq(&new_ctx_id),
             dsl::value.eq(&ctx_condition),
             dsl::priority.eq(priority),
+            dsl::last_modified.eq(Utc::now()),
         ))
         .get_result(conn);
 
@@ -335,9 +428,12 @@ fn r#move(
         value: ctx_condition,
         priority,
         created_at: Utc::now(),
-        created_by: user.get_email(),
+        created_by: user.get_recorded_email(),
         override_id: ctx.override_id,
         override_: ctx.override_,
+        last_modified: Utc::now(),
+        override_priority: ctx.override_priority,
+        description: ctx.description,
     };
 
     let handle_unique_violation =
@@ -348,14 +444,14 @@ fn r#move(
                     .get_result(db_conn)?;
 
                 let ctx = contruct_new_ctx_with_old_overrides(deleted_ctxt);
-                update_override_of_existing_ctx(db_conn, ctx)
+                update_override_of_existing_ctx(db_conn, ctx, None)
             } else {
                 db_conn.build_transaction().read_write().run(|conn| {
                     let deleted_ctxt = diesel::delete(dsl::contexts)
                         .filter(dsl::id.eq(&old_ctx_id))
                         .get_result(conn)?;
                     let ctx = contruct_new_ctx_with_old_overrides(deleted_ctxt);
-                    update_override_of_existing_ctx(conn, ctx)
+                    update_override_of_existing_ctx(conn, ctx, None)
                 })
             }
         };
@@ -377,47 +473,396 @@ fn r#move(
 
 #[put("/move/{ctx_id}")]
 async fn move_handler(
+    http_req: HttpRequest,
     path: Path<String>,
     req: Json<MoveReq>,
     mut db_conn: DbConnection,
     user: User,
-) -> superposition::Result<Json<PutResp>> {
-    r#move(path.into_inner(), req, &mut db_conn, false, &user)
-        .map(|resp| Json(resp))
-        .map_err(|err| {
-            log::info!("move api failed with error: {:?}", err);
-            err
-        })
+) -> superposition::Result<HttpResponse> {
+    let unmodified_since = get_if_unmodified_since(&http_req)?;
+    let ctx_id = path.into_inner();
+    update_if_unmodified_since(
+        &mut db_conn,
+        &ctx_id.clone(),
+        unmodified_since,
+        |conn, already_under_txn| r#move(ctx_id, req, conn, already_under_txn, &user),
+    )
+    .map_err(|err| {
+        log::info!("move api failed with error: {:?}", err);
+        err
+    })
+}
+
+/// Adds and removes keys of the override of context `ctx_id`, leaving its
+/// condition untouched.
+fn patch(
+    ctx_id: &str,
+    req: PatchReq,
+    conn: &mut DBConnection,
+) -> superposition::Result<PutResp> {
+    use contexts::dsl;
+    let PatchReq {
+        add_overrides,
+        remove_override_keys,
+    } = req;
+    if add_overrides.is_empty() && remove_override_keys.is_empty() {
+        return Err(bad_argument!(
+            "Provide add_overrides or remove_override_keys to update the context"
+        ));
+    }
+    if let Some(key) = remove_override_keys
+        .iter()
+        .find(|key| add_overrides.contains_key(key.as_str()))
+    {
+        return Err(bad_argument!(
+            "Override key {} is both added and removed",
+            key
+        ));
+    }
+
+    let ctx: Context = dsl::contexts
+        .filter(dsl::id.eq(ctx_id))
+        .get_result(conn)
+        .optional()?
+        .ok_or(not_found!("Context Id `{}` doesn't exists", ctx_id))?;
+    let mut new_override = match ctx.override_.clone() {
+        Value::Object(override_) => override_,
+        _ => return Err(unexpected_error!("Something went wrong")),
+    };
+    for key in remove_override_keys.iter() {
+        if new_override.remove(key).is_none() {
+            return Err(bad_argument!(
+                "Override key {} is not overridden by the context",
+                key
+            ));
+        }
+    }
+    new_override.extend(add_overrides);
+    if new_override.is_empty() {
+        return Err(bad_argument!(
+            "Override cannot be empty, delete the context instead"
+        ));
+    }
+
+    let resolved_override = resolve_pointer_overrides(conn, &new_override)?;
+    validate_override_with_default_configs(conn, &resolved_override)?;
+    validate_override_with_functions(conn, &resolved_override)?;
+
+    let new_override = Value::Object(new_override);
+    let new_ctx = Context {
+        override_id: hash(&new_override),
+        override_: new_override,
+        last_modified: Utc::now(),
+        ..ctx
+    };
+    diesel::update(dsl::contexts)
+        .filter(dsl::id.eq(&new_ctx.id))
+        .set(&new_ctx)
+        .execute(conn)?;
+    Ok(get_put_resp(new_ctx))
+}
+
+#[patch("/{ctx_id}")]
+async fn patch_handler(
+    http_req: HttpRequest,
+    path: Path<String>,
+    req: Json<PatchReq>,
+    mut db_conn: DbConnection,
+) -> superposition::Result<HttpResponse> {
+    let unmodified_since = get_if_unmodified_since(&http_req)?;
+    let ctx_id = path.into_inner();
+    update_if_unmodified_since(&mut db_conn, &ctx_id, unmodified_since, |conn, _| {
+        patch(&ctx_id, req.into_inner(), conn)
+    })
+    .map_err(|err| {
+        log::info!("context patch failed with error: {:?}", err);
+        err
+    })
+}
+
+/// Replaces the condition of context `ctx_id`, keeping its override. The new
+/// condition is rejected if it would tie with an overlapping context on both
+/// priorities while overriding a common key, as the winner would be ambiguous.
+/// The context is then moved to the new condition like `/context/move` does.
+fn update_condition(
+    ctx_id: &str,
+    condition: Map<String, Value>,
+    conn: &mut DBConnection,
+    user: &User,
+) -> superposition::Result<Context> {
+    use crate::db::schema::composite_dimensions::dsl as composite;
+    use contexts::dsl;
+
+    let ctx: Context = dsl::contexts
+        .filter(dsl::id.eq(ctx_id))
+        .for_update()
+        .get_result(conn)
+        .optional()?
+        .ok_or(not_found!("Context Id `{}` doesn't exists", ctx_id))?;
+
+    let move_req = MoveReq {
+        context: condition.clone(),
+    };
+    let condition = Value::Object(condition);
+    validate_condition_with_functions(conn, &condition)?;
+    let dimension_schema_map = get_all_dimension_schema_map(conn)?;
+    let priority = validate_dimensions_and_calculate_priority(
+        "condition",
+        &condition,
+        &dimension_schema_map,
+    )?;
+    if priority == 0 {
+        return Err(bad_argument!("No dimension found in condition"));
+    }
+
+    let new_ctx_id = hash(&condition);
+    if new_ctx_id != ctx.id {
+        let existing: Option<String> = dsl::contexts
+            .filter(dsl::id.eq(&new_ctx_id))
+            .select(dsl::id)
+            .get_result(conn)
+            .optional()?;
+        if existing.is_some() {
+            return Err(bad_argument!(
+                "Context {} already has this condition, use /context/move/{} to merge into it",
+                new_ctx_id,
+                ctx_id
+            ));
+        }
+    }
+
+    let composite_dimensions: CompositeDimensions = composite::composite_dimensions
+        .select((composite::name, composite::component_dimensions))
+        .load::<(String, Vec<String>)>(conn)?
+        .into_iter()
+        .collect();
+    let override_keys = match &ctx.override_ {
+        Value::Object(override_) => override_,
+        _ => return Err(unexpected_error!("Something went wrong")),
+    };
+    let tied_contexts: Vec<(String, Value, Value)> = dsl::contexts
+        .filter(dsl::id.ne(&ctx.id))
+        .filter(dsl::priority.eq(priority))
+        .filter(dsl::override_priority.eq(ctx.override_priority))
+        .select((dsl::id, dsl::value, dsl::override_))
+        .load(conn)?;
+    for (other_id, other_condition, other_override) in tied_contexts {
+        let shares_key = other_override
+            .as_object()
+            .map_or(false, |o| o.keys().any(|k| override_keys.contains_key(k)));
+        if shares_key
+            && are_overlapping_contexts(
+                &condition,
+                &other_condition,
+                &composite_dimensions,
+            )?
+        {
+            return Err(bad_argument!(
+                "Condition overlaps with context {} of the same priority and override_priority, set a different override_priority on either to resolve the ambiguity",
+                other_id
+            ));
+        }
+    }
+
+    let moved = r#move(ctx.id, Json(move_req), conn, true, user)?;
+    let updated_ctx = dsl::contexts
+        .filter(dsl::id.eq(&moved.context_id))
+        .get_result(conn)?;
+    Ok(updated_ctx)
+}
+
+#[patch("/{ctx_id}/condition")]
+async fn update_condition_handler(
+    state: Data<AppState>,
+    tenant: Tenant,
+    path: Path<String>,
+    req: Json<ConditionUpdateReq>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<Context>> {
+    let DbConnection(mut conn) = db_conn;
+    let ctx_id = path.into_inner();
+    let condition = req.into_inner().condition;
+    // the variants of an experiment keep the ids of their contexts, which a
+    // new condition would change under them
+    let experiment_context_ids = get_active_experiment_context_ids(&state, &tenant)?;
+    ensure_not_used_by_experiments(&ctx_id, &experiment_context_ids)?;
+    conn.transaction(|transaction_conn| {
+        update_condition(&ctx_id, condition, transaction_conn, &user)
+    })
+    .map(Json)
+    .map_err(|err: superposition::AppError| {
+        log::info!("context condition update failed with error: {:?}", err);
+        err
+    })
+}
+
+/// Filters contexts overriding `key`, either as a whole or at a JSON pointer
+/// inside it, the same rule as `overrides_config_key`.
+fn overrides_key(
+    key: String,
+) -> Box<dyn BoxableExpression<contexts::table, Pg, SqlType = Bool>> {
+    Box::new(
+        sql::<Bool>(
+            "EXISTS (SELECT 1 FROM jsonb_object_keys(\"override\"::jsonb) \
+             AS override_key WHERE override_key = ",
+        )
+        .bind::<Text, _>(key.clone())
+        .sql(" OR starts_with(override_key, ")
+        .bind::<Text, _>(key)
+        .sql(" || '#/'))"),
+    )
+}
+
+#[get("")]
+async fn get_contexts(
+    filters: Query<ContextFilters>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<ContextListResponse>> {
+    use crate::db::schema::contexts::dsl::*;
+    let DbConnection(mut conn) = db_conn;
+
+    let query_builder = |filters: &ContextFilters| {
+        let mut builder = contexts.into_boxed();
+        if let Some(key) = filters.key.clone() {
+            builder = builder.filter(overrides_key(key));
+        }
+        if let Some(dimension) = filters.dimension.clone() {
+            builder = builder.filter(
+                sql::<Bool>(
+                    "jsonb_path_exists(value::jsonb, '$.** ? (@.var == $dimension)', \
+                     jsonb_build_object('dimension', ",
+                )
+                .bind::<Text, _>(dimension)
+                .sql("))"),
+            );
+        }
+        builder
+    };
+    let filters = filters.into_inner();
+    let base_query = query_builder(&filters);
+    let count_query = query_builder(&filters);
+
+    let page = filters.page.unwrap_or(1);
+    if page < 1 {
+        return Err(bad_argument!("Param 'page' has to be at least 1."));
+    }
+    let limit = filters.count.unwrap_or(20);
+    if limit < 1 {
+        return Err(bad_argument!("Param 'count' has to be at least 1."));
+    }
+
+    let total_count: i64 = count_query.count().get_result(&mut conn)?;
+    let data: Vec<Context> = base_query
+        .order((priority.asc(), override_priority.asc(), created_at.asc()))
+        .limit(limit)
+        .offset((page - 1) * limit)
+        .load(&mut conn)?;
+
+    Ok(Json(ContextListResponse {
+        data,
+        total_count,
+        page,
+    }))
 }
 
 #[get("/{ctx_id}")]
 async fn get_context(
     path: Path<String>,
     db_conn: DbConnection,
-) -> superposition::Result<impl Responder> {
+) -> superposition::Result<Json<Context>> {
     use crate::db::schema::contexts::dsl::*;
 
     let ctx_id = path.into_inner();
     let DbConnection(mut conn) = db_conn;
 
     let ctx: Context = contexts
-        .filter(id.eq(ctx_id))
-        .get_result::<Context>(&mut conn)?;
+        .filter(id.eq(&ctx_id))
+        .get_result::<Context>(&mut conn)
+        .optional()?
+        .ok_or(not_found!("Context Id `{}` doesn't exists", ctx_id))?;
 
     Ok(Json(ctx))
 }
 
+#[get("/{ctx_id}/experiments")]
+async fn get_context_experiments(
+    state: Data<AppState>,
+    tenant: Tenant,
+    path: Path<String>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<ContextExperimentsResponse>> {
+    use crate::db::schema::{composite_dimensions::dsl as composite, contexts::dsl};
+
+    let ctx_id = path.into_inner();
+    let DbConnection(mut conn) = db_conn;
+
+    let context_value: Value = dsl::contexts
+        .filter(dsl::id.eq(&ctx_id))
+        .select(dsl::value)
+        .get_result(&mut conn)
+        .optional()?
+        .ok_or(not_found!("Context Id `{}` doesn't exists", ctx_id))?;
+    let composite_dimensions: CompositeDimensions = composite::composite_dimensions
+        .select((composite::name, composite::component_dimensions))
+        .load::<(String, Vec<String>)>(&mut conn)?
+        .into_iter()
+        .collect();
+
+    let experiment_ids = get_overlapping_experiment_ids(
+        &state,
+        &tenant,
+        &context_value,
+        &composite_dimensions,
+    )?;
+
+    Ok(Json(ContextExperimentsResponse {
+        context_id: ctx_id,
+        experiment_ids,
+    }))
+}
+
+#[post("/test")]
+async fn test_context(
+    req: Json<ContextTestReq>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<ContextTestResponse>> {
+    let DbConnection(mut conn) = db_conn;
+    let ContextTestReq {
+        context_id,
+        user_context,
+    } = req.into_inner();
+
+    let condition: Value = contexts::table
+        .filter(contexts::id.eq(&context_id))
+        .select(contexts::value)
+        .get_result(&mut conn)
+        .optional()?
+        .ok_or(not_found!("Context Id `{}` doesn't exists", context_id))?;
+
+    let result = jsonlogic::apply(&condition, &user_context).map_err(|err| {
+        log::error!("failed to evaluate context {context_id}: {err}");
+        bad_argument!("Unable to evaluate the context condition: {}", err)
+    })?;
+
+    Ok(Json(ContextTestResponse {
+        matches: result == Value::Bool(true),
+        evaluated_condition: condition.to_string(),
+    }))
+}
+
 #[get("/list")]
 async fn list_contexts(
-    qparams: Query<PaginationParams>,
+    qparams: Query<ListFilters>,
     db_conn: DbConnection,
 ) -> superposition::Result<impl Responder> {
     use crate::db::schema::contexts::dsl::*;
     let DbConnection(mut conn) = db_conn;
 
-    let PaginationParams {
+    let ListFilters {
         page: opt_page,
         size: opt_size,
+        override_key,
     } = qparams.into_inner();
     let default_page = 1;
     let page = opt_page.unwrap_or(default_page);
@@ -430,7 +875,12 @@ async fn list_contexts(
         return Err(bad_argument!("Param 'size' has to be at least 1."));
     }
 
-    let result: Vec<Context> = contexts
+    let mut builder = contexts.into_boxed();
+    if let Some(key) = override_key {
+        builder = builder.filter(overrides_key(key));
+    }
+
+    let result: Vec<Context> = builder
         .order(created_at)
         .limit(i64::from(size))
         .offset(i64::from(size * (page - 1)))
@@ -439,6 +889,51 @@ async fn list_contexts(
     Ok(Json(result))
 }
 
+#[delete("/stale")]
+async fn delete_stale_contexts(
+    state: Data<AppState>,
+    tenant: Tenant,
+    params: Query<StaleContextsQuery>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<StaleContextsResponse>> {
+    use contexts::dsl;
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+
+    let StaleContextsQuery {
+        older_than,
+        dry_run,
+    } = params.into_inner();
+    let cutoff = Utc::now() - parse_duration(&older_than)?;
+    let experiment_context_ids = get_active_experiment_context_ids(&state, &tenant)?;
+
+    let stale_contexts = dsl::contexts
+        .filter(dsl::last_modified.lt(cutoff))
+        .filter(dsl::id.ne_all(experiment_context_ids));
+    let context_ids: Vec<String> = if dry_run {
+        stale_contexts.select(dsl::id).load(&mut conn)?
+    } else {
+        delete(stale_contexts)
+            .returning(dsl::id)
+            .get_results(&mut conn)?
+    };
+
+    if !dry_run {
+        log::info!(
+            "{} deleted {} contexts not modified since {cutoff}: {:?}",
+            user.get_recorded_email(),
+            context_ids.len(),
+            context_ids
+        );
+    }
+    Ok(Json(StaleContextsResponse {
+        count: context_ids.len(),
+        context_ids,
+        dry_run,
+    }))
+}
+
 #[delete("/{ctx_id}")]
 async fn delete_context(
     path: Path<String>,
@@ -454,7 +949,7 @@ async fn delete_context(
     match deleted_row {
         Ok(0) => Err(not_found!("Context Id `{}` doesn't exists", ctx_id)),
         Ok(_) => {
-            log::info!("{ctx_id} context deleted by {}", user.get_email());
+            log::info!("{ctx_id} context deleted by {}", user.get_recorded_email());
             Ok(HttpResponse::NoContent().finish())
         }
         Err(e) => {
@@ -491,7 +986,7 @@ async fn bulk_operations(
                 ContextAction::DELETE(ctx_id) => {
                     let deleted_row =
                         delete(contexts.filter(id.eq(&ctx_id))).execute(transaction_conn);
-                    let email: String = user.get_email();
+                    let email: String = user.get_recorded_email();
                     match deleted_row {
                         // Any kind of error would rollback the tranction but explicitly returning rollback tranction allows you to rollback from any point in transaction.
                         Ok(0) => {
@@ -531,6 +1026,50 @@ async fn bulk_operations(
     Ok(Json(response))
 }
 
+#[post("/bulk")]
+async fn bulk_create(
+    reqs: Json<Vec<BulkCreateItem>>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<Vec<String>>> {
+    let DbConnection(mut conn) = db_conn;
+
+    let put_reqs = reqs
+        .into_inner()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, item)| match item.condition {
+            Value::Object(context) => Ok(PutReq {
+                context,
+                r#override: item.overrides,
+                override_priority: None,
+                pointer: None,
+                description: item.description,
+            }),
+            _ => Err(bad_argument!(
+                "condition of item {} should be a JSON object",
+                idx
+            )),
+        })
+        .collect::<superposition::Result<Vec<PutReq>>>()?;
+
+    let context_ids =
+        conn.transaction::<_, superposition::AppError, _>(|transaction_conn| {
+            put_reqs
+                .into_iter()
+                .map(|put_req| {
+                    put(Json(put_req), transaction_conn, true, &user)
+                        .map(|put_resp| put_resp.context_id)
+                        .map_err(|err| {
+                            log::error!("bulk context create failed due to {:?}", err);
+                            err
+                        })
+                })
+                .collect::<superposition::Result<Vec<String>>>()
+        })?;
+    Ok(Json(context_ids))
+}
+
 #[put("/priority/recompute")]
 async fn priority_recompute(
     db_conn: DbConnection,
@@ -544,49 +1083,40 @@ async fn priority_recompute(
     })?;
 
     let dimension_schema_map = get_all_dimension_schema_map(&mut conn)?;
-    let mut response: Vec<PriorityRecomputeResponse> = vec![];
+    let old_priorities: HashMap<String, i32> = result
+        .iter()
+        .map(|context| (context.id.clone(), context.priority))
+        .collect();
 
-    let update_contexts = result
-        .clone()
-        .into_iter()
-        .map(|context| {
-            let new_priority = calculate_context_priority(
-                "context",
-                &context.value,
-                &dimension_schema_map,
-            )
-            .map_err(|err| {
-                log::error!("failed to calculate context priority: {}", err);
-                unexpected_error!("Something went wrong")
-            });
-
-            match new_priority {
-                Ok(val) => {
-                    response.push(PriorityRecomputeResponse {
-                        id: context.id.clone(),
-                        condition: context.value.clone(),
-                        old_priority: context.priority.clone(),
-                        new_priority: val,
-                    });
-                    Ok(Context {
-                        priority: val,
-                        ..context.clone()
-                    })
-                }
-                Err(e) => Err(e),
-            }
-        })
-        .collect::<superposition::Result<Vec<Context>>>()?;
+    let update_contexts = recompute_context_priorities(result, &dimension_schema_map)
+        .map_err(|err| {
+            log::error!("failed to calculate context priority: {}", err);
+            unexpected_error!("Something went wrong")
+        })?;
 
     let insert = diesel::insert_into(contexts)
         .values(&update_contexts)
         .on_conflict(id)
         .do_update()
         .set(priority.eq(excluded(priority)))
-        .execute(&mut conn);
+        .get_results::<Context>(&mut conn);
 
     match insert {
-        Ok(_) => Ok(HttpResponse::Ok().json(response)),
+        Ok(updated_contexts) => {
+            let response = updated_contexts
+                .into_iter()
+                .map(|context| PriorityRecomputeResponse {
+                    old_priority: old_priorities
+                        .get(&context.id)
+                        .copied()
+                        .unwrap_or(context.priority),
+                    new_priority: context.priority,
+                    id: context.id,
+                    condition: context.value,
+                })
+                .collect::<Vec<PriorityRecomputeResponse>>();
+            Ok(HttpResponse::Ok().json(response))
+        }
         Err(err) => {
             log::error!(
                 "Failed to execute query while recomputing priority, error: {err}"
@@ -1,10 +1,18 @@
 extern crate base64;
 use base64::prelude::*;
-use service_utils::helpers::extract_dimensions;
-use service_utils::{result as superposition, unexpected_error, validation_error};
+use experimentation_platform::{
+    api::experiments::{helpers::are_overlapping_contexts, types::Variant},
+    db::{models::ExperimentStatusType, schema::experiments},
+};
+use service_utils::helpers::{extract_dimensions, CompositeDimensions};
+use service_utils::service::types::{AppScope, AppState, DbConnection, Tenant};
+use service_utils::{
+    bad_argument, result as superposition, unexpected_error, validation_error,
+};
 use std::str;
 
 use crate::api::functions::helpers::get_published_functions_by_names;
+use crate::helpers::json_to_sorted_string;
 use crate::validation_functions::execute_fn;
 use crate::{
     api::context::types::FunctionsInfo,
@@ -13,14 +21,20 @@ use crate::{
         dimensions::{self},
     },
 };
+use cac_client::{set_pointer, split_pointer_key};
 use diesel::{
     r2d2::{ConnectionManager, PooledConnection},
     ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
 };
-use serde_json::{Map, Value};
+use serde_json::{map::Entry, Map, Value};
 use std::collections::HashMap;
 type DBConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
+pub fn hash(val: &Value) -> String {
+    let sorted_str: String = json_to_sorted_string(val);
+    blake3::hash(sorted_str.as_bytes()).to_string()
+}
+
 pub fn validate_condition_with_functions(
     conn: &mut DBConnection,
     context: &Value,
@@ -122,8 +136,8 @@ pub fn validate_value_with_function(
         log::error!("Failed to parse function code in UTF-8: {}", err);
         unexpected_error!("Failed to parse function code in UTF-8: {}", err)
     })?;
-    if let Err((err, stdout)) = execute_fn(&utf8_decoded, key, value.to_owned()) {
-        let stdout = stdout.unwrap_or(String::new());
+    if let Err(err) = execute_fn(&utf8_decoded, key, value.to_owned()) {
+        let stdout = err.stdout();
         log::error!("function validation failed for {key} with error: {err}");
         return Err(validation_error!(
             "Function validation failed for {} with error {}. {}",
@@ -134,3 +148,169 @@ pub fn validate_value_with_function(
     }
     Ok(())
 }
+
+/// Ids of contexts used by variants of experiments that are yet to be concluded.
+pub fn get_active_experiment_context_ids(
+    state: &AppState,
+    tenant: &Tenant,
+) -> superposition::Result<Vec<String>> {
+    use experiments::dsl;
+    let DbConnection(mut conn) =
+        DbConnection::for_scope(state, tenant, AppScope::EXPERIMENTATION)?;
+    let variants: Vec<Value> = dsl::experiments
+        .filter(dsl::status.eq_any(vec![
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .select(dsl::variants)
+        .load(&mut conn)?;
+
+    let mut context_ids = Vec::new();
+    for variants in variants {
+        let variants = serde_json::from_value::<Vec<Variant>>(variants).map_err(|e| {
+            log::error!("failed to parse experiment variants: {e}");
+            unexpected_error!("Something went wrong")
+        })?;
+        context_ids.extend(variants.into_iter().filter_map(|v| v.context_id));
+    }
+    Ok(context_ids)
+}
+
+/// Rejects changes to context `ctx_id` when it is one of the
+/// `experiment_context_ids` returned by `get_active_experiment_context_ids`.
+pub fn ensure_not_used_by_experiments(
+    ctx_id: &str,
+    experiment_context_ids: &[String],
+) -> superposition::Result<()> {
+    if experiment_context_ids
+        .iter()
+        .any(|context_id| context_id == ctx_id)
+    {
+        return Err(bad_argument!(
+            "Context {} is used by an experiment that is yet to be concluded, conclude or discard the experiment first",
+            ctx_id
+        ));
+    }
+    Ok(())
+}
+
+/// Ids of experiments yet to be concluded whose context overlaps with `context`.
+pub fn get_overlapping_experiment_ids(
+    state: &AppState,
+    tenant: &Tenant,
+    context: &Value,
+    composite_dimensions: &CompositeDimensions,
+) -> superposition::Result<Vec<String>> {
+    use experiments::dsl;
+    let DbConnection(mut conn) =
+        DbConnection::for_scope(state, tenant, AppScope::EXPERIMENTATION)?;
+    let active_experiments: Vec<(i64, Value)> = dsl::experiments
+        .filter(dsl::status.eq_any(vec![
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .select((dsl::id, dsl::context))
+        .order(dsl::id.asc())
+        .load(&mut conn)?;
+
+    let mut experiment_ids = Vec::new();
+    for (experiment_id, experiment_context) in active_experiments {
+        if are_overlapping_contexts(context, &experiment_context, composite_dimensions)? {
+            experiment_ids.push(experiment_id.to_string());
+        }
+    }
+    Ok(experiment_ids)
+}
+
+/// Moves the overrides of `req_override` under `pointer`, by keying them as
+/// `<config key>#<pointer>`. An empty pointer targets the whole value.
+pub fn apply_override_pointer(
+    req_override: &Map<String, Value>,
+    pointer: Option<&str>,
+) -> superposition::Result<Map<String, Value>> {
+    match pointer {
+        None | Some("") => Ok(req_override.clone()),
+        Some(pointer) if !pointer.starts_with('/') => Err(bad_argument!(
+            "pointer {} is not a valid JSON pointer, it should start with /",
+            pointer
+        )),
+        Some(pointer) => req_override
+            .iter()
+            .map(|(key, value)| {
+                if split_pointer_key(key).1.is_some() {
+                    return Err(bad_argument!(
+                        "override key {} already targets a pointer",
+                        key
+                    ));
+                }
+                Ok((format!("{key}#{pointer}"), value.clone()))
+            })
+            .collect(),
+    }
+}
+
+/// The value each config key touched by `override_` resolves to, pointer
+/// overrides being set on top of the key's override or its default value.
+/// Validations run on these, as a pointer override alone is a partial value.
+pub fn resolve_pointer_overrides(
+    conn: &mut DBConnection,
+    override_: &Map<String, Value>,
+) -> superposition::Result<Map<String, Value>> {
+    let (pointer_overrides, mut resolved): (Map<String, Value>, Map<String, Value>) =
+        override_
+            .clone()
+            .into_iter()
+            .partition(|(key, _)| split_pointer_key(key).1.is_some());
+    if pointer_overrides.is_empty() {
+        return Ok(resolved);
+    }
+
+    let config_keys: Vec<&str> = pointer_overrides
+        .keys()
+        .map(|key| split_pointer_key(key).0)
+        .collect();
+    let default_values: HashMap<String, Value> = dsl::default_configs
+        .filter(dsl::key.eq_any(config_keys))
+        .select((dsl::key, dsl::value))
+        .load::<(String, Value)>(conn)?
+        .into_iter()
+        .collect();
+
+    for (key, value) in pointer_overrides {
+        let (config_key, pointer) = split_pointer_key(&key);
+        let base = match resolved.entry(config_key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let default_value = default_values.get(config_key).ok_or(
+                    bad_argument!("failed to get schema for config key {}", config_key),
+                )?;
+                entry.insert(default_value.clone())
+            }
+        };
+        set_pointer(base, pointer.unwrap_or_default(), value)
+            .map_err(|err| bad_argument!("failed to apply override {}: {}", key, err))?;
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_not_used_by_experiments() {
+        let experiment_context_ids =
+            vec!["control-context".to_string(), "test-context".to_string()];
+
+        assert!(
+            ensure_not_used_by_experiments("other-context", &experiment_context_ids)
+                .is_ok()
+        );
+        assert!(ensure_not_used_by_experiments("other-context", &[]).is_ok());
+
+        let err = ensure_not_used_by_experiments("test-context", &experiment_context_ids)
+            .unwrap_err();
+        assert!(matches!(err, superposition::AppError::BadArgument(_)));
+        assert!(err.to_string().contains("test-context"));
+    }
+}
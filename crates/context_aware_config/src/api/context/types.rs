@@ -1,10 +1,28 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
+use crate::db::models::Context;
+
 #[derive(Deserialize, Clone)]
 pub struct PutReq {
     pub context: Map<String, Value>,
     pub r#override: Map<String, Value>,
+    /// Breaks ties between contexts of equal dimension priority, the higher
+    /// `override_priority` wins. Left unchanged for existing contexts when absent.
+    pub override_priority: Option<i32>,
+    /// RFC 6901 pointer applied to every key of `override`, so that only the
+    /// value at that path inside the key is overridden.
+    pub pointer: Option<String>,
+    /// Left unchanged for existing contexts when absent.
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PatchReq {
+    #[serde(default)]
+    pub add_overrides: Map<String, Value>,
+    #[serde(default)]
+    pub remove_override_keys: Vec<String>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -12,6 +30,11 @@ pub struct MoveReq {
     pub context: Map<String, Value>,
 }
 
+#[derive(Deserialize)]
+pub struct ConditionUpdateReq {
+    pub condition: Map<String, Value>,
+}
+
 #[derive(Deserialize, Clone)]
 pub struct DimensionCondition {
     pub var: String,
@@ -22,12 +45,43 @@ pub struct PutResp {
     pub context_id: String,
     pub override_id: String,
     pub priority: i32,
+    pub override_priority: i32,
 }
 
 #[derive(Deserialize)]
-pub struct PaginationParams {
+pub struct ListFilters {
     pub page: Option<u32>,
     pub size: Option<u32>,
+    pub override_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ContextFilters {
+    pub page: Option<i64>,
+    pub count: Option<i64>,
+    pub key: Option<String>,
+    pub dimension: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ContextListResponse {
+    pub data: Vec<Context>,
+    pub total_count: i64,
+    pub page: i64,
+}
+
+#[derive(Deserialize)]
+pub struct StaleContextsQuery {
+    pub older_than: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize)]
+pub struct StaleContextsResponse {
+    pub count: usize,
+    pub context_ids: Vec<String>,
+    pub dry_run: bool,
 }
 
 #[derive(serde::Deserialize)]
@@ -57,3 +111,28 @@ pub struct PriorityRecomputeResponse {
     pub old_priority: i32,
     pub new_priority: i32,
 }
+
+#[derive(Serialize)]
+pub struct ContextExperimentsResponse {
+    pub context_id: String,
+    pub experiment_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BulkCreateItem {
+    pub condition: Value,
+    pub overrides: Map<String, Value>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ContextTestReq {
+    pub context_id: String,
+    pub user_context: Value,
+}
+
+#[derive(Serialize)]
+pub struct ContextTestResponse {
+    pub matches: bool,
+    pub evaluated_condition: String,
+}
@@ -1,11 +1,18 @@
 extern crate base64;
-use super::types::CreateReq;
+use std::collections::HashSet;
+
+use super::types::{
+    BulkUpdateItem, BulkUpdateResponse, BulkUpdateResult, BulkUpdateStatus, ContextUsage,
+    CreateReq, DeleteImpactResponse, DeleteQuery, ExperimentUsage, JsonSchemaDraft,
+    KeyUsagesResponse, UpdateSchemaReq, ValidateValueReq, ValidateValueResponse,
+    ValueValidationError,
+};
 use service_utils::helpers::validation_err_to_str;
 use service_utils::{
-    bad_argument, db_error, not_found, unexpected_error, validation_error,
+    bad_argument, db_error, not_found, response_error, unexpected_error, validation_error,
 };
 
-use superposition_types::{SuperpositionUser, User};
+use superposition_types::User;
 
 use crate::api::context::helpers::validate_value_with_function;
 use crate::{
@@ -15,27 +22,48 @@ use crate::{
         models::{Context, DefaultConfig},
         schema::{contexts::dsl::contexts, default_configs::dsl::default_configs},
     },
-    helpers::validate_jsonschema,
+    helpers::{
+        apply_merge_patch, overrides_config_key, validate_jsonschema,
+        validate_nullability,
+    },
 };
 use actix_web::{
-    delete, get, put,
-    web::{self, Data, Json, Path},
-    HttpResponse, Scope,
+    delete, get,
+    http::{header, StatusCode},
+    patch, post, put,
+    web::{self, Bytes, Data, Json, Path, Query},
+    HttpRequest, HttpResponse, Scope,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::{
     r2d2::{ConnectionManager, PooledConnection},
-    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+    Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
+    RunQueryDsl,
+};
+use experimentation_platform::{
+    api::experiments::types::Variant,
+    db::{
+        models::{Experiment, ExperimentStatusType},
+        schema::experiments::dsl as experiments,
+    },
 };
-use jsonschema::{Draft, JSONSchema, ValidationError};
-use serde_json::{from_value, json, Map, Value};
+use jsonschema::ValidationError;
+use serde_json::{from_value, Map, Value};
 use service_utils::{
     result as superposition,
-    service::types::{AppState, DbConnection},
+    service::types::{AppScope, AppState, DbConnection, Tenant},
 };
 
 pub fn endpoints() -> Scope {
-    Scope::new("").service(create).service(get).service(delete)
+    Scope::new("")
+        .service(create)
+        .service(bulk_update)
+        .service(update_schema)
+        .service(update)
+        .service(validate_value)
+        .service(get)
+        .service(delete)
+        .service(get_usages)
 }
 
 #[put("/{key}")]
@@ -45,12 +73,134 @@ async fn create(
     request: web::Json<CreateReq>,
     db_conn: DbConnection,
     user: User,
+) -> superposition::Result<Json<DefaultConfig>> {
+    let DbConnection(mut conn) = db_conn;
+    let (default_config, _) = prepare_default_config(
+        &state,
+        &mut conn,
+        key.into_inner(),
+        request.into_inner(),
+        &user,
+    )?;
+
+    let upsert = diesel::insert_into(default_configs)
+        .values(&default_config)
+        .on_conflict(db::schema::default_configs::key)
+        .do_update()
+        .set(&default_config)
+        .get_result::<DefaultConfig>(&mut conn);
+
+    match upsert {
+        Ok(default_config) => Ok(Json(default_config)),
+        Err(e) => {
+            log::info!("DefaultConfig creation failed with error: {e}");
+            Err(unexpected_error!(
+                "Something went wrong, failed to create DefaultConfig"
+            ))
+        }
+    }
+}
+
+#[patch("/bulk")]
+async fn bulk_update(
+    state: Data<AppState>,
+    request: web::Json<Vec<BulkUpdateItem>>,
+    db_conn: DbConnection,
+    user: User,
 ) -> superposition::Result<HttpResponse> {
     let DbConnection(mut conn) = db_conn;
-    let req = request.into_inner();
-    let key = key.into_inner();
+    let items = request.into_inner();
+    if items.is_empty() {
+        return Err(bad_argument!("Please provide at least one key to update."));
+    }
+
+    let mut seen_keys = HashSet::new();
+    let mut prepared = Vec::with_capacity(items.len());
+    let mut results = Vec::with_capacity(items.len());
+    for BulkUpdateItem { key, request } in items {
+        let outcome = if seen_keys.insert(key.to_owned()) {
+            prepare_default_config(&state, &mut conn, key.to_owned(), request, &user)
+        } else {
+            Err(bad_argument!(
+                "{} appears more than once in the request",
+                key
+            ))
+        };
+        match outcome {
+            Ok((default_config, exists)) => {
+                results.push(BulkUpdateResult {
+                    key,
+                    status: if exists {
+                        BulkUpdateStatus::Updated
+                    } else {
+                        BulkUpdateStatus::Created
+                    },
+                    error: None,
+                    default_config: None,
+                });
+                prepared.push(default_config);
+            }
+            Err(e) => results.push(BulkUpdateResult {
+                key,
+                status: BulkUpdateStatus::Failed,
+                error: Some(e.to_string()),
+                default_config: None,
+            }),
+        }
+    }
+
+    // nothing is written unless every key passes validation
+    if prepared.len() != results.len() {
+        results
+            .iter_mut()
+            .filter(|result| result.status != BulkUpdateStatus::Failed)
+            .for_each(|result| result.status = BulkUpdateStatus::Skipped);
+        return Ok(HttpResponse::BadRequest().json(BulkUpdateResponse { results }));
+    }
+
+    let stored = conn
+        .transaction::<_, superposition::AppError, _>(|conn| {
+            prepared
+                .iter()
+                .map(|default_config| {
+                    Ok(diesel::insert_into(default_configs)
+                        .values(default_config)
+                        .on_conflict(db::schema::default_configs::key)
+                        .do_update()
+                        .set(default_config)
+                        .get_result::<DefaultConfig>(conn)?)
+                })
+                .collect::<superposition::Result<Vec<DefaultConfig>>>()
+        })
+        .map_err(|e| {
+            log::error!("bulk update of default configs failed with error: {e}");
+            unexpected_error!("Something went wrong, failed to update DefaultConfigs")
+        })?;
+    // every key passed validation, so results and stored rows line up
+    for (result, default_config) in results.iter_mut().zip(stored) {
+        result.default_config = Some(default_config);
+    }
 
-    if req.value.is_none() && req.schema.is_none() && req.function_name.is_none() {
+    Ok(HttpResponse::Ok().json(BulkUpdateResponse { results }))
+}
+
+/// Merges `req` into the stored default config of `key` (if any) and validates
+/// the result, returning it along with whether the key already existed.
+fn prepare_default_config(
+    state: &AppState,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    key: String,
+    req: CreateReq,
+    user: &User,
+) -> superposition::Result<(DefaultConfig, bool)> {
+    if req.value.is_none()
+        && req.schema.is_none()
+        && req.function_name.is_none()
+        && req.expires_at.is_none()
+        && req.description.is_none()
+        && req.nullable.is_none()
+        && req.draft.is_none()
+    {
         log::error!("No data provided in the request body for {key}");
         return Err(bad_argument!("Please provide data in the request body."));
     }
@@ -65,60 +215,142 @@ async fn create(
         }
     };
 
-    let result = fetch_default_key(&key, &mut conn);
+    let expiry = match &req.expires_at {
+        Some(Value::Null) | None => None,
+        Some(val) => Some(from_value::<DateTime<Utc>>(val.clone()).map_err(|e| {
+            log::error!("Invalid expires_at for {key}: {e}");
+            bad_argument!("expires_at should be an RFC 3339 timestamp or null")
+        })?),
+    };
 
-    let (value, schema, function_name) = match result {
-        Ok((val, schema, f_name)) => {
-            let val = req.value.unwrap_or_else(|| val);
-            let schema = req.schema.map_or_else(|| schema, Value::Object);
-            let f_name = if req.function_name == Some(Value::Null) {
-                None
-            } else {
-                func_name.or(f_name)
-            };
-            (val, schema, f_name)
+    let new_description = match &req.description {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Null) | None => None,
+        Some(_) => {
+            return Err(bad_argument!(
+                "Expected a string or null as the description."
+            ))
         }
-        Err(superposition::AppError::DbError(diesel::NotFound)) => {
-            match (req.value, req.schema) {
-                (Some(val), Some(schema)) => (val, Value::Object(schema), func_name),
-                _ => {
-                    log::error!("No record found for {key}.");
-                    return Err(bad_argument!("No record found for {}", key));
+    };
+
+    let result = fetch_default_key(&key, conn);
+
+    let exists = result.is_ok();
+    let (value, schema, function_name, expires_at, description, nullable, draft) =
+        match result {
+            Ok((val, schema, f_name, expiry_at, desc, is_nullable, stored_draft)) => {
+                let val = req.value.unwrap_or_else(|| val);
+                let schema = req.schema.map_or_else(|| schema, Value::Object);
+                let f_name = if req.function_name == Some(Value::Null) {
+                    None
+                } else {
+                    func_name.or(f_name)
+                };
+                let expiry_at = if req.expires_at == Some(Value::Null) {
+                    None
+                } else {
+                    expiry.or(expiry_at)
+                };
+                let desc = if req.description == Some(Value::Null) {
+                    None
+                } else {
+                    new_description.or(desc)
+                };
+                (
+                    val,
+                    schema,
+                    f_name,
+                    expiry_at,
+                    desc,
+                    req.nullable.unwrap_or(is_nullable),
+                    req.draft
+                        .unwrap_or_else(|| JsonSchemaDraft::from_stored(&stored_draft)),
+                )
+            }
+            Err(superposition::AppError::DbError(diesel::NotFound)) => {
+                match (req.value, req.schema) {
+                    (Some(val), Some(schema)) => (
+                        val,
+                        Value::Object(schema),
+                        func_name,
+                        expiry,
+                        new_description,
+                        req.nullable.unwrap_or(true),
+                        req.draft.unwrap_or_default(),
+                    ),
+                    _ => {
+                        log::error!("No record found for {key}.");
+                        return Err(bad_argument!("No record found for {}", key));
+                    }
                 }
             }
-        }
-        Err(e) => {
-            log::error!("Failed to fetch default_config {key} with error: {e}.");
-            return Err(unexpected_error!("Something went wrong."));
-        }
-    };
+            Err(e) => {
+                log::error!("Failed to fetch default_config {key} with error: {e}.");
+                return Err(unexpected_error!("Something went wrong."));
+            }
+        };
 
     let default_config = DefaultConfig {
         key: key.to_owned(),
         value,
         schema,
         function_name,
-        created_by: user.get_email(),
+        expires_at,
+        description,
+        nullable,
+        schema_draft: draft.to_string(),
+        created_by: user.get_recorded_email(),
         created_at: Utc::now(),
     };
 
-    validate_jsonschema(
-        &state.default_config_validation_schema,
+    validate_nullability(
+        &default_config.key,
+        &default_config.value,
+        default_config.nullable,
+    )?;
+    validate_value_with_schema(
+        state,
         &default_config.schema,
+        draft,
+        &default_config.value,
     )?;
 
-    let schema_compile_result = JSONSchema::options()
-        .with_draft(Draft::Draft7)
-        .compile(&default_config.schema);
-    let jschema = match schema_compile_result {
+    if let Some(f_name) = &default_config.function_name {
+        let function_code = get_published_function_code(conn, f_name.to_string())
+            .map_err(|e| {
+                log::info!("Function not found with error : {e}");
+                bad_argument!("Function {} doesn't exists.", f_name)
+            })?;
+        if let Some(f_code) = function_code {
+            validate_value_with_function(
+                f_name,
+                &f_code,
+                &default_config.key,
+                &default_config.value,
+            )?;
+        }
+    }
+
+    Ok((default_config, exists))
+}
+
+fn validate_value_with_schema(
+    state: &AppState,
+    schema: &Value,
+    draft: JsonSchemaDraft,
+    value: &Value,
+) -> superposition::Result<()> {
+    validate_jsonschema(&state.default_config_validation_schema, schema)?;
+
+    let jschema = match draft.compile(schema) {
         Ok(jschema) => jschema,
         Err(e) => {
-            log::info!("Failed to compile as a Draft-7 JSON schema: {e}");
+            log::info!("Failed to compile as a {draft} JSON schema: {e}");
             return Err(bad_argument!("Invalid JSON schema (failed to compile)"));
         }
     };
 
-    if let Err(e) = jschema.validate(&default_config.value) {
+    if let Err(e) = jschema.validate(value) {
         let verrors = e.collect::<Vec<ValidationError>>();
         log::info!(
             "Validation for value with given JSON schema failed: {:?}",
@@ -131,55 +363,210 @@ async fn create(
                 .unwrap_or(&String::new())
         ));
     }
+    Ok(())
+}
 
-    if let Some(f_name) = &default_config.function_name {
-        let function_code = get_published_function_code(&mut conn, f_name.to_string())
-            .map_err(|e| {
-                log::info!("Function not found with error : {e}");
-                bad_argument!("Function {} doesn't exists.", f_name)
-            })?;
-        if let Some(f_code) = function_code {
-            validate_value_with_function(
-                f_name,
-                &f_code,
-                &default_config.key,
-                &default_config.value,
-            )?;
-        }
-    }
+#[patch("/{key}/schema")]
+async fn update_schema(
+    state: Data<AppState>,
+    key: Path<String>,
+    request: Json<UpdateSchemaReq>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<DefaultConfig>> {
+    use db::schema::default_configs::dsl;
+    let DbConnection(mut conn) = db_conn;
+    let key = key.into_inner();
+    let schema = Value::Object(request.into_inner().schema);
 
-    let upsert = diesel::insert_into(default_configs)
-        .values(&default_config)
-        .on_conflict(db::schema::default_configs::key)
-        .do_update()
-        .set(&default_config)
-        .execute(&mut conn);
+    let (value, _, _, _, _, _, schema_draft) = fetch_default_key(&key, &mut conn)
+        .map_err(|e| match e {
+            superposition::AppError::DbError(diesel::NotFound) => {
+                not_found!("Default config `{}` doesn't exists", key)
+            }
+            e => e,
+        })?;
+    validate_value_with_schema(
+        &state,
+        &schema,
+        JsonSchemaDraft::from_stored(&schema_draft),
+        &value,
+    )?;
 
-    match upsert {
-        Ok(_) => Ok(HttpResponse::Ok().json(json!({
-            "message": "DefaultConfig created/updated successfully."
-        }))),
-        Err(e) => {
-            log::info!("DefaultConfig creation failed with error: {e}");
-            Err(unexpected_error!(
-                "Something went wrong, failed to create DefaultConfig"
+    let updated_config = diesel::update(dsl::default_configs)
+        .filter(dsl::key.eq(&key))
+        .set(dsl::schema.eq(schema))
+        .get_result::<DefaultConfig>(&mut conn)?;
+    log::info!(
+        "{} updated schema of default config {key}",
+        user.get_recorded_email()
+    );
+    Ok(Json(updated_config))
+}
+
+enum UpdateBody {
+    MergePatch(Value),
+    Fields(CreateReq),
+}
+
+/// Updates an existing default config. Plain JSON bodies take the same fields
+/// as `PUT /{key}`, while `application/merge-patch+json` bodies are applied to
+/// the stored value as an RFC 7396 merge patch.
+#[patch("/{key}")]
+async fn update(
+    state: Data<AppState>,
+    key: Path<String>,
+    http_req: HttpRequest,
+    body: Bytes,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<DefaultConfig>> {
+    use db::schema::default_configs::dsl;
+    let DbConnection(mut conn) = db_conn;
+    let key = key.into_inner();
+
+    let content_type = http_req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    let update_body = match content_type.as_deref() {
+        Some("application/merge-patch+json") => {
+            let patch = serde_json::from_slice::<Value>(&body).map_err(|e| {
+                log::error!("Invalid merge patch for {key}: {e}");
+                bad_argument!("Request body is not a valid JSON merge patch")
+            })?;
+            UpdateBody::MergePatch(patch)
+        }
+        Some("application/json") | None => {
+            let request = serde_json::from_slice::<CreateReq>(&body).map_err(|e| {
+                log::error!("Invalid update request for {key}: {e}");
+                bad_argument!("Invalid request body: {}", e)
+            })?;
+            UpdateBody::Fields(request)
+        }
+        Some(mime) => {
+            return Err(response_error!(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!(
+                    "Unsupported content type {mime}, expected application/json or application/merge-patch+json"
+                )
             ))
         }
-    }
+    };
+
+    // the row stays locked from the read to the write, so concurrent updates
+    // of a key apply one after the other instead of overwriting each other
+    let updated_config =
+        conn.transaction::<_, superposition::AppError, _>(|transaction_conn| {
+            let mut value: Value = dsl::default_configs
+                .filter(dsl::key.eq(&key))
+                .select(dsl::value)
+                .for_update()
+                .get_result(transaction_conn)
+                .optional()?
+                .ok_or(not_found!("Default config `{}` doesn't exists", key))?;
+
+            let request = match update_body {
+                UpdateBody::MergePatch(patch) => {
+                    apply_merge_patch(&mut value, &patch);
+                    CreateReq {
+                        value: Some(value),
+                        schema: None,
+                        function_name: None,
+                        expires_at: None,
+                        description: None,
+                        nullable: None,
+                        draft: None,
+                    }
+                }
+                UpdateBody::Fields(request) => request,
+            };
+
+            let (default_config, _) = prepare_default_config(
+                &state,
+                transaction_conn,
+                key.to_owned(),
+                request,
+                &user,
+            )?;
+            Ok(diesel::update(dsl::default_configs)
+                .filter(dsl::key.eq(&key))
+                .set(&default_config)
+                .get_result::<DefaultConfig>(transaction_conn)?)
+        })?;
+    Ok(Json(updated_config))
 }
 
+#[post("/{key}/validate")]
+async fn validate_value(
+    key: Path<String>,
+    request: Json<ValidateValueReq>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<ValidateValueResponse>> {
+    let DbConnection(mut conn) = db_conn;
+    let key = key.into_inner();
+
+    let (schema, schema_draft): (Value, String) = default_configs
+        .filter(db::schema::default_configs::key.eq(&key))
+        .select((
+            db::schema::default_configs::schema,
+            db::schema::default_configs::schema_draft,
+        ))
+        .get_result(&mut conn)
+        .optional()?
+        .ok_or(not_found!("Default config `{}` doesn't exists", key))?;
+
+    let jschema = JsonSchemaDraft::from_stored(&schema_draft)
+        .compile(&schema)
+        .map_err(|e| {
+            log::error!("stored schema of {key} failed to compile: {e}");
+            unexpected_error!("Stored schema of {} is not a valid JSON schema", key)
+        })?;
+
+    let errors = match jschema.validate(&request.value) {
+        Ok(()) => Vec::new(),
+        Err(verrors) => verrors
+            .map(|error| ValueValidationError {
+                path: error.instance_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect(),
+    };
+
+    Ok(Json(ValidateValueResponse {
+        valid: errors.is_empty(),
+        errors,
+    }))
+}
+
+type DefaultKeyRow = (
+    Value,
+    Value,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+    bool,
+    String,
+);
+
 fn fetch_default_key(
     key: &String,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-) -> superposition::Result<(Value, Value, Option<String>)> {
-    let res: (Value, Value, Option<String>) = default_configs
+) -> superposition::Result<DefaultKeyRow> {
+    let res = default_configs
         .filter(db::schema::default_configs::key.eq(key))
         .select((
             db::schema::default_configs::value,
             db::schema::default_configs::schema,
             db::schema::default_configs::function_name,
+            db::schema::default_configs::expires_at,
+            db::schema::default_configs::description,
+            db::schema::default_configs::nullable,
+            db::schema::default_configs::schema_draft,
         ))
-        .get_result::<(Value, Value, Option<String>)>(conn)?;
+        .get_result::<DefaultKeyRow>(conn)?;
     Ok(res)
 }
 
@@ -191,32 +578,113 @@ async fn get(db_conn: DbConnection) -> superposition::Result<Json<Vec<DefaultCon
     Ok(Json(result))
 }
 
-pub fn get_key_usage_context_ids(
+pub fn get_key_usage_contexts(
     key: &str,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
-) -> superposition::Result<Vec<String>> {
+) -> superposition::Result<Vec<Context>> {
     let result: Vec<Context> = contexts.load(conn).map_err(|err| {
         log::error!("failed to fetch contexts with error: {}", err);
         db_error!(err)
     })?;
 
-    let mut context_ids = vec![];
-    for context in result.iter() {
-        from_value::<Map<String, Value>>(context.override_.to_owned())
+    let mut usage_contexts = vec![];
+    for context in result.into_iter() {
+        let is_used = from_value::<Map<String, Value>>(context.override_.to_owned())
             .map_err(|err| {
                 log::error!("failed decode override into object: {}", err);
                 unexpected_error!("failed to decode override")
             })?
-            .get(key)
-            .map_or((), |_| context_ids.push(context.id.to_owned()))
+            .keys()
+            .any(|override_key| overrides_config_key(override_key, key));
+        if is_used {
+            usage_contexts.push(context);
+        }
     }
-    Ok(context_ids)
+    Ok(usage_contexts)
+}
+
+pub fn get_key_usage_context_ids(
+    key: &str,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+) -> superposition::Result<Vec<String>> {
+    Ok(get_key_usage_contexts(key, conn)?
+        .into_iter()
+        .map(|context| context.id)
+        .collect())
+}
+
+pub fn get_key_usage_experiments(
+    key: &str,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+) -> superposition::Result<Vec<Experiment>> {
+    let active_experiments = experiments::experiments
+        .filter(experiments::status.eq_any(vec![
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .load::<Experiment>(conn)
+        .map_err(|err| {
+            log::error!("failed to fetch experiments with error: {}", err);
+            db_error!(err)
+        })?;
+    // override keys can point inside a key's value, which an array containment
+    // filter on the key cannot match
+    Ok(active_experiments
+        .into_iter()
+        .filter(|experiment| {
+            experiment
+                .override_keys
+                .iter()
+                .any(|override_key| overrides_config_key(override_key, key))
+        })
+        .collect())
+}
+
+/// Logs default config keys that are past their `expires_at` and, when
+/// `delete_expired` is set, removes the ones not used by any context.
+pub fn handle_expired_configs(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    delete_expired: bool,
+) -> superposition::Result<Vec<String>> {
+    let expired_keys: Vec<String> = default_configs
+        .filter(db::schema::default_configs::expires_at.le(Utc::now()))
+        .select(db::schema::default_configs::key)
+        .load::<String>(conn)?;
+
+    if expired_keys.is_empty() {
+        return Ok(expired_keys);
+    }
+    log::info!("default config keys expired: {:?}", expired_keys);
+
+    if !delete_expired {
+        return Ok(expired_keys);
+    }
+
+    let mut deleted_keys = Vec::new();
+    for key in expired_keys.into_iter() {
+        let context_ids = get_key_usage_context_ids(&key, conn)?;
+        if !context_ids.is_empty() {
+            log::info!(
+                "skipping delete of expired key {key}, still in use in contexts: {}",
+                context_ids.join(",")
+            );
+            continue;
+        }
+        diesel::delete(default_configs.filter(db::schema::default_configs::key.eq(&key)))
+            .execute(conn)?;
+        deleted_keys.push(key);
+    }
+    log::info!("expired default config keys deleted: {:?}", deleted_keys);
+    Ok(deleted_keys)
 }
 
 #[delete("/{key}")]
 async fn delete(
+    state: Data<AppState>,
     path: Path<String>,
+    query: Query<DeleteQuery>,
     db_conn: DbConnection,
+    tenant: Tenant,
     user: User,
 ) -> superposition::Result<HttpResponse> {
     let DbConnection(mut conn) = db_conn;
@@ -225,6 +693,20 @@ async fn delete(
     fetch_default_key(&key, &mut conn)?;
     let context_ids = get_key_usage_context_ids(&key, &mut conn)
         .map_err(|_| unexpected_error!("Something went wrong"))?;
+
+    if query.dry_run.unwrap_or(false) {
+        let DbConnection(mut exp_conn) =
+            DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+        let experiment_ids = get_key_usage_experiments(&key, &mut exp_conn)?
+            .into_iter()
+            .map(|experiment| experiment.id.to_string())
+            .collect();
+        return Ok(HttpResponse::Ok().json(DeleteImpactResponse {
+            context_ids,
+            experiment_ids,
+        }));
+    }
+
     if context_ids.is_empty() {
         let deleted_row = diesel::delete(
             default_configs.filter(db::schema::default_configs::key.eq(&key)),
@@ -233,7 +715,10 @@ async fn delete(
         match deleted_row {
             Ok(0) => Err(not_found!("default config key `{}` doesn't exists", key)),
             Ok(_) => {
-                log::info!("default config key: {key} deleted by {}", user.get_email());
+                log::info!(
+                    "default config key: {key} deleted by {}",
+                    user.get_recorded_email()
+                );
                 Ok(HttpResponse::NoContent().finish())
             }
             Err(e) => {
@@ -248,3 +733,55 @@ async fn delete(
         ))
     }
 }
+
+#[get("/{key}/usages")]
+async fn get_usages(
+    state: Data<AppState>,
+    path: Path<String>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+) -> superposition::Result<Json<KeyUsagesResponse>> {
+    let DbConnection(mut conn) = db_conn;
+
+    let key = path.into_inner();
+    fetch_default_key(&key, &mut conn)?;
+    let contexts_usage = get_key_usage_contexts(&key, &mut conn)?
+        .into_iter()
+        .map(|context| ContextUsage {
+            id: context.id,
+            condition: context.value,
+        })
+        .collect();
+
+    let DbConnection(mut exp_conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+    let mut experiments_usage = vec![];
+    for experiment in get_key_usage_experiments(&key, &mut exp_conn)? {
+        let variants: Vec<Variant> = from_value(experiment.variants).map_err(|err| {
+            log::error!(
+                "failed to parse variants of experiment {} with error: {}",
+                experiment.id,
+                err
+            );
+            unexpected_error!("Something went wrong, failed to fetch key usages")
+        })?;
+        for variant in variants {
+            if variant
+                .overrides
+                .keys()
+                .any(|override_key| overrides_config_key(override_key, &key))
+            {
+                experiments_usage.push(ExperimentUsage {
+                    id: experiment.id.to_string(),
+                    name: experiment.name.clone(),
+                    variant_id: variant.id,
+                });
+            }
+        }
+    }
+
+    Ok(Json(KeyUsagesResponse {
+        contexts: contexts_usage,
+        experiments: experiments_usage,
+    }))
+}
@@ -1,3 +1,4 @@
 mod handlers;
 mod types;
-pub use handlers::endpoints;
+pub use handlers::{endpoints, get_key_usage_context_ids, handle_expired_configs};
+pub use types::JsonSchemaDraft;
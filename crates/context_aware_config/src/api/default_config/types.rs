@@ -1,4 +1,6 @@
-use serde::{Deserialize, Deserializer};
+use crate::db::models::DefaultConfig;
+use jsonschema::{Draft, JSONSchema, ValidationError};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Value};
 
 #[derive(Debug, Deserialize)]
@@ -8,6 +10,96 @@ pub struct CreateReq {
     pub schema: Option<Map<String, Value>>,
     #[serde(default, deserialize_with = "deserialize_option")]
     pub function_name: Option<Value>,
+    #[serde(default, deserialize_with = "deserialize_option")]
+    pub expires_at: Option<Value>,
+    #[serde(default, deserialize_with = "deserialize_option")]
+    pub description: Option<Value>,
+    /// Whether the value may be `null`, left unchanged for existing keys when
+    /// absent. New keys are nullable unless this is `false`.
+    pub nullable: Option<bool>,
+    /// Draft the schema is written in, left unchanged for existing keys when
+    /// absent. New keys default to Draft 7.
+    pub draft: Option<JsonSchemaDraft>,
+}
+
+/// JSON schema drafts default config schemas can be written in, stored by name
+/// in `default_configs.schema_draft`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+)]
+pub enum JsonSchemaDraft {
+    #[default]
+    Draft7,
+    Draft201909,
+    Draft202012,
+}
+
+impl JsonSchemaDraft {
+    /// Parses a stored draft name, keys created before drafts were stored
+    /// have `Draft7`.
+    pub fn from_stored(draft: &str) -> Self {
+        draft.parse().unwrap_or_else(|_| {
+            log::error!("unknown schema draft {draft}, falling back to Draft7");
+            Self::default()
+        })
+    }
+
+    pub fn compile(self, schema: &Value) -> Result<JSONSchema, ValidationError<'_>> {
+        JSONSchema::options()
+            .with_draft(self.into())
+            .compile(schema)
+    }
+}
+
+impl From<JsonSchemaDraft> for Draft {
+    fn from(draft: JsonSchemaDraft) -> Self {
+        match draft {
+            JsonSchemaDraft::Draft7 => Draft::Draft7,
+            JsonSchemaDraft::Draft201909 => Draft::Draft201909,
+            JsonSchemaDraft::Draft202012 => Draft::Draft202012,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateItem {
+    pub key: String,
+    #[serde(flatten)]
+    pub request: CreateReq,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkUpdateStatus {
+    Created,
+    Updated,
+    /// valid on its own but not written because another key failed
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateResult {
+    pub key: String,
+    pub status: BulkUpdateStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// the key as stored, once the bulk update went through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_config: Option<DefaultConfig>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateResponse {
+    pub results: Vec<BulkUpdateResult>,
 }
 
 fn deserialize_option<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
@@ -17,3 +109,55 @@ where
     let value: Value = Deserialize::deserialize(deserializer)?;
     Ok(Some(value))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSchemaReq {
+    pub schema: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateValueReq {
+    pub value: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidateValueResponse {
+    pub valid: bool,
+    pub errors: Vec<ValueValidationError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteQuery {
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteImpactResponse {
+    pub context_ids: Vec<String>,
+    pub experiment_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContextUsage {
+    pub id: String,
+    pub condition: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExperimentUsage {
+    pub id: String,
+    pub name: String,
+    pub variant_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyUsagesResponse {
+    pub contexts: Vec<ContextUsage>,
+    pub experiments: Vec<ExperimentUsage>,
+}
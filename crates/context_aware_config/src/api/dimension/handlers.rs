@@ -1,27 +1,48 @@
 use crate::{
-    api::dimension::types::CreateReq,
-    db::{models::Dimension, schema::dimensions::dsl::*},
-    helpers::validate_jsonschema,
+    api::dimension::{
+        types::{
+            CreateReq, DependencyGraphQuery, DimensionValueType, GraphFormat,
+            ListFilters, ListResponse, UpdatePriorityReq,
+        },
+        utils::{dependency_graph_dot, get_all_dimension_schema_map},
+    },
+    db::{
+        models::{CompositeDimension, Context, Dimension},
+        schema::{composite_dimensions, contexts, dimensions::dsl::*},
+    },
+    helpers::{recompute_context_priorities, validate_jsonschema},
 };
 use actix_web::{
-    get, put,
-    web::{self, Data, Json},
+    get, patch, put,
+    web::{self, Data, Json, Path, Query},
     HttpResponse, Scope,
 };
 use chrono::Utc;
-use diesel::RunQueryDsl;
+use diesel::{
+    upsert::excluded, Connection, ExpressionMethods, PgTextExpressionMethods, QueryDsl,
+    RunQueryDsl,
+};
+use experimentation_platform::db::{
+    models::ExperimentStatusType, schema::experiments::dsl as experiments,
+};
 use jsonschema::{Draft, JSONSchema};
 use serde_json::Value;
 use service_utils::{
-    bad_argument, result as superposition,
-    service::types::{AppState, DbConnection},
+    bad_argument,
+    helpers::escape_like_pattern,
+    not_found, result as superposition,
+    service::types::{AppScope, AppState, DbConnection, Tenant},
     unexpected_error,
 };
 
-use superposition_types::{SuperpositionUser, User};
+use superposition_types::User;
 
 pub fn endpoints() -> Scope {
-    Scope::new("").service(create).service(get)
+    Scope::new("")
+        .service(create)
+        .service(get)
+        .service(get_dependency_graph)
+        .service(update_priority)
 }
 
 #[put("")]
@@ -46,13 +67,42 @@ async fn create(
         .with_draft(Draft::Draft7)
         .compile(&schema_value);
 
-    if let Err(e) = schema_compile_result {
-        return Err(bad_argument!(
-            "Invalid JSON schema (failed to compile): {:?}",
-            e
-        ));
+    let jschema = match schema_compile_result {
+        Ok(jschema) => jschema,
+        Err(e) => {
+            return Err(bad_argument!(
+                "Invalid JSON schema (failed to compile): {:?}",
+                e
+            ));
+        }
     };
 
+    let dimension_allowed_values =
+        match (create_req.value_type, create_req.allowed_values) {
+            (Some(DimensionValueType::ENUM), Some(values)) if !values.is_empty() => {
+                if let Some(invalid_value) =
+                    values.iter().find(|value| !jschema.is_valid(value))
+                {
+                    return Err(bad_argument!(
+                        "allowed value {} doesn't match the dimension schema",
+                        invalid_value
+                    ));
+                }
+                Some(Value::Array(values))
+            }
+            (Some(DimensionValueType::ENUM), _) => {
+                return Err(bad_argument!(
+                    "allowed_values should be a non-empty list for enum dimensions"
+                ));
+            }
+            (_, Some(_)) => {
+                return Err(bad_argument!(
+                    "allowed_values can only be set for enum dimensions"
+                ));
+            }
+            (_, None) => None,
+        };
+
     let fun_name = match create_req.function_name {
         Some(Value::String(func_name)) => Some(func_name),
         Some(Value::Null) | None => None,
@@ -68,9 +118,11 @@ async fn create(
         dimension: create_req.dimension,
         priority: i32::from(create_req.priority),
         schema: schema_value,
-        created_by: user.get_email(),
+        created_by: user.get_recorded_email(),
         created_at: Utc::now(),
         function_name: fun_name.clone(),
+        value_type: create_req.value_type.map(|v_type| v_type.to_string()),
+        allowed_values: dimension_allowed_values,
     };
 
     let upsert = diesel::insert_into(dimensions)
@@ -104,9 +156,154 @@ async fn create(
 }
 
 #[get("")]
-async fn get(db_conn: DbConnection) -> superposition::Result<Json<Vec<Dimension>>> {
+async fn get(
+    filters: Query<ListFilters>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<ListResponse>> {
+    let DbConnection(mut conn) = db_conn;
+
+    let query_builder = |filters: &ListFilters| {
+        let mut builder = dimensions.into_boxed();
+        if let Some(search) = filters.search.clone() {
+            let search = escape_like_pattern(&search);
+            builder = builder.filter(dimension.ilike(format!("{search}%")));
+        }
+        builder
+    };
+    let filters = filters.into_inner();
+    let base_query = query_builder(&filters);
+    let count_query = query_builder(&filters);
+
+    let page = filters.page.unwrap_or(1);
+    if page < 1 {
+        return Err(bad_argument!("Param 'page' has to be at least 1."));
+    }
+
+    let total_count: i64 = count_query.count().get_result(&mut conn)?;
+
+    let mut query = base_query.order((priority.asc(), dimension.asc()));
+    // without an explicit count all matching dimensions are returned
+    if let Some(limit) = filters.count {
+        if limit < 1 {
+            return Err(bad_argument!("Param 'count' has to be at least 1."));
+        }
+        query = query.limit(limit).offset((page - 1) * limit);
+    }
+    let data: Vec<Dimension> = query.load(&mut conn)?;
+
+    Ok(Json(ListResponse {
+        data,
+        total_count,
+        page,
+    }))
+}
+
+#[get("/dependency_graph")]
+async fn get_dependency_graph(
+    state: Data<AppState>,
+    query: Query<DependencyGraphQuery>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+) -> superposition::Result<HttpResponse> {
+    let DbConnection(mut conn) = db_conn;
+    let all_dimensions: Vec<Dimension> = dimensions
+        .order((priority.asc(), dimension.asc()))
+        .load(&mut conn)?;
+    let all_composites: Vec<CompositeDimension> = composite_dimensions::table
+        .order(composite_dimensions::name.asc())
+        .load(&mut conn)?;
+    let context_conditions: Vec<Value> =
+        contexts::table.select(contexts::value).load(&mut conn)?;
+
+    let DbConnection(mut exp_conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+    let experiment_conditions: Vec<Value> = experiments::experiments
+        .filter(experiments::status.eq_any([
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .select(experiments::context)
+        .load(&mut exp_conn)?;
+
+    match query.into_inner().format {
+        GraphFormat::Dot => Ok(HttpResponse::Ok()
+            .content_type("text/vnd.graphviz")
+            .body(dependency_graph_dot(
+                &all_dimensions,
+                &all_composites,
+                &context_conditions,
+                &experiment_conditions,
+            ))),
+    }
+}
+
+#[patch("/{name}/priority")]
+async fn update_priority(
+    path: Path<String>,
+    req: web::Json<UpdatePriorityReq>,
+    user: User,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Vec<Dimension>>> {
     let DbConnection(mut conn) = db_conn;
+    let name = path.into_inner();
+    let new_priority = req.into_inner().priority;
+
+    if new_priority <= 0 {
+        return Err(bad_argument!("Priority should be greater than 0"));
+    }
+
+    let result =
+        conn.transaction::<_, superposition::AppError, _>(|transaction_conn| {
+            let updated_rows = diesel::update(dimensions)
+                .filter(dimension.eq(&name))
+                .set(priority.eq(new_priority))
+                .execute(transaction_conn)?;
+            if updated_rows == 0 {
+                return Err(not_found!("Dimension `{}` doesn't exists", name));
+            }
+
+            let has_conflict = diesel::select(diesel::dsl::exists(
+                dimensions
+                    .filter(dimension.ne(&name))
+                    .filter(priority.eq(new_priority)),
+            ))
+            .get_result::<bool>(transaction_conn)?;
+
+            // shift every dimension at or above the requested priority
+            // up by one, so that relative ordering is preserved
+            if has_conflict {
+                diesel::update(dimensions)
+                    .filter(dimension.ne(&name))
+                    .filter(priority.ge(new_priority))
+                    .set(priority.eq(priority + 1))
+                    .execute(transaction_conn)?;
+            }
+
+            // context priorities are sums of their dimensions' priorities, so they
+            // have to follow the new ordering in the same transaction
+            let dimension_schema_map = get_all_dimension_schema_map(transaction_conn)?;
+            let all_contexts: Vec<Context> = contexts::table.load(transaction_conn)?;
+            let recomputed_contexts =
+                recompute_context_priorities(all_contexts, &dimension_schema_map)
+                    .map_err(|err| {
+                        log::error!("failed to recompute context priorities: {}", err);
+                        unexpected_error!("Something went wrong")
+                    })?;
+            diesel::insert_into(contexts::table)
+                .values(&recomputed_contexts)
+                .on_conflict(contexts::id)
+                .do_update()
+                .set(contexts::priority.eq(excluded(contexts::priority)))
+                .execute(transaction_conn)?;
+
+            Ok(dimensions
+                .order(priority.asc())
+                .get_results::<Dimension>(transaction_conn)?)
+        })?;
 
-    let result: Vec<Dimension> = dimensions.get_results(&mut conn)?;
+    log::info!(
+        "priority of dimension {name} updated to {new_priority} by {}",
+        user.get_recorded_email()
+    );
     Ok(Json(result))
 }
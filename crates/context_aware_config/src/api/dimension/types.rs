@@ -1,6 +1,8 @@
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
+use crate::db::models::Dimension;
+
 #[derive(Debug, Deserialize)]
 pub struct CreateReq {
     pub dimension: String,
@@ -8,6 +10,50 @@ pub struct CreateReq {
     pub schema: Value,
     #[serde(default, deserialize_with = "deserialize_option")]
     pub function_name: Option<Value>,
+    pub value_type: Option<DimensionValueType>,
+    pub allowed_values: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, strum_macros::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DimensionValueType {
+    STRING,
+    NUMBER,
+    BOOLEAN,
+    ENUM,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DependencyGraphQuery {
+    #[serde(default)]
+    pub format: GraphFormat,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePriorityReq {
+    pub priority: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListFilters {
+    pub page: Option<i64>,
+    pub count: Option<i64>,
+    pub search: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ListResponse {
+    pub data: Vec<Dimension>,
+    pub total_count: i64,
+    pub page: i64,
 }
 
 fn deserialize_option<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
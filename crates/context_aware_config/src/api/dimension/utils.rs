@@ -1,13 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
 
-use crate::db::{models::Dimension, schema::dimensions::dsl::*};
+use crate::db::{
+    models::{CompositeDimension, Dimension},
+    schema::dimensions::dsl::*,
+};
 use diesel::RunQueryDsl;
 use diesel::{
     r2d2::{ConnectionManager, PooledConnection},
     PgConnection,
 };
 use jsonschema::{Draft, JSONSchema};
-use service_utils::result as superposition;
+use serde_json::Value;
+use service_utils::{helpers::extract_dimensions, result as superposition};
 
 pub fn get_all_dimension_schema_map(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
@@ -28,3 +33,98 @@ pub fn get_all_dimension_schema_map(
 
     Ok(dimension_schema_map)
 }
+
+fn dot_escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", dot_escape(name))
+}
+
+fn condition_dimensions(condition: &Value) -> BTreeSet<String> {
+    extract_dimensions(condition)
+        .map(|found| found.into_iter().map(|(name, _)| name).collect())
+        .unwrap_or_default()
+}
+
+/// Renders dimensions as a Graphviz DOT graph. Nodes carry the priority of a
+/// dimension and how many contexts and experiments use it, composite
+/// dimensions point at their components and dimensions used together in a
+/// condition are linked by the number of conditions they share.
+pub fn dependency_graph_dot(
+    all_dimensions: &[Dimension],
+    composite_dimensions: &[CompositeDimension],
+    context_conditions: &[Value],
+    experiment_conditions: &[Value],
+) -> String {
+    let mut usages: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut shared: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let conditions = context_conditions
+        .iter()
+        .map(|condition| (condition, true))
+        .chain(
+            experiment_conditions
+                .iter()
+                .map(|condition| (condition, false)),
+        );
+    for (condition, is_context) in conditions {
+        let used = condition_dimensions(condition);
+        for name in &used {
+            let usage = usages.entry(name.clone()).or_default();
+            if is_context {
+                usage.0 += 1;
+            } else {
+                usage.1 += 1;
+            }
+        }
+        for (i, a) in used.iter().enumerate() {
+            for b in used.iter().skip(i + 1) {
+                *shared.entry((a.clone(), b.clone())).or_default() += 1;
+            }
+        }
+    }
+
+    let mut dot = String::from("digraph dimensions {\n    rankdir=LR;\n");
+    for item in all_dimensions {
+        let (contexts, experiments) =
+            usages.get(&item.dimension).copied().unwrap_or_default();
+        let _ = writeln!(
+            dot,
+            "    {} [label=\"{}\\npriority {}\\n{} contexts, {} experiments\"];",
+            dot_id(&item.dimension),
+            dot_escape(&item.dimension),
+            item.priority,
+            contexts,
+            experiments
+        );
+    }
+    for composite in composite_dimensions {
+        let _ = writeln!(
+            dot,
+            "    {} [shape=box, label=\"{}\\npriority {}\"];",
+            dot_id(&composite.name),
+            dot_escape(&composite.name),
+            composite.priority
+        );
+        for component in &composite.component_dimensions {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [style=dashed];",
+                dot_id(&composite.name),
+                dot_id(component)
+            );
+        }
+    }
+    for ((a, b), count) in shared {
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [dir=none, label=\"{}\"];",
+            dot_id(&a),
+            dot_id(&b),
+            count
+        );
+    }
+    dot.push_str("}\n");
+    dot
+}
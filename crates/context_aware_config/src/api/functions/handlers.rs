@@ -4,7 +4,9 @@ use base64::prelude::*;
 use super::helpers::{decode_function, fetch_function};
 
 use crate::{
-    api::functions::types::{Stage, TestFunctionRequest, TestParam},
+    api::functions::types::{
+        Stage, TestFunctionRequest, TestFunctionResponse, TestParam,
+    },
     db::{
         self,
         models::Function,
@@ -19,13 +21,13 @@ use actix_web::{
 };
 use chrono::Utc;
 use diesel::{delete, ExpressionMethods, QueryDsl, RunQueryDsl};
-use serde_json::json;
+use serde_json::{json, Value};
 use service_utils::{bad_argument, not_found, service::types::DbConnection};
 
-use superposition_types::{SuperpositionUser, User};
+use superposition_types::User;
 
 use service_utils::{result as superposition, unexpected_error};
-use validation_functions::{compile_fn, execute_fn};
+use validation_functions::{compile_fn, execute_fn, FunctionExecutionError};
 
 use super::types::{CreateFunctionRequest, UpdateFunctionRequest};
 
@@ -37,6 +39,7 @@ pub fn endpoints() -> Scope {
         .service(list_functions)
         .service(delete_function)
         .service(test)
+        .service(test_published)
         .service(publish)
 }
 
@@ -55,7 +58,7 @@ async fn create(
         function_name: req.function_name,
         draft_code: BASE64_STANDARD.encode(req.function),
         draft_runtime_version: req.runtime_version,
-        draft_edited_by: user.get_email(),
+        draft_edited_by: user.get_recorded_email(),
         draft_edited_at: Utc::now().naive_utc(),
         published_code: None,
         published_at: None,
@@ -135,7 +138,7 @@ async fn update(
             .runtime_version
             .unwrap_or(result.draft_runtime_version),
         function_description: req.description.unwrap_or(result.function_description),
-        draft_edited_by: user.get_email(),
+        draft_edited_by: user.get_recorded_email(),
         draft_edited_at: Utc::now().naive_utc(),
         published_code: result.published_code,
         published_at: result.published_at,
@@ -191,7 +194,7 @@ async fn delete_function(
     match deleted_row {
         Ok(0) => Err(not_found!("Function {} doesn't exists", f_name)),
         Ok(_) => {
-            log::info!("{f_name} function deleted by {}", user.get_email());
+            log::info!("{f_name} function deleted by {}", user.get_recorded_email());
             Ok(HttpResponse::NoContent().finish())
         }
         Err(e) => {
@@ -234,10 +237,7 @@ async fn test(
             Some(code) => execute_fn(&code, &req.key, req.value),
             None => {
                 log::error!("Function test failed: function not published yet");
-                Err((
-                    "Function test failed as function not published yet".to_owned(),
-                    None,
-                ))
+                Err(FunctionExecutionError::NotPublished)
             }
         },
     };
@@ -245,10 +245,57 @@ async fn test(
     match result {
         Ok(stdout) => Ok(HttpResponse::Ok()
             .json(json!({"message": "Function validated the given value successfully", "stdout": stdout}))),
-        Err((e, stdout)) => Err(bad_argument!("Function validation failed with error: {}, stdout: {:?}", e, stdout.unwrap_or(String::new()))),
+        Err(e) => Err(bad_argument!("Function validation failed with error: {}, stdout: {:?}", e, e.stdout())),
     }
 }
 
+#[post("/{function_name}/test")]
+async fn test_published(
+    params: Path<String>,
+    request: web::Json<TestFunctionRequest>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<TestFunctionResponse>> {
+    let DbConnection(mut conn) = db_conn;
+    let fun_name = params.into_inner();
+    let req = request.into_inner();
+    let mut function = match fetch_function(&fun_name, &mut conn) {
+        Ok(val) => val,
+        Err(superposition::AppError::DbError(diesel::result::Error::NotFound)) => {
+            log::error!("Function {} not found.", fun_name);
+            return Err(bad_argument!("Function {} doesn't exists", fun_name));
+        }
+        Err(e) => {
+            log::error!("Failed to fetch Function {fun_name} with error: {e}");
+            return Err(unexpected_error!(
+                "Something went wrong, failed to test function"
+            ));
+        }
+    };
+
+    decode_function(&mut function)?;
+    let result = match function.published_code {
+        Some(code) => execute_fn(&code, &req.key, req.value),
+        None => Err(FunctionExecutionError::NotPublished),
+    };
+
+    let response = match result {
+        Ok(stdout) => TestFunctionResponse {
+            passed: true,
+            output: Value::String(stdout),
+            error: None,
+        },
+        Err(e) => {
+            log::info!("Function {fun_name} test failed with error: {e}");
+            TestFunctionResponse {
+                passed: false,
+                output: Value::String(e.stdout()),
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    Ok(Json(response))
+}
+
 #[put("/{function_name}/publish")]
 async fn publish(
     params: web::Path<String>,
@@ -278,7 +325,7 @@ async fn publish(
             dsl::published_code.eq(Some(function.draft_code.clone())),
             dsl::published_runtime_version
                 .eq(Some(function.draft_runtime_version.clone())),
-            dsl::published_by.eq(Some(user.get_email())),
+            dsl::published_by.eq(Some(user.get_recorded_email())),
             dsl::published_at.eq(Some(Utc::now().naive_utc())),
         ))
         .get_result::<Function>(&mut conn)?;
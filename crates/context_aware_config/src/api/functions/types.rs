@@ -45,3 +45,10 @@ pub struct TestFunctionRequest {
     pub key: String,
     pub value: Value,
 }
+
+#[derive(Debug, Serialize)]
+pub struct TestFunctionResponse {
+    pub passed: bool,
+    pub output: Value,
+    pub error: Option<String>,
+}
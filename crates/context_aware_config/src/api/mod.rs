@@ -1,6 +1,9 @@
+pub mod admin;
 pub mod audit_log;
+pub mod composite_dimension;
 pub mod config;
 pub mod context;
 pub mod default_config;
 pub mod dimension;
 pub mod functions;
+pub mod state;
@@ -0,0 +1,52 @@
+use actix_web::{get, web::Data, web::Json, Scope};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use experimentation_platform::{
+    api::experiments::types::ExperimentResponse,
+    db::{
+        models::{Experiment, ExperimentStatusType},
+        schema::experiments::dsl as experiments,
+    },
+};
+use service_utils::{
+    result as superposition,
+    service::types::{AppScope, AppState, DbConnection, Tenant},
+};
+use superposition_types::User;
+
+use super::types::StateResponse;
+use crate::api::config::generate_cac;
+
+pub fn endpoints() -> Scope {
+    Scope::new("").service(get_state)
+}
+
+/// The config and the running experiments of the tenant in one response, the
+/// runtime state a client would load.
+#[get("/state")]
+async fn get_state(
+    state: Data<AppState>,
+    tenant: Tenant,
+    _user: User,
+) -> superposition::Result<Json<StateResponse>> {
+    let DbConnection(mut cac_conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::CAC)?;
+    let config = generate_cac(&mut cac_conn).await?;
+
+    let DbConnection(mut exp_conn) =
+        DbConnection::for_scope(&state, &tenant, AppScope::EXPERIMENTATION)?;
+    let experiments = experiments::experiments
+        .filter(experiments::status.eq_any([
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .order(experiments::created_at.desc())
+        .load::<Experiment>(&mut exp_conn)?
+        .into_iter()
+        .map(ExperimentResponse::from)
+        .collect();
+
+    Ok(Json(StateResponse {
+        experiments,
+        config,
+    }))
+}
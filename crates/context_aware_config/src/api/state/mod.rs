@@ -0,0 +1,3 @@
+mod handlers;
+mod types;
+pub use handlers::endpoints;
@@ -0,0 +1,10 @@
+use experimentation_platform::api::experiments::types::ExperimentResponse;
+use serde::Serialize;
+
+use crate::api::config::Config;
+
+#[derive(Serialize)]
+pub struct StateResponse {
+    pub experiments: Vec<ExperimentResponse>,
+    pub config: Config,
+}
@@ -0,0 +1,117 @@
+use std::future::Future;
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    Error,
+};
+
+#[cfg(feature = "redis-cache")]
+pub use redis_cache::ConfigCache;
+
+/// Drops the cached config of the request's namespace once a mutating request
+/// succeeds. Meant for `wrap_fn` on scopes that write contexts, overrides or
+/// default configs, it only passes requests through without `redis-cache`.
+pub fn invalidate_on_write<S, B>(
+    req: ServiceRequest,
+    srv: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    let response = srv.call(req);
+    async move {
+        let response = response.await?;
+        #[cfg(feature = "redis-cache")]
+        redis_cache::invalidate_after(&response).await;
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+mod redis_cache {
+    use actix_web::{dev::ServiceResponse, http::Method, web::Data};
+    use redis::{aio::ConnectionManager, AsyncCommands};
+    use service_utils::{
+        helpers::{get_from_env_or_default, get_from_env_unsafe},
+        service::types::AppExecutionNamespace,
+    };
+
+    use crate::api::config::Config;
+
+    #[derive(Clone)]
+    pub struct ConfigCache {
+        conn: ConnectionManager,
+        ttl_secs: u64,
+    }
+
+    impl ConfigCache {
+        /// Connects to `REDIS_URL`, returns `None` when it is not configured or
+        /// unreachable so that the service keeps working off the database.
+        pub async fn from_env() -> Option<Self> {
+            let url: String = get_from_env_unsafe("REDIS_URL").ok()?;
+            let ttl_secs = get_from_env_or_default("REDIS_CACHE_TTL_SECS", 60);
+            let client = redis::Client::open(url)
+                .map_err(|e| log::error!("invalid REDIS_URL: {e}"))
+                .ok()?;
+            let conn = ConnectionManager::new(client)
+                .await
+                .map_err(|e| log::error!("failed to connect to redis: {e}"))
+                .ok()?;
+            Some(ConfigCache { conn, ttl_secs })
+        }
+
+        fn key(namespace: &str) -> String {
+            format!("superposition:config:{namespace}")
+        }
+
+        pub async fn get(&self, namespace: &str) -> Option<Config> {
+            let cached: Option<String> = self
+                .conn
+                .clone()
+                .get(Self::key(namespace))
+                .await
+                .map_err(|e| log::error!("failed to read cached config: {e}"))
+                .ok()?;
+            cached.and_then(|config| {
+                serde_json::from_str(&config)
+                    .map_err(|e| log::error!("failed to decode cached config: {e}"))
+                    .ok()
+            })
+        }
+
+        pub async fn set(&self, namespace: &str, config: &Config) {
+            let config = match serde_json::to_string(config) {
+                Ok(config) => config,
+                Err(e) => return log::error!("failed to encode config for cache: {e}"),
+            };
+            let result: redis::RedisResult<()> = self
+                .conn
+                .clone()
+                .set_ex(Self::key(namespace), config, self.ttl_secs)
+                .await;
+            if let Err(e) = result {
+                log::error!("failed to cache config: {e}");
+            }
+        }
+
+        pub async fn invalidate(&self, namespace: &str) {
+            let result: redis::RedisResult<()> =
+                self.conn.clone().del(Self::key(namespace)).await;
+            if let Err(e) = result {
+                log::error!("failed to invalidate cached config of {namespace}: {e}");
+            }
+        }
+    }
+
+    pub(super) async fn invalidate_after<B>(response: &ServiceResponse<B>) {
+        let req = response.request();
+        if req.method() == Method::GET || !response.status().is_success() {
+            return;
+        }
+        let cache = req.app_data::<Data<ConfigCache>>().cloned();
+        let namespace = AppExecutionNamespace::from_request_sync(req);
+        if let (Some(cache), Ok(AppExecutionNamespace(namespace))) = (cache, namespace) {
+            cache.invalidate(&namespace).await;
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod models;
 pub mod schema;
+
+use include_dir::{include_dir, Dir};
+
+pub static MIGRATIONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/migrations");
@@ -1,4 +1,7 @@
-use crate::db::schema::{contexts, default_configs, dimensions, event_log, functions};
+use crate::db::schema::{
+    composite_dimensions, config_changelog, contexts, default_configs, dimensions,
+    event_log, functions, tenants,
+};
 use chrono::{offset::Utc, DateTime, NaiveDateTime};
 use diesel::{AsChangeset, Insertable, Queryable, Selectable};
 use serde::Serialize;
@@ -16,6 +19,9 @@ pub struct Context {
     pub priority: i32,
     #[serde(rename(serialize = "override"))]
     pub override_: Value,
+    pub last_modified: DateTime<Utc>,
+    pub override_priority: i32,
+    pub description: Option<String>,
 }
 
 #[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize)]
@@ -29,9 +35,22 @@ pub struct Dimension {
     pub created_by: String,
     pub schema: Value,
     pub function_name: Option<String>,
+    pub value_type: Option<String>,
+    pub allowed_values: Option<Value>,
 }
 
-#[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize, Clone)]
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize, Clone, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(primary_key(name))]
+pub struct CompositeDimension {
+    pub name: String,
+    pub component_dimensions: Vec<String>,
+    pub priority: i32,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize, Clone, Debug)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(primary_key(key))]
 #[diesel(treat_none_as_null = true)]
@@ -42,6 +61,10 @@ pub struct DefaultConfig {
     pub created_by: String,
     pub schema: Value,
     pub function_name: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub description: Option<String>,
+    pub nullable: bool,
+    pub schema_draft: String,
 }
 
 #[derive(Queryable, Selectable, Insertable, AsChangeset, Serialize, Clone, Debug)]
@@ -74,3 +97,34 @@ pub struct EventLog {
     pub new_data: Option<Value>,
     pub query: String,
 }
+
+#[derive(Queryable, Selectable, Serialize, Clone, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = config_changelog)]
+#[diesel(primary_key(id))]
+pub struct ConfigChangelog {
+    pub id: uuid::Uuid,
+    pub table_name: String,
+    pub entity_id: String,
+    pub keys: Vec<String>,
+    pub action: String,
+    pub actor: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    /// full rows of changed contexts, for rewinding them in config previews
+    #[serde(skip_serializing)]
+    pub old_row: Option<Value>,
+    #[serde(skip_serializing)]
+    pub new_row: Option<Value>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Clone, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = tenants)]
+#[diesel(primary_key(id))]
+pub struct TenantActivity {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+}
@@ -1,5 +1,31 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    composite_dimensions (name) {
+        name -> Text,
+        component_dimensions -> Array<Text>,
+        priority -> Int4,
+        created_at -> Timestamptz,
+        created_by -> Text,
+    }
+}
+
+diesel::table! {
+    config_changelog (id) {
+        id -> Uuid,
+        table_name -> Text,
+        entity_id -> Text,
+        keys -> Array<Text>,
+        action -> Text,
+        actor -> Text,
+        old_value -> Nullable<Json>,
+        new_value -> Nullable<Json>,
+        old_row -> Nullable<Json>,
+        new_row -> Nullable<Json>,
+        timestamp -> Timestamptz,
+    }
+}
+
 diesel::table! {
     contexts (id) {
         id -> Varchar,
@@ -10,6 +36,9 @@ diesel::table! {
         priority -> Int4,
         #[sql_name = "override"]
         override_ -> Json,
+        last_modified -> Timestamptz,
+        override_priority -> Int4,
+        description -> Nullable<Text>,
     }
 }
 
@@ -21,6 +50,10 @@ diesel::table! {
         created_by -> Varchar,
         schema -> Json,
         function_name -> Nullable<Text>,
+        expires_at -> Nullable<Timestamptz>,
+        description -> Nullable<Text>,
+        nullable -> Bool,
+        schema_draft -> Varchar,
     }
 }
 
@@ -32,6 +65,8 @@ diesel::table! {
         created_by -> Varchar,
         schema -> Json,
         function_name -> Nullable<Text>,
+        value_type -> Nullable<Varchar>,
+        allowed_values -> Nullable<Json>,
     }
 }
 
@@ -598,10 +633,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tenants (id) {
+        id -> Text,
+        created_at -> Timestamptz,
+        last_active_at -> Timestamptz,
+    }
+}
+
 diesel::joinable!(default_configs -> functions (function_name));
 diesel::joinable!(dimensions -> functions (function_name));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    composite_dimensions,
+    config_changelog,
     contexts,
     default_configs,
     dimensions,
@@ -648,4 +693,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     event_log_y2026m11,
     event_log_y2026m12,
     functions,
+    tenants,
 );
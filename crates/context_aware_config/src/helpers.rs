@@ -1,9 +1,16 @@
-use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::db::models::Context;
+use actix_web::http::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use cac_client::split_pointer_key;
+use chrono::Duration;
 use itertools::{self, Itertools};
 use jsonschema::{Draft, JSONSchema, ValidationError};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 use service_utils::{
-    helpers::validation_err_to_str, result as superposition, validation_error,
+    bad_argument, helpers::validation_err_to_str, response_error,
+    result as superposition, validation_error,
 };
 use std::collections::HashMap;
 
@@ -287,6 +294,107 @@ pub fn calculate_context_priority(
     }
 }
 
+/// Rejects a `null` value of `key` unless the key is nullable.
+pub fn validate_nullability(
+    key: &str,
+    value: &Value,
+    nullable: bool,
+) -> superposition::Result<()> {
+    if !nullable && value.is_null() {
+        return Err(response_error!(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("{} is not nullable, provide a non-null value", key)
+        ));
+    }
+    Ok(())
+}
+
+/// Whether an override key sets `config_key`, either as a whole or at a JSON
+/// pointer inside its value (`<config key>#/<pointer>`).
+pub fn overrides_config_key(override_key: &str, config_key: &str) -> bool {
+    split_pointer_key(override_key).0 == config_key
+}
+
+/// Recomputes the priority of each context from the current priorities of the
+/// dimensions in its condition.
+pub fn recompute_context_priorities(
+    contexts: Vec<Context>,
+    dimension_schema_map: &HashMap<String, (JSONSchema, i32)>,
+) -> Result<Vec<Context>, String> {
+    contexts
+        .into_iter()
+        .map(|context| {
+            let priority = calculate_context_priority(
+                "context",
+                &context.value,
+                dimension_schema_map,
+            )?;
+            Ok(Context {
+                priority,
+                ..context
+            })
+        })
+        .collect()
+}
+
+/// Parses durations like `90s`, `15m`, `12h`, `30d` or `2w`.
+pub fn parse_duration(duration: &str) -> superposition::Result<Duration> {
+    let duration = duration.trim();
+    let invalid = || {
+        bad_argument!(
+            "Invalid duration `{}`, expected a number followed by one of s, m, h, d, w",
+            duration
+        )
+    };
+    if duration.len() < 2 || !duration.is_char_boundary(duration.len() - 1) {
+        return Err(invalid());
+    }
+    let (amount, unit) = duration.split_at(duration.len() - 1);
+    let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    amount
+        .checked_mul(unit_seconds)
+        .filter(|seconds| *seconds <= i64::MAX / 1000)
+        .map(Duration::seconds)
+        .ok_or_else(invalid)
+}
+
+/// Applies an RFC 7396 JSON merge patch to `target`: `null` members of the
+/// patch remove keys, objects are merged recursively and anything else
+/// replaces the target.
+pub fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        _ => {
+            *target = patch.clone();
+            return;
+        }
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                let entry = target.entry(key.to_owned()).or_insert(Value::Null);
+                apply_merge_patch(entry, value);
+            }
+        }
+    }
+}
+
 // ************ Tests *************
 
 #[cfg(test)]
@@ -422,4 +530,129 @@ mod tests {
         assert_eq!(err_arr_context, true);
         assert_eq!(ok_arr_context.unwrap(), ());
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("-1d").is_err());
+        assert!(parse_duration("10y").is_err());
+    }
+
+    #[test]
+    fn test_apply_merge_patch() {
+        let mut value = json!({
+            "title": "Goodbye!",
+            "author": {"givenName": "John", "familyName": "Doe"},
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        apply_merge_patch(
+            &mut value,
+            &json!({
+                "title": "Hello!",
+                "phoneNumber": "+01-123-456-7890",
+                "author": {"familyName": null},
+                "tags": ["example"]
+            }),
+        );
+        assert_eq!(
+            value,
+            json!({
+                "title": "Hello!",
+                "author": {"givenName": "John"},
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            })
+        );
+
+        let mut value = json!(["a"]);
+        apply_merge_patch(&mut value, &json!({"a": {"b": null, "c": 1}}));
+        assert_eq!(value, json!({"a": {"c": 1}}));
+
+        let mut value = json!({"a": "b"});
+        apply_merge_patch(&mut value, &json!(null));
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn test_recompute_context_priorities_follows_dimension_priorities() {
+        let context = |id: &str, condition: Value| Context {
+            id: id.to_string(),
+            value: condition,
+            override_id: format!("{id}-override"),
+            created_at: chrono::Utc::now(),
+            created_by: "user@superposition.io".to_string(),
+            priority: 0,
+            override_: json!({}),
+            last_modified: chrono::Utc::now(),
+            override_priority: 0,
+            description: None,
+        };
+        let contexts = vec![
+            context("os", json!({"==": [{"var": "os"}, "android"]})),
+            context("client", json!({"==": [{"var": "clientId"}, "geddit"]})),
+            context(
+                "both",
+                json!({"and": [
+                    {"==": [{"var": "os"}, "android"]},
+                    {"==": [{"var": "clientId"}, "geddit"]}
+                ]}),
+            ),
+        ];
+        let schema_map = |os: i32, client_id: i32| {
+            let schema = || JSONSchema::compile(&json!({"type": "string"})).unwrap();
+            HashMap::from([
+                ("os".to_string(), (schema(), os)),
+                ("clientId".to_string(), (schema(), client_id)),
+            ])
+        };
+        let ordered_ids = |contexts: &[Context]| {
+            contexts
+                .iter()
+                .sorted_by_key(|context| context.priority)
+                .map(|context| context.id.clone())
+                .collect::<Vec<String>>()
+        };
+
+        let contexts = recompute_context_priorities(contexts, &schema_map(1, 2)).unwrap();
+        assert_eq!(ordered_ids(&contexts), vec!["os", "client", "both"]);
+
+        let contexts = recompute_context_priorities(contexts, &schema_map(4, 2)).unwrap();
+        assert_eq!(ordered_ids(&contexts), vec!["client", "os", "both"]);
+        assert_eq!(contexts[2].priority, 6);
+
+        assert!(recompute_context_priorities(
+            vec![context("unknown", json!({"==": [{"var": "region"}, "eu"]}))],
+            &schema_map(1, 2)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_overrides_config_key() {
+        assert!(overrides_config_key("ui", "ui"));
+        assert!(overrides_config_key("ui#/theme/primary_color", "ui"));
+        assert!(!overrides_config_key("ui#/theme/primary_color", "theme"));
+        assert!(!overrides_config_key("ui_theme", "ui"));
+        assert!(!overrides_config_key("ui", "ui#/theme"));
+    }
+
+    #[test]
+    fn test_validate_nullability() {
+        assert!(validate_nullability("key1", &json!(null), true).is_ok());
+        assert!(validate_nullability("key1", &json!("value1"), false).is_ok());
+        assert!(validate_nullability("key1", &json!({}), false).is_ok());
+
+        match validate_nullability("key1", &json!(null), false) {
+            Err(superposition::AppError::ResponseError(err)) => {
+                assert_eq!(err.status_code, StatusCode::UNPROCESSABLE_ENTITY);
+                assert!(err.message.contains("key1"));
+            }
+            result => panic!("expected a 422 for a null value, got {result:?}"),
+        }
+    }
 }
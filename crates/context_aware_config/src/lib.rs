@@ -1,4 +1,5 @@
 pub mod api;
+pub mod config_cache;
 pub mod db;
 pub mod helpers;
 pub mod middlewares;
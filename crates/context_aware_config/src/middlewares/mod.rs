@@ -1 +1,2 @@
 pub mod cookie_to_header;
+pub mod tenant_activity;
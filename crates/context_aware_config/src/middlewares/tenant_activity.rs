@@ -0,0 +1,98 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    web::Data,
+    Error, HttpMessage,
+};
+use chrono::Utc;
+use diesel::{upsert::excluded, ExpressionMethods, RunQueryDsl};
+use service_utils::service::types::{AppScope, AppState, DbConnection, Tenant};
+use superposition_types::User;
+
+use crate::db::{models::TenantActivity, schema::tenants::dsl};
+
+/// `last_active_at` is bumped at most once per tenant within this window, so
+/// that polling clients do not turn every read into a write.
+const ACTIVITY_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+fn last_recorded() -> &'static Mutex<HashMap<String, Instant>> {
+    static LAST_RECORDED: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+    LAST_RECORDED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether the activity of `tenant` is due to be written, marking it as
+/// written when it is.
+fn is_due(tenant: &str) -> bool {
+    let mut last_recorded = last_recorded()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match last_recorded.get(tenant) {
+        Some(at) if at.elapsed() < ACTIVITY_WRITE_INTERVAL => false,
+        _ => {
+            last_recorded.insert(tenant.to_string(), Instant::now());
+            true
+        }
+    }
+}
+
+fn upsert_tenant(state: &AppState, tenant: &Tenant) -> service_utils::result::Result<()> {
+    let DbConnection(mut conn) = DbConnection::for_scope(state, tenant, AppScope::CAC)?;
+    let now = Utc::now();
+    diesel::insert_into(dsl::tenants)
+        .values(TenantActivity {
+            id: tenant.to_string(),
+            created_at: now,
+            last_active_at: now,
+        })
+        .on_conflict(dsl::id)
+        .do_update()
+        .set(dsl::last_active_at.eq(excluded(dsl::last_active_at)))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+/// Records the tenant of every authenticated request in the `tenants` table,
+/// in the background.
+/// Meant for `wrap_fn` registered inside the tenant middleware, so that the
+/// tenant is known by the time the response comes back.
+pub fn record_tenant_activity<S, B>(
+    req: ServiceRequest,
+    srv: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    let response = srv.call(req);
+    async move {
+        let response = response.await?;
+        let request = response.request();
+        let tenant = request.extensions().get::<Tenant>().cloned();
+        let is_authenticated = request.extensions().get::<User>().is_some();
+        if let (Some(tenant), true, Some(state)) = (
+            tenant,
+            is_authenticated,
+            request.app_data::<Data<AppState>>(),
+        ) {
+            if is_due(tenant.as_str()) {
+                // the upsert blocks on the database, so it runs on the blocking
+                // pool and the response does not wait for it
+                let state = state.clone();
+                actix_web::rt::task::spawn_blocking(move || {
+                    if let Err(err) = upsert_tenant(&state, &tenant) {
+                        log::error!(
+                            "failed to record activity of {}: {err}",
+                            tenant.as_str()
+                        );
+                    }
+                });
+            }
+        }
+        Ok(response)
+    }
+}
@@ -1,14 +1,64 @@
 use serde_json::{json, Value};
+use service_utils::helpers::get_from_env_or_default;
 use service_utils::result as superposition;
 use service_utils::unexpected_error;
 use service_utils::validation_error;
+use std::fmt;
 use std::process::Command;
 use std::str;
 
+const TIMEOUT_EXIT_CODE: i32 = 124;
+const MEMORY_LIMIT_EXIT_CODE: i32 = 125;
+
+// resource limits applied to the worker thread running the function
+const MAX_OLD_GENERATION_SIZE_MB: u32 = 64;
+const MAX_YOUNG_GENERATION_SIZE_MB: u32 = 16;
+const STACK_SIZE_MB: u32 = 4;
+
+/// Module path functions load axios from, relative to the working directory
+/// unless absolute.
+fn axios_module_path() -> String {
+    get_from_env_or_default("AXIOS_MODULE_PATH", "./target/node_modules/axios".into())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FunctionExecutionError {
+    Timeout { stdout: String },
+    MemoryLimitExceeded { stdout: String },
+    Failed { stderr: String, stdout: String },
+    NotPublished,
+    Runtime(String),
+}
+
+impl FunctionExecutionError {
+    pub fn stdout(&self) -> String {
+        match self {
+            Self::Timeout { stdout }
+            | Self::MemoryLimitExceeded { stdout }
+            | Self::Failed { stdout, .. } => stdout.to_owned(),
+            Self::NotPublished | Self::Runtime(_) => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for FunctionExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { .. } => write!(f, "function exceeded the time limit"),
+            Self::MemoryLimitExceeded { .. } => {
+                write!(f, "function exceeded the memory limit")
+            }
+            Self::Failed { stderr, .. } => write!(f, "{}", stderr),
+            Self::NotPublished => write!(f, "function not published yet"),
+            Self::Runtime(err) => write!(f, "js_eval error: {}", err),
+        }
+    }
+}
+
 fn type_check_validate(code_str: &str) -> String {
     format!(
         r#"const vm = require("node:vm")
-        const axios = require("./target/node_modules/axios")
+        const axios = require({})
         const script = new vm.Script(\`
 
         {}
@@ -20,6 +70,7 @@ fn type_check_validate(code_str: &str) -> String {
 
         script.runInNewContext({{axios,console}}, {{ timeout: 1500}});
         "#,
+        json!(axios_module_path()),
         code_str
     )
 }
@@ -28,7 +79,7 @@ fn execute_validate_fun(code_str: &str, value: Value, key: String) -> String {
     format!(
         r#"
         const vm = require("node:vm")
-        const axios = require("./target/node_modules/axios")
+        const axios = require({})
         const script = new vm.Script(\`
 
         {}
@@ -44,7 +95,10 @@ fn execute_validate_fun(code_str: &str, value: Value, key: String) -> String {
 
         script.runInNewContext({{axios,console,process}}, {{ timeout: 1500}});
         "#,
-        code_str, value, key
+        json!(axios_module_path()),
+        code_str,
+        value,
+        key
     )
 }
 
@@ -59,7 +113,14 @@ fn generate_code(code_str: &str) -> String {
     function runService() {{
         return new Promise((resolve, reject) => {{
         const worker = new Worker(
-            `{}`,{{eval:true}}
+            `{}`,{{
+                eval:true,
+                resourceLimits: {{
+                    maxOldGenerationSizeMb: {},
+                    maxYoungGenerationSizeMb: {},
+                    stackSizeMb: {},
+                }},
+            }}
         );
         worker.on("message", (msg) => {{
             console.log(msg);
@@ -67,6 +128,12 @@ fn generate_code(code_str: &str) -> String {
         worker.on("error", (err) => {{
             clearTimeout(tl);
             console.error(err.message);
+            if (err.code === "ERR_WORKER_OUT_OF_MEMORY") {{
+                process.exit({});
+            }}
+            if (err.code === "ERR_SCRIPT_EXECUTION_TIMEOUT") {{
+                process.exit({});
+            }}
             process.exit(1);
         }});
         worker.on("exit", (code) => {{
@@ -81,7 +148,8 @@ fn generate_code(code_str: &str) -> String {
 
         function timelimit() {{
             worker.terminate();
-            throw new Error("time limit exceeded");
+            console.error("time limit exceeded");
+            process.exit({});
         }}
 
         // terminate worker thread if execution time exceed 2 secs
@@ -98,7 +166,13 @@ fn generate_code(code_str: &str) -> String {
     }}
 
     "#,
-        code_str
+        code_str,
+        MAX_OLD_GENERATION_SIZE_MB,
+        MAX_YOUNG_GENERATION_SIZE_MB,
+        STACK_SIZE_MB,
+        MEMORY_LIMIT_EXIT_CODE,
+        TIMEOUT_EXIT_CODE,
+        TIMEOUT_EXIT_CODE
     )
 }
 
@@ -106,7 +180,7 @@ pub fn execute_fn(
     code_str: &str,
     key: &str,
     value: Value,
-) -> Result<String, (String, Option<String>)> {
+) -> Result<String, FunctionExecutionError> {
     let exec_code =
         execute_validate_fun(code_str, value, format!("\"{}\"", key.to_string()));
     let output = Command::new("node")
@@ -127,14 +201,20 @@ pub fn execute_fn(
                     "{}",
                     format!("validation function output error: {:?}", stderr)
                 );
-                Err((stderr, Some(stdout)))
+                Err(match val.status.code() {
+                    Some(TIMEOUT_EXIT_CODE) => FunctionExecutionError::Timeout { stdout },
+                    Some(MEMORY_LIMIT_EXIT_CODE) => {
+                        FunctionExecutionError::MemoryLimitExceeded { stdout }
+                    }
+                    _ => FunctionExecutionError::Failed { stderr, stdout },
+                })
             } else {
                 Ok(stdout)
             }
         }
         Err(e) => {
             log::error!("js_eval error: {}", e);
-            Err((format!("js_eval error: {}", e), None))
+            Err(FunctionExecutionError::Runtime(e.to_string()))
         }
     }
 }
@@ -1,6 +1,9 @@
-use context_aware_config::validation_functions::{compile_fn, execute_fn};
+use context_aware_config::validation_functions::{
+    compile_fn, execute_fn, FunctionExecutionError,
+};
 use serde_json::json;
 use service_utils::result as superposition;
+use std::{env, fs, sync::Once};
 
 // #[test] //todo : currently there is issue in running this test
 fn test_execute_fn() {
@@ -25,7 +28,7 @@ fn test_execute_fn() {
     let err_execute =
         match execute_fn(&(execute_code_error.to_owned()), "test", json!(10)) {
             Ok(_) => false,
-            Err((e, _)) => e.contains("Bad schema"),
+            Err(e) => e.to_string().contains("Bad schema"),
         };
     let err_compile = match compile_fn(&(compile_code_error.to_owned())) {
         Ok(()) => false,
@@ -40,3 +43,52 @@ fn test_execute_fn() {
     assert_eq!(compile_fn(&(code_ok.to_owned())).unwrap(), ());
     assert_eq!(err_compile, true);
 }
+
+/// Points functions at an empty axios stand-in in a temp dir, which is enough
+/// for functions that never call it.
+fn mock_axios() {
+    static MOCK_AXIOS: Once = Once::new();
+    MOCK_AXIOS.call_once(|| {
+        let dir = env::temp_dir()
+            .join(format!("superposition-cac-tests-{}", std::process::id()))
+            .join("axios");
+        fs::create_dir_all(&dir).expect("failed to create the axios mock directory");
+        fs::write(dir.join("index.js"), "module.exports = {};\n")
+            .expect("failed to mock axios");
+        env::set_var("AXIOS_MODULE_PATH", dir);
+    });
+}
+
+#[test]
+fn test_execute_fn_infinite_loop() {
+    mock_axios();
+    let code = r#"
+        function validate() {
+            while (true) {}
+        }
+    "#;
+
+    assert!(matches!(
+        execute_fn(code, "test", json!(10)),
+        Err(FunctionExecutionError::Timeout { .. })
+    ));
+}
+
+#[test]
+fn test_execute_fn_memory_bomb() {
+    mock_axios();
+    let code = r#"
+        async function validate() {
+            const hog = [];
+            while (true) {
+                hog.push(new Array(1000000).fill("superposition"));
+                await Promise.resolve();
+            }
+        }
+    "#;
+
+    assert!(matches!(
+        execute_fn(code, "test", json!(10)),
+        Err(FunctionExecutionError::MemoryLimitExceeded { .. })
+    ));
+}
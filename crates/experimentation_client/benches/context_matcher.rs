@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use experimentation_client::ContextMatcher;
+use serde_json::{json, Value};
+
+fn context_matching(c: &mut Criterion) {
+    let condition = json!({
+        "and": [
+            { "==": [{ "var": "city" }, "Bangalore"] },
+            { "<=": [10, { "var": "app_version" }, 20] },
+            { "in": ["7259558160762015744-test", { "var": "variantIds" }] }
+        ]
+    });
+    let context = json!({
+        "city": "Bangalore",
+        "app_version": 12,
+        "variantIds": ["7259558160762015744-control", "7259558160762015744-test"]
+    });
+    let matcher = ContextMatcher::new(&condition);
+
+    let mut group = c.benchmark_group("context_matching");
+    group.bench_function("jsonlogic_apply", |b| {
+        b.iter(|| {
+            jsonlogic::apply(black_box(&condition), black_box(&context))
+                == Ok(Value::Bool(true))
+        })
+    });
+    group.bench_function("context_matcher", |b| {
+        b.iter(|| matcher.matches(black_box(&context)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, context_matching);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Consecutive failed fetches after which the circuit opens
+pub(crate) const FAILURE_THRESHOLD: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// requests flow normally
+    Closed,
+    /// requests are held back until the open duration has passed
+    Open,
+    /// the open duration has passed and the next request decides the state
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub(crate) fn as_gauge(self) -> f64 {
+        match self {
+            CircuitState::Closed => 0.0,
+            CircuitState::HalfOpen => 1.0,
+            CircuitState::Open => 2.0,
+        }
+    }
+}
+
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold,
+            open_duration,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+
+    pub(crate) fn state(&self) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.open_duration => {
+                CircuitState::Open
+            }
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// How much longer requests are held back, if the circuit is open.
+    pub(crate) fn remaining_open(&self) -> Option<Duration> {
+        self.opened_at
+            .map(|opened_at| self.open_duration.saturating_sub(opened_at.elapsed()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    pub(crate) fn record_failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.state() == CircuitState::HalfOpen
+            || self.consecutive_failures >= self.failure_threshold
+        {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("state", &self.state())
+            .field("consecutive_failures", &self.consecutive_failures)
+            .field("open_duration", &self.open_duration)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_recovers() {
+        let mut breaker = CircuitBreaker::new(3, Duration::from_millis(50));
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.remaining_open().is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.remaining_open().is_none());
+
+        // a single failure while half open opens the circuit again
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(60));
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
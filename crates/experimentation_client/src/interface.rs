@@ -48,7 +48,7 @@ pub fn take_last_error() -> Option<String> {
     LAST_ERROR.with(|prev| prev.take())
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn last_error_length() -> c_int {
     LAST_ERROR.with(|prev| match *prev.borrow() {
         Some(ref err) => err.to_string().len() as c_int + 1,
@@ -56,7 +56,7 @@ pub extern "C" fn last_error_length() -> c_int {
     })
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub unsafe extern "C" fn last_error_message() -> *const c_char {
     let last_error = match take_last_error() {
         Some(err) => err,
@@ -68,7 +68,7 @@ pub unsafe extern "C" fn last_error_message() -> *const c_char {
     err.into_raw()
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub unsafe extern "C" fn free_string(s: *mut c_char) {
     if s.is_null() {
         return;
@@ -78,7 +78,7 @@ pub unsafe extern "C" fn free_string(s: *mut c_char) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn new_client(
     tenant: *const c_char,
     update_frequency: c_ulong,
@@ -108,15 +108,14 @@ pub extern "C" fn new_client(
         {
             Ok(_) => 0,
             Err(err) => {
-                update_last_error(err);
+                update_last_error(err.to_string());
                 1
             }
         }
-    });
-    0
+    })
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn start_polling_update(tenant: *const c_char) {
     if tenant.is_null() {
         return ();
@@ -132,7 +131,7 @@ pub extern "C" fn start_polling_update(tenant: *const c_char) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn free_client(ptr: *mut Arc<Client>) {
     if ptr.is_null() {
         return;
@@ -142,7 +141,7 @@ pub extern "C" fn free_client(ptr: *mut Arc<Client>) {
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_client(tenant: *const c_char) -> *mut Arc<Client> {
     let ten = match cstring_to_rstring(tenant) {
         Ok(t) => t,
@@ -160,7 +159,7 @@ pub extern "C" fn get_client(tenant: *const c_char) -> *mut Arc<Client> {
                 Ok(client) => Box::into_raw(Box::new(client)),
                 Err(err) => {
                     // println!("error occurred {err}");
-                    update_last_error(err);
+                    update_last_error(err.to_string());
                     // println!("error set");
                     std::ptr::null_mut()
                 }
@@ -169,7 +168,7 @@ pub extern "C" fn get_client(tenant: *const c_char) -> *mut Arc<Client> {
     )
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_applicable_variant(
     client: *mut Arc<Client>,
     c_context: *const c_char,
@@ -194,7 +193,7 @@ pub extern "C" fn get_applicable_variant(
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_satisfied_experiments(
     client: *mut Arc<Client>,
     c_context: *const c_char,
@@ -221,7 +220,7 @@ pub extern "C" fn get_satisfied_experiments(
     }
 }
 
-#[no_mangle]
+#[cfg_attr(feature = "c_exports", no_mangle)]
 pub extern "C" fn get_running_experiments(client: *mut Arc<Client>) -> *mut c_char {
     let local = task::LocalSet::new();
     let experiments = local.block_on(&Runtime::new().unwrap(), unsafe {
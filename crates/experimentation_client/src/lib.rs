@@ -1,16 +1,38 @@
-mod interface;
+mod circuit_breaker;
+/// The C FFI. Its functions are exported unmangled with the default
+/// `c_exports` feature, `superposition_sdk` turns it off to export them under
+/// prefixed names instead.
+#[allow(clippy::missing_safety_doc, clippy::not_unsafe_ptr_arg_deref)]
+pub mod interface;
+mod matcher;
+mod metrics;
 mod types;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
 
 use chrono::{DateTime, TimeZone, Utc};
+pub use circuit_breaker::CircuitState;
+use circuit_breaker::{CircuitBreaker, FAILURE_THRESHOLD};
 use derive_more::{Deref, DerefMut};
+pub use matcher::ContextMatcher;
+use reqwest::{header, StatusCode};
 use serde_json::Value;
 use tokio::{
-    sync::RwLock,
+    sync::{mpsc, Mutex as AsyncMutex, Notify, RwLock},
     time::{self, Duration},
 };
-pub use types::{Config, Experiment, Experiments, Variants};
-use types::{ExperimentStore, ListExperimentsResponse, Variant, VariantType};
+pub use types::{
+    ClientError, Config, ConfigBuilder, ConfigError, Experiment, ExperimentFilter,
+    Experiments, ObserveFn, Variants, DEFAULT_CIRCUIT_OPEN_DURATION_SECS,
+    DEFAULT_CONNECT_TIMEOUT_MS, DEFAULT_POLL_FREQUENCY_SECS, DEFAULT_READ_TIMEOUT_MS,
+};
+use types::{ExperimentStore, ListExperimentsResponse, StoredExperiment};
 
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -18,112 +40,309 @@ pub struct Client {
     pub(crate) experiments: Arc<RwLock<ExperimentStore>>,
     pub(crate) http_client: reqwest::Client,
     last_polled: Arc<RwLock<DateTime<Utc>>>,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    force_refresh_tx: Arc<mpsc::Sender<()>>,
+    force_refresh_rx: Arc<AsyncMutex<mpsc::Receiver<()>>>,
+    shutdown_requested: Arc<AtomicBool>,
+    shutdown: Arc<Notify>,
 }
 
 //TODO: replace all unwraps with proper error handling
 // DO NOT let panics show up in library
 
+/// Upper bound for the delay between retries after consecutive fetch failures
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
 impl Client {
-    pub fn new(config: Config) -> Self {
-        Client {
+    pub fn new(config: Config) -> Result<Self, ClientError> {
+        let http_client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .timeout(Duration::from_millis(config.read_timeout_ms))
+            .build()?;
+        let circuit_breaker = CircuitBreaker::new(
+            FAILURE_THRESHOLD,
+            Duration::from_secs(config.circuit_open_duration_secs),
+        );
+        // a pending signal already forces the next poll, extra ones are dropped
+        let (force_refresh_tx, force_refresh_rx) = mpsc::channel(1);
+        Ok(Client {
+            circuit_breaker: Arc::new(Mutex::new(circuit_breaker)),
             client_config: Arc::new(config),
+            force_refresh_tx: Arc::new(force_refresh_tx),
+            force_refresh_rx: Arc::new(AsyncMutex::new(force_refresh_rx)),
+            shutdown_requested: Arc::new(AtomicBool::new(false)),
+            shutdown: Arc::new(Notify::new()),
             experiments: Arc::new(RwLock::new(HashMap::new())),
-            http_client: reqwest::Client::new(),
+            http_client,
             last_polled: Arc::new(RwLock::new(
                 Utc.with_ymd_and_hms(2023, 01, 1, 0, 0, 0).unwrap(),
             )),
+        })
+    }
+
+    /// Builds a client with its store filled from `experiments`, for tests that
+    /// should not depend on an experiment server. Nothing is fetched unless
+    /// polling is started explicitly, so the store stays as given.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn with_experiments(config: Config, experiments: Experiments) -> Self {
+        let store = experiments
+            .into_iter()
+            .filter(|experiment| {
+                experiment.status != types::ExperimentStatusType::CONCLUDED
+            })
+            .map(|experiment| (experiment.id.clone(), StoredExperiment::from(experiment)))
+            .collect::<ExperimentStore>();
+        Client {
+            experiments: Arc::new(RwLock::new(store)),
+            last_polled: Arc::new(RwLock::new(Utc::now())),
+            ..Self::new(config).expect("failed to build the experiment http client")
         }
     }
 
     pub async fn run_polling_updates(self: Arc<Self>) {
         let poll_interval = self.client_config.poll_frequency;
-        let hostname = &self.client_config.hostname;
-        let mut interval = time::interval(Duration::from_secs(poll_interval));
-        let mut start_date = self.last_polled.write().await;
+        let period = Duration::from_secs(poll_interval);
+        // the first poll runs right away, so the first tick is a period out
+        let mut interval = time::interval_at(time::Instant::now() + period, period);
+        let mut consecutive_failures: u32 = 0;
+        let mut etag: Option<String> = None;
+        let mut force_refresh = self.force_refresh_rx.lock().await;
         loop {
-            // NOTE: this additional block scopes the write lock
-            // at the end of this block, the write lock on exp store is released
-            // allowing other threads to read updated data
+            if self.shutdown_requested.load(Ordering::SeqCst) {
+                log::info!("stopped polling experiments");
+                return;
+            }
+            if let Some(remaining) = self.with_circuit(|breaker| breaker.remaining_open())
             {
-                let experiments = get_experiments(
-                    hostname.clone(),
-                    self.http_client.clone(),
-                    start_date.to_string(),
-                    self.client_config.tenant.to_string(),
-                )
-                .await
-                .unwrap();
-
-                let mut exp_store = self.experiments.write().await;
-                for (exp_id, experiment) in experiments.into_iter() {
-                    match experiment.status {
-                        types::ExperimentStatusType::CONCLUDED => {
-                            exp_store.remove(&exp_id)
+                tokio::select! {
+                    _ = time::sleep(remaining) => interval.reset(),
+                    _ = self.shutdown.notified() => {}
+                }
+                continue;
+            }
+            // NOTE: the write lock on `last_polled` is held until the store is
+            // updated, so a concurrent `refresh_now` waits for this poll instead
+            // of interleaving its own updates with it
+            let mut last_polled = self.last_polled.write().await;
+            match self.fetch_experiments(&last_polled, etag.as_deref()).await {
+                Ok(FetchedExperiments::Updated {
+                    experiments,
+                    etag: new_etag,
+                }) => {
+                    consecutive_failures = 0;
+                    etag = new_etag;
+                    self.update_store(experiments).await;
+                }
+                // nothing changed since the last poll, the store is left as is
+                Ok(FetchedExperiments::NotModified) => {
+                    consecutive_failures = 0;
+                }
+                Err(err) => {
+                    drop(last_polled);
+                    consecutive_failures = consecutive_failures.saturating_add(1);
+                    if err.is_server_failure()
+                        && self.circuit_state() == CircuitState::Open
+                    {
+                        log::error!(
+                            "failed to fetch experiments: {err}, pausing polling for {}s",
+                            self.client_config.circuit_open_duration_secs
+                        );
+                        continue;
+                    }
+                    let backoff = Duration::from_secs(poll_interval)
+                        .saturating_mul(2u32.saturating_pow(consecutive_failures.min(16)))
+                        .min(MAX_BACKOFF);
+                    match &err {
+                        ClientError::HttpError(err) if err.is_timeout() => {
+                            log::warn!(
+                                "timed out fetching experiments, retrying in {backoff:?}"
+                            )
                         }
-                        _ => exp_store.insert(exp_id, experiment),
-                    };
+                        _ => log::error!(
+                            "failed to fetch experiments: {err}, retrying in {backoff:?}"
+                        ),
+                    }
+                    tokio::select! {
+                        _ = time::sleep(backoff) => interval.reset(),
+                        _ = self.shutdown.notified() => {}
+                    }
+                    continue;
                 }
-            } // write lock on exp store releases here
-            *start_date = Utc::now();
-            interval.tick().await;
+            }
+            *last_polled = Utc::now();
+            drop(last_polled);
+            tokio::select! {
+                _ = interval.tick() => {}
+                Some(()) = force_refresh.recv() => interval.reset(),
+                _ = self.shutdown.notified() => {}
+            }
         }
     }
 
-    pub async fn get_applicable_variant(&self, context: &Value, toss: i8) -> Vec<String> {
-        let experiments: Experiments = self.get_satisfied_experiments(context).await;
-        let mut variants: Vec<String> = Vec::new();
-        for exp in experiments {
-            if let Some(v) =
-                self.decide_variant(exp.traffic_percentage, exp.variants, toss)
-            {
-                variants.push(v.id)
+    /// Makes `run_polling_updates` return, waking it up if it is waiting for
+    /// the next poll. A fetch in flight is completed first.
+    pub fn notify_shutdown(&self) {
+        self.shutdown_requested.store(true, Ordering::SeqCst);
+        // stores a permit when the loop is not waiting, so the wake up is not lost
+        self.shutdown.notify_one();
+    }
+
+    /// Sending on this makes the polling loop fetch experiments right away
+    /// instead of waiting for the next tick, `try_send` never blocks.
+    pub fn force_refresh_sender(&self) -> &mpsc::Sender<()> {
+        &self.force_refresh_tx
+    }
+
+    /// Fetches experiments changed since the last poll right away, without
+    /// waiting for the polling loop, and returns how many of them were added
+    /// to, updated in or removed from the store.
+    pub async fn refresh_now(&self) -> Result<usize, ClientError> {
+        let mut last_polled = self.last_polled.write().await;
+        let updated = match self.fetch_experiments(&last_polled, None).await? {
+            FetchedExperiments::Updated { experiments, .. } => {
+                self.update_store(experiments).await
+            }
+            FetchedExperiments::NotModified => 0,
+        };
+        *last_polled = Utc::now();
+        Ok(updated)
+    }
+
+    async fn fetch_experiments(
+        &self,
+        start_date: &DateTime<Utc>,
+        etag: Option<&str>,
+    ) -> Result<FetchedExperiments, ClientError> {
+        let result = get_experiments(
+            self.client_config.hostname.clone(),
+            self.http_client.clone(),
+            start_date.to_string(),
+            self.client_config.tenant.to_string(),
+            etag,
+        )
+        .await;
+        match &result {
+            Ok(_) => self.with_circuit(CircuitBreaker::record_success),
+            Err(err) if err.is_server_failure() => {
+                self.with_circuit(CircuitBreaker::record_failure)
             }
+            Err(_) => (),
+        }
+        result
+    }
+
+    /// Applies fetched experiments to the store, dropping concluded ones, and
+    /// returns the number of experiments applied.
+    async fn update_store(&self, experiments: HashMap<String, Experiment>) -> usize {
+        let updated = experiments.len();
+        let mut exp_store = self.experiments.write().await;
+        for (exp_id, experiment) in experiments.into_iter() {
+            match experiment.status {
+                types::ExperimentStatusType::CONCLUDED => exp_store.remove(&exp_id),
+                _ => exp_store.insert(exp_id, StoredExperiment::from(experiment)),
+            };
+        }
+        metrics::set_active_experiments(&self.client_config.tenant, exp_store.len());
+        updated
+    }
+
+    /// State of the circuit breaker guarding calls to the experiment server.
+    pub fn circuit_state(&self) -> CircuitState {
+        self.with_circuit(|breaker| breaker.state())
+    }
+
+    fn with_circuit<T>(&self, f: impl FnOnce(&mut CircuitBreaker) -> T) -> T {
+        let mut breaker = self
+            .circuit_breaker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let result = f(&mut breaker);
+        metrics::set_circuit_state(&self.client_config.tenant, breaker.state());
+        result
+    }
+
+    pub async fn get_applicable_variant(&self, context: &Value, toss: i8) -> Vec<String> {
+        self.get_applicable_variant_filtered(context, toss, &ExperimentFilter::default())
+            .await
+    }
+
+    /// Same as `get_applicable_variant`, but experiments not passing `filter`
+    /// are skipped before their context is evaluated.
+    pub async fn get_applicable_variant_filtered(
+        &self,
+        context: &Value,
+        toss: i8,
+        filter: &ExperimentFilter,
+    ) -> Vec<String> {
+        let started_at = Instant::now();
+        let running_experiments = self.experiments.read().await;
+        let variants = running_experiments
+            .values()
+            .filter(|stored| filter.matches(&stored.experiment))
+            .filter(|stored| stored.matcher.matches(context))
+            .filter_map(|stored| stored.experiment.decide_variant(toss))
+            .map(|variant| variant.id.clone())
+            .collect::<Vec<String>>();
+        metrics::record_evaluation(&self.client_config.tenant, started_at, &variants);
+        if let Some(observe) = &self.client_config.observe {
+            observe(started_at.elapsed());
         }
         variants
     }
 
+    /// Evaluates several `(context, toss)` pairs under a single read lock on the
+    /// experiment store, returning the applicable variants for each in order.
+    pub async fn get_applicable_variant_batch(
+        &self,
+        requests: Vec<(Value, i8)>,
+    ) -> Vec<Vec<String>> {
+        let running_experiments = self.experiments.read().await;
+        requests
+            .into_iter()
+            .map(|(context, toss)| {
+                let started_at = Instant::now();
+                let variants = running_experiments
+                    .values()
+                    .filter(|stored| stored.matcher.matches(&context))
+                    .filter_map(|stored| stored.experiment.decide_variant(toss))
+                    .map(|variant| variant.id.clone())
+                    .collect::<Vec<String>>();
+                metrics::record_evaluation(
+                    &self.client_config.tenant,
+                    started_at,
+                    &variants,
+                );
+                variants
+            })
+            .collect()
+    }
+
     pub async fn get_satisfied_experiments(&self, context: &Value) -> Experiments {
         let running_experiments = self.experiments.read().await;
         running_experiments
-            .iter()
-            .filter(|(_, exp)| {
-                jsonlogic::apply(&exp.context, context) == Ok(Value::Bool(true))
-            })
-            .map(|(_, exp)| exp.clone())
+            .values()
+            .filter(|stored| stored.matcher.matches(context))
+            .map(|stored| stored.experiment.clone())
             .collect::<Experiments>()
     }
 
     pub async fn get_running_experiments(&self) -> Experiments {
         let running_experiments = self.experiments.read().await;
-        let experiments: Experiments = running_experiments.values().cloned().collect();
+        let experiments: Experiments = running_experiments
+            .values()
+            .map(|stored| stored.experiment.clone())
+            .collect();
         experiments
     }
+}
 
-    // decide which variant to return among all applicable experiments
-    fn decide_variant(
-        &self,
-        traffic: u8,
-        applicable_variants: Variants,
-        toss: i8,
-    ) -> Option<Variant> {
-        if toss < 0 {
-            for variant in applicable_variants.iter() {
-                if variant.variant_type == VariantType::EXPERIMENTAL {
-                    return Some(variant.clone());
-                }
-            }
-        }
-        let variant_count = applicable_variants.len() as u8;
-        let range = (traffic * variant_count) as i32;
-        if (toss as i32) >= range {
-            return None;
-        }
-        let buckets = (1..=variant_count)
-            .map(|i| (traffic * i) as i8)
-            .collect::<Vec<i8>>();
-        let index = buckets.into_iter().position(|x| toss < x);
-        applicable_variants.get(index.unwrap()).map(Variant::clone)
-    }
+enum FetchedExperiments {
+    /// the server answered `304 Not Modified` to the `If-None-Match` sent
+    NotModified,
+    Updated {
+        experiments: HashMap<String, Experiment>,
+        etag: Option<String>,
+    },
 }
 
 async fn get_experiments(
@@ -131,24 +350,46 @@ async fn get_experiments(
     http_client: reqwest::Client,
     start_date: String,
     tenant: String,
-) -> Result<ExperimentStore, String> {
-    let mut curr_exp_store: ExperimentStore = HashMap::new();
+    etag: Option<&str>,
+) -> Result<FetchedExperiments, ClientError> {
+    let mut curr_exp_store: HashMap<String, Experiment> = HashMap::new();
     let requesting_count = 10;
     let mut page = 1;
     let now = Utc::now();
+    let mut first_page_etag = None;
     loop {
         let endpoint = format!(
             "{hostname}/experiments?from_date={start_date}&to_date={now}&page={page}&count={requesting_count}"
         );
-        let list_experiments_response = http_client
+        let mut request = http_client
             .get(format!("{endpoint}&status=CREATED,INPROGRESS,CONCLUDED"))
-            .header("x-tenant", tenant.to_string())
-            .send()
-            .await
-            .unwrap()
-            .json::<ListExperimentsResponse>()
-            .await
-            .unwrap_or_default();
+            .header("x-tenant", tenant.to_string());
+        // any change to the list shows up on its first page
+        if let (1, Some(etag)) = (page, etag) {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(FetchedExperiments::NotModified);
+        }
+        if page == 1 {
+            first_page_etag = response
+                .headers()
+                .get(header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+        }
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(ClientError::ServerError {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        let list_experiments_response =
+            serde_json::from_str::<ListExperimentsResponse>(&body)?;
 
         let experiments = list_experiments_response.data;
         // println!("got these running experiments: {:?}", running_experiments);
@@ -163,43 +404,217 @@ async fn get_experiments(
         }
     }
 
-    Ok(curr_exp_store)
+    Ok(FetchedExperiments::Updated {
+        experiments: curr_exp_store,
+        etag: first_page_etag,
+    })
 }
 
-#[derive(Deref, DerefMut)]
+/// Holds one [`Client`] per tenant for applications serving several tenants.
+#[derive(Deref, DerefMut, Default)]
 pub struct ClientFactory(RwLock<HashMap<String, Arc<Client>>>);
 impl ClientFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub async fn create_client(
         &self,
         tenant: String,
         poll_frequency: u64,
         hostname: String,
-    ) -> Result<Arc<Client>, String> {
+    ) -> Result<Arc<Client>, ClientError> {
         let mut factory = self.write().await;
 
         if let Some(client) = factory.get(&tenant) {
             return Ok(client.clone());
         }
 
-        let client = Arc::new(Client::new(Config {
+        let config = Config {
             tenant: tenant.to_string(),
             hostname,
             poll_frequency,
-        }));
+            ..Default::default()
+        };
+        config.validate().map_err(ClientError::InvalidConfig)?;
+
+        let client = Arc::new(Client::new(config)?);
 
         factory.insert(tenant.to_string(), client.clone());
         Ok(client.clone())
     }
 
-    pub async fn get_client(&self, tenant: String) -> Result<Arc<Client>, String> {
+    pub async fn get_client(&self, tenant: String) -> Result<Arc<Client>, ClientError> {
         let factory = self.read().await;
         match factory.get(&tenant) {
             Some(client) => Ok(client.clone()),
-            None => Err("No such tenant found".to_string()),
+            None => Err(ClientError::TenantNotFound(tenant)),
         }
     }
+
+    /// Returns the client for `tenant`, building it from `config_fn` and
+    /// spawning its polling loop onto the current tokio runtime the first time
+    /// the tenant is seen.
+    pub async fn get_or_create<F>(
+        &self,
+        tenant: &str,
+        config_fn: F,
+    ) -> Result<Arc<Client>, ClientError>
+    where
+        F: FnOnce() -> Config,
+    {
+        let mut factory = self.write().await;
+        if let Some(client) = factory.get(tenant) {
+            return Ok(client.clone());
+        }
+
+        let config = config_fn();
+        config.validate().map_err(ClientError::InvalidConfig)?;
+        let client = Arc::new(Client::new(config)?);
+        tokio::spawn(client.clone().run_polling_updates());
+
+        factory.insert(tenant.to_string(), client.clone());
+        Ok(client)
+    }
+
+    /// Removes the client for `tenant` and stops its polling loop, returning
+    /// the evicted client if there was one.
+    pub async fn evict(&self, tenant: &str) -> Option<Arc<Client>> {
+        let client = self.write().await.remove(tenant)?;
+        client.notify_shutdown();
+        Some(client)
+    }
+}
+
+impl Drop for ClientFactory {
+    fn drop(&mut self) {
+        self.get_mut()
+            .values()
+            .for_each(|client| client.notify_shutdown());
+    }
 }
 
 use once_cell::sync::Lazy;
-pub static CLIENT_FACTORY: Lazy<ClientFactory> =
-    Lazy::new(|| ClientFactory(RwLock::new(HashMap::new())));
+pub static CLIENT_FACTORY: Lazy<ClientFactory> = Lazy::new(ClientFactory::new);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_experiments_serves_given_store() {
+        let experiments: Experiments = serde_json::from_value(json!([
+            {
+                "id": "1",
+                "name": "experiment-1",
+                "status": "INPROGRESS",
+                "traffic_percentage": 10,
+                "context": { "==": [{ "var": "os" }, "android"] },
+                "variants": [
+                    { "id": "1-control", "variant_type": "CONTROL", "overrides": {} },
+                    { "id": "1-experimental", "variant_type": "EXPERIMENTAL", "overrides": {} }
+                ]
+            },
+            {
+                "id": "2",
+                "name": "experiment-2",
+                "status": "CONCLUDED",
+                "traffic_percentage": 10,
+                "context": { "==": [{ "var": "os" }, "android"] },
+                "variants": []
+            }
+        ]))
+        .unwrap();
+        let client = Client::with_experiments(
+            Config {
+                tenant: "test".to_string(),
+                hostname: "http://localhost:1".to_string(),
+                poll_frequency: 60,
+                ..Default::default()
+            },
+            experiments,
+        );
+
+        assert_eq!(client.get_running_experiments().await.len(), 1);
+        assert_eq!(
+            client
+                .get_applicable_variant(&json!({ "os": "android" }), 15)
+                .await,
+            vec!["1-experimental".to_string()]
+        );
+        assert!(client
+            .get_applicable_variant(&json!({ "os": "ios" }), 5)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_applicable_variant_filtered_skips_filtered_out_experiments() {
+        let experiments: Experiments = serde_json::from_value(json!([
+            {
+                "id": "1",
+                "name": "experiment-1",
+                "status": "INPROGRESS",
+                "traffic_percentage": 50,
+                "context": { "==": [{ "var": "os" }, "android"] },
+                "override_keys": ["checkout.flow"],
+                "namespace": "payments",
+                "variants": [
+                    { "id": "1-control", "variant_type": "CONTROL", "overrides": {} }
+                ]
+            },
+            {
+                "id": "2",
+                "name": "experiment-2",
+                "status": "INPROGRESS",
+                "traffic_percentage": 50,
+                "context": { "==": [{ "var": "os" }, "android"] },
+                "override_keys": ["home.banner"],
+                "variants": [
+                    { "id": "2-control", "variant_type": "CONTROL", "overrides": {} }
+                ]
+            }
+        ]))
+        .unwrap();
+        let client = Client::with_experiments(
+            Config {
+                tenant: "test".to_string(),
+                hostname: "http://localhost:1".to_string(),
+                poll_frequency: 60,
+                ..Default::default()
+            },
+            experiments,
+        );
+        let context = json!({ "os": "android" });
+
+        let by_namespace = ExperimentFilter {
+            namespaces: Some(vec!["payments".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            client
+                .get_applicable_variant_filtered(&context, 5, &by_namespace)
+                .await,
+            vec!["1-control".to_string()]
+        );
+
+        let by_override_key = ExperimentFilter {
+            override_keys: Some(vec!["home.banner".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            client
+                .get_applicable_variant_filtered(&context, 5, &by_override_key)
+                .await,
+            vec!["2-control".to_string()]
+        );
+
+        let mut unfiltered = client
+            .get_applicable_variant_filtered(&context, 5, &ExperimentFilter::default())
+            .await;
+        unfiltered.sort();
+        assert_eq!(unfiltered, vec!["1-control", "2-control"]);
+    }
+}
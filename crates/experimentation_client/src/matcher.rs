@@ -0,0 +1,398 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+/// Operators understood by `jsonlogic`. A condition using anything else is
+/// rejected by `jsonlogic::apply` as a whole, so it is never compiled.
+const JSONLOGIC_OPERATORS: [&str; 34] = [
+    "var",
+    "missing",
+    "missing_some",
+    "if",
+    "?:",
+    "==",
+    "===",
+    "!=",
+    "!==",
+    "!",
+    "!!",
+    "or",
+    "and",
+    ">",
+    ">=",
+    "<",
+    "<=",
+    "max",
+    "min",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "map",
+    "filter",
+    "reduce",
+    "all",
+    "none",
+    "some",
+    "merge",
+    "in",
+    "cat",
+    "substr",
+];
+
+/// A JSON-logic condition compiled once so that it can be evaluated against
+/// many contexts without re-parsing the condition every time.
+///
+/// Only the operators experiment contexts are built from are compiled. Other
+/// operators, and operands whose types need JavaScript coercion rules, are
+/// handed over to `jsonlogic::apply`, so a match always agrees with
+/// `jsonlogic::apply(condition, context) == Ok(Value::Bool(true))`.
+#[derive(Clone, Debug)]
+pub struct ContextMatcher {
+    root: Node,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Literal(Value),
+    Var {
+        path: Vec<String>,
+        default: Option<Box<Node>>,
+        raw: Value,
+    },
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+    Truthy(Box<Node>),
+    Compare {
+        op: CompareOp,
+        args: Vec<Node>,
+        raw: Value,
+    },
+    In {
+        needle: Box<Node>,
+        haystack: Box<Node>,
+        raw: Value,
+    },
+    /// evaluated by `jsonlogic::apply` on every call
+    Fallback(Value),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CompareOp {
+    Eq,
+    NotEq,
+    StrictEq,
+    StrictNotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+type EvalResult<'a> = Result<Cow<'a, Value>, String>;
+
+impl ContextMatcher {
+    pub fn new(condition: &Value) -> Self {
+        let root = if uses_unknown_operator(condition) {
+            Node::Fallback(condition.clone())
+        } else {
+            compile(condition)
+        };
+        ContextMatcher { root }
+    }
+
+    /// Returns true when the condition evaluates to `true` for `context`.
+    pub fn matches(&self, context: &Value) -> bool {
+        matches!(self.root.eval(context).as_deref(), Ok(Value::Bool(true)))
+    }
+
+    /// Whether any part of the condition is left to `jsonlogic::apply`.
+    pub fn is_fully_compiled(&self) -> bool {
+        self.root.is_compiled()
+    }
+}
+
+fn uses_unknown_operator(condition: &Value) -> bool {
+    match condition {
+        Value::Object(map) => map.iter().any(|(op, args)| {
+            !JSONLOGIC_OPERATORS.contains(&op.as_str()) || uses_unknown_operator(args)
+        }),
+        Value::Array(values) => values.iter().any(uses_unknown_operator),
+        _ => false,
+    }
+}
+
+fn compile(condition: &Value) -> Node {
+    match condition {
+        Value::Object(map) => {
+            compile_operation(map).unwrap_or_else(|| Node::Fallback(condition.clone()))
+        }
+        Value::Array(values) if values.iter().all(is_plain_data) => {
+            Node::Literal(condition.clone())
+        }
+        Value::Array(_) => Node::Fallback(condition.clone()),
+        value => Node::Literal(value.clone()),
+    }
+}
+
+/// Arrays of plain data evaluate to themselves, while objects anywhere in them
+/// are operations.
+fn is_plain_data(value: &Value) -> bool {
+    match value {
+        Value::Object(_) => false,
+        Value::Array(values) => values.iter().all(is_plain_data),
+        _ => true,
+    }
+}
+
+fn compile_operation(map: &Map<String, Value>) -> Option<Node> {
+    if map.len() != 1 {
+        return None;
+    }
+    let (op, args) = map.iter().next()?;
+    let raw = Value::Object(map.clone());
+    let node = match (op.as_str(), args) {
+        ("var", Value::String(path)) => Node::Var {
+            path: split_path(path),
+            default: None,
+            raw,
+        },
+        ("var", Value::Array(args)) => match args.as_slice() {
+            [Value::String(path)] => Node::Var {
+                path: split_path(path),
+                default: None,
+                raw,
+            },
+            [Value::String(path), default] => Node::Var {
+                path: split_path(path),
+                default: Some(Box::new(compile(default))),
+                raw,
+            },
+            _ => return None,
+        },
+        ("and", Value::Array(args)) if !args.is_empty() => {
+            Node::And(args.iter().map(compile).collect())
+        }
+        ("or", Value::Array(args)) if !args.is_empty() => {
+            Node::Or(args.iter().map(compile).collect())
+        }
+        ("!", args) => Node::Not(Box::new(compile(single_arg(args)?))),
+        ("!!", args) => Node::Truthy(Box::new(compile(single_arg(args)?))),
+        ("in", Value::Array(args)) => match args.as_slice() {
+            [needle, haystack] => Node::In {
+                needle: Box::new(compile(needle)),
+                haystack: Box::new(compile(haystack)),
+                raw,
+            },
+            _ => return None,
+        },
+        (op, Value::Array(args)) => {
+            let op = match (op, args.len()) {
+                ("==", 2) => CompareOp::Eq,
+                ("!=", 2) => CompareOp::NotEq,
+                ("===", 2) => CompareOp::StrictEq,
+                ("!==", 2) => CompareOp::StrictNotEq,
+                ("<", 2 | 3) => CompareOp::Lt,
+                ("<=", 2 | 3) => CompareOp::Le,
+                (">", 2) => CompareOp::Gt,
+                (">=", 2) => CompareOp::Ge,
+                _ => return None,
+            };
+            Node::Compare {
+                op,
+                args: args.iter().map(compile).collect(),
+                raw,
+            }
+        }
+        _ => return None,
+    };
+    Some(node)
+}
+
+fn single_arg(args: &Value) -> Option<&Value> {
+    match args {
+        Value::Array(args) if args.len() == 1 => args.first(),
+        _ => None,
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    if path.is_empty() {
+        Vec::new()
+    } else {
+        path.split('.').map(String::from).collect()
+    }
+}
+
+impl Node {
+    fn eval<'a>(&'a self, data: &'a Value) -> EvalResult<'a> {
+        match self {
+            Node::Literal(value) => Ok(Cow::Borrowed(value)),
+            Node::Var { path, default, raw } => match lookup(data, path) {
+                Some(Value::Null) if default.is_some() => fallback(raw, data),
+                Some(value) => Ok(Cow::Borrowed(value)),
+                None => match default {
+                    Some(default) => default.eval(data),
+                    None => Ok(Cow::Owned(Value::Null)),
+                },
+            },
+            Node::And(args) => {
+                let mut last = Cow::Owned(Value::Null);
+                for arg in args {
+                    last = arg.eval(data)?;
+                    if !truthy(&last) {
+                        break;
+                    }
+                }
+                Ok(last)
+            }
+            Node::Or(args) => {
+                let mut last = Cow::Owned(Value::Null);
+                for arg in args {
+                    last = arg.eval(data)?;
+                    if truthy(&last) {
+                        break;
+                    }
+                }
+                Ok(last)
+            }
+            Node::Not(arg) => {
+                Ok(Cow::Owned(Value::Bool(!truthy(arg.eval(data)?.as_ref()))))
+            }
+            Node::Truthy(arg) => {
+                Ok(Cow::Owned(Value::Bool(truthy(arg.eval(data)?.as_ref()))))
+            }
+            Node::Compare { op, args, raw } => {
+                let values = args
+                    .iter()
+                    .map(|arg| arg.eval(data))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match compare(*op, &values) {
+                    Some(result) => Ok(Cow::Owned(Value::Bool(result))),
+                    None => fallback(raw, data),
+                }
+            }
+            Node::In {
+                needle,
+                haystack,
+                raw,
+            } => {
+                let needle = needle.eval(data)?;
+                let haystack = haystack.eval(data)?;
+                match (needle.as_ref(), haystack.as_ref()) {
+                    (Value::String(needle), Value::String(haystack)) => {
+                        Ok(Cow::Owned(Value::Bool(haystack.contains(needle.as_str()))))
+                    }
+                    (needle, Value::Array(haystack)) if is_primitive(needle) => {
+                        match contains(haystack, needle) {
+                            Some(found) => Ok(Cow::Owned(Value::Bool(found))),
+                            None => fallback(raw, data),
+                        }
+                    }
+                    _ => fallback(raw, data),
+                }
+            }
+            Node::Fallback(raw) => fallback(raw, data),
+        }
+    }
+
+    fn is_compiled(&self) -> bool {
+        match self {
+            Node::Literal(_) => true,
+            Node::Var { default, .. } => default
+                .as_ref()
+                .map_or(true, |default| default.is_compiled()),
+            Node::And(args) | Node::Or(args) | Node::Compare { args, .. } => {
+                args.iter().all(Node::is_compiled)
+            }
+            Node::Not(arg) | Node::Truthy(arg) => arg.is_compiled(),
+            Node::In {
+                needle, haystack, ..
+            } => needle.is_compiled() && haystack.is_compiled(),
+            Node::Fallback(_) => false,
+        }
+    }
+}
+
+fn fallback<'a>(raw: &Value, data: &Value) -> EvalResult<'a> {
+    jsonlogic::apply(raw, data).map(Cow::Owned)
+}
+
+fn lookup<'a>(data: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(data, |current, segment| match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(values) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| values.get(index)),
+            _ => None,
+        })
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().map_or(false, |n| n != 0.0),
+        Value::String(value) => !value.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn is_primitive(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// `===` for primitives. `None` when either side is an array or object, or
+/// when an integer meets a float, since those are compared by `jsonlogic`.
+fn strict_eq(a: &Value, b: &Value) -> Option<bool> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) if a.is_f64() != b.is_f64() => None,
+        (a, b) if is_primitive(a) && is_primitive(b) => Some(a == b),
+        _ => None,
+    }
+}
+
+/// Looks up a primitive `needle` in `haystack`, `None` when some item cannot
+/// be compared without `jsonlogic`.
+fn contains(haystack: &[Value], needle: &Value) -> Option<bool> {
+    let mut found = false;
+    for item in haystack.iter().filter(|item| is_primitive(item)) {
+        found |= strict_eq(needle, item)?;
+    }
+    Some(found)
+}
+
+/// Evaluates comparisons whose outcome does not depend on type coercion,
+/// returning `None` for everything else.
+fn compare(op: CompareOp, values: &[Cow<'_, Value>]) -> Option<bool> {
+    match (op, values) {
+        (CompareOp::StrictEq, [a, b]) => strict_eq(a, b),
+        (CompareOp::StrictNotEq, [a, b]) => strict_eq(a, b).map(|eq| !eq),
+        // loose and strict equality only differ across types
+        (CompareOp::Eq, [a, b]) if same_type(a, b) => strict_eq(a, b),
+        (CompareOp::NotEq, [a, b]) if same_type(a, b) => strict_eq(a, b).map(|eq| !eq),
+        (CompareOp::Eq | CompareOp::NotEq, _) => None,
+        (op, values) => {
+            let numbers = values
+                .iter()
+                .map(|value| value.as_f64())
+                .collect::<Option<Vec<f64>>>()?;
+            let ordered = |a: f64, b: f64| match op {
+                CompareOp::Lt => a < b,
+                CompareOp::Le => a <= b,
+                CompareOp::Gt => a > b,
+                _ => a >= b,
+            };
+            Some(numbers.windows(2).all(|pair| ordered(pair[0], pair[1])))
+        }
+    }
+}
+
+fn same_type(a: &Value, b: &Value) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
@@ -0,0 +1,35 @@
+use std::time::Instant;
+
+use ::metrics::{counter, gauge, histogram};
+
+use crate::circuit_breaker::CircuitState;
+
+const EVALUATION_DURATION: &str = "experiment_evaluation_duration_seconds";
+const ACTIVE_EXPERIMENTS: &str = "active_experiments";
+const VARIANT_ASSIGNMENTS: &str = "variant_assignments_total";
+const CIRCUIT_STATE: &str = "experiment_server_circuit_state";
+
+// These go through the `metrics` facade, so they are no-ops unless the
+// application has installed a recorder such as `metrics-exporter-prometheus`.
+
+pub(crate) fn record_evaluation(tenant: &str, started_at: Instant, variants: &[String]) {
+    histogram!(EVALUATION_DURATION, "tenant" => tenant.to_string())
+        .record(started_at.elapsed().as_secs_f64());
+    for variant_id in variants {
+        counter!(
+            VARIANT_ASSIGNMENTS,
+            "tenant" => tenant.to_string(),
+            "variant_id" => variant_id.clone()
+        )
+        .increment(1);
+    }
+}
+
+pub(crate) fn set_active_experiments(tenant: &str, count: usize) {
+    gauge!(ACTIVE_EXPERIMENTS, "tenant" => tenant.to_string()).set(count as f64);
+}
+
+/// Reported as 0 when closed, 1 when half open and 2 when open.
+pub(crate) fn set_circuit_state(tenant: &str, state: CircuitState) {
+    gauge!(CIRCUIT_STATE, "tenant" => tenant.to_string()).set(state.as_gauge());
+}
@@ -1,52 +1,281 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, env, fmt, str::FromStr, sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
-#[derive(Clone, Debug)]
+use crate::matcher::ContextMatcher;
+
+pub const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 2000;
+pub const DEFAULT_READ_TIMEOUT_MS: u64 = 5000;
+pub const DEFAULT_CIRCUIT_OPEN_DURATION_SECS: u64 = 60;
+pub const DEFAULT_POLL_FREQUENCY_SECS: u64 = 10;
+
+/// Called with the time taken by each `get_applicable_variant` call.
+pub type ObserveFn = Arc<dyn Fn(Duration) + Send + Sync>;
+
+#[derive(Clone)]
 pub struct Config {
     pub tenant: String,
     pub hostname: String,
     pub poll_frequency: u64,
+    pub connect_timeout_ms: u64,
+    pub read_timeout_ms: u64,
+    /// how long polling pauses once the experiment server keeps failing
+    pub circuit_open_duration_secs: u64,
+    pub observe: Option<ObserveFn>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
-pub(crate) enum ExperimentStatusType {
-    CREATED,
-    INPROGRESS,
-    CONCLUDED,
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("tenant", &self.tenant)
+            .field("hostname", &self.hostname)
+            .field("poll_frequency", &self.poll_frequency)
+            .field("connect_timeout_ms", &self.connect_timeout_ms)
+            .field("read_timeout_ms", &self.read_timeout_ms)
+            .field(
+                "circuit_open_duration_secs",
+                &self.circuit_open_duration_secs,
+            )
+            .field("observe", &self.observe.as_ref().map(|_| "Fn(Duration)"))
+            .finish()
+    }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
-pub(crate) enum VariantType {
-    CONTROL,
-    EXPERIMENTAL,
+/// Uses the default poll frequency and timeouts, so that struct literals only
+/// need to set `tenant` and `hostname` and can take the rest with
+/// `..Default::default()`.
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            tenant: String::new(),
+            hostname: String::new(),
+            poll_frequency: DEFAULT_POLL_FREQUENCY_SECS,
+            connect_timeout_ms: DEFAULT_CONNECT_TIMEOUT_MS,
+            read_timeout_ms: DEFAULT_READ_TIMEOUT_MS,
+            circuit_open_duration_secs: DEFAULT_CIRCUIT_OPEN_DURATION_SECS,
+            observe: None,
+        }
+    }
 }
 
-#[repr(C)]
-#[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct Variant {
-    pub id: String,
-    pub overrides: Value,
-    pub(crate) variant_type: VariantType,
+impl Config {
+    /// Checks the config up front, so that a bad hostname or a zero poll
+    /// frequency is reported on startup instead of failing silently later.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        match reqwest::Url::parse(&self.hostname) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => (),
+            Ok(url) => errors.push(ConfigError::InvalidHostname(format!(
+                "unsupported scheme {}",
+                url.scheme()
+            ))),
+            Err(err) => errors.push(ConfigError::InvalidHostname(err.to_string())),
+        }
+        if self.poll_frequency == 0 {
+            errors.push(ConfigError::ZeroPollFrequency);
+        }
+        if self.connect_timeout_ms == 0 {
+            errors.push(ConfigError::ZeroTimeout("connect_timeout_ms"));
+        }
+        if self.read_timeout_ms == 0 {
+            errors.push(ConfigError::ZeroTimeout("read_timeout_ms"));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
 }
 
-pub type Variants = Vec<Variant>;
+/// Builds a [`Config`], falling back to the default timeouts when they are not
+/// set.
+#[derive(Clone, Default)]
+pub struct ConfigBuilder {
+    tenant: Option<String>,
+    hostname: Option<String>,
+    poll_frequency: Option<u64>,
+    connect_timeout_ms: Option<u64>,
+    read_timeout_ms: Option<u64>,
+    circuit_open_duration_secs: Option<u64>,
+    observe: Option<ObserveFn>,
+    env_prefix: Option<String>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    pub fn hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    pub fn poll_frequency(mut self, poll_frequency: u64) -> Self {
+        self.poll_frequency = Some(poll_frequency);
+        self
+    }
 
-#[repr(C)]
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Experiment {
-    pub variants: Variants,
-    pub(crate) name: String,
-    pub(crate) id: String,
-    pub(crate) traffic_percentage: u8,
-    pub(crate) context: Value,
-    pub(crate) status: ExperimentStatusType,
+    pub fn connect_timeout_ms(mut self, connect_timeout_ms: u64) -> Self {
+        self.connect_timeout_ms = Some(connect_timeout_ms);
+        self
+    }
+
+    pub fn read_timeout_ms(mut self, read_timeout_ms: u64) -> Self {
+        self.read_timeout_ms = Some(read_timeout_ms);
+        self
+    }
+
+    pub fn circuit_open_duration_secs(mut self, circuit_open_duration_secs: u64) -> Self {
+        self.circuit_open_duration_secs = Some(circuit_open_duration_secs);
+        self
+    }
+
+    pub fn observe(mut self, observe: ObserveFn) -> Self {
+        self.observe = Some(observe);
+        self
+    }
+
+    /// Makes `build` read `{prefix}_TENANT`, `{prefix}_HOSTNAME`,
+    /// `{prefix}_POLL_FREQUENCY`, `{prefix}_CONNECT_TIMEOUT_MS`,
+    /// `{prefix}_READ_TIMEOUT_MS` and `{prefix}_CIRCUIT_OPEN_DURATION_SECS`,
+    /// which take precedence over the values set on the builder.
+    pub fn with_env_overrides(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn build(mut self) -> Result<Config, Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        if let Some(prefix) = self.env_prefix.take() {
+            let errors = &mut errors;
+            self.tenant = env_override(&prefix, "TENANT", errors).or(self.tenant);
+            self.hostname = env_override(&prefix, "HOSTNAME", errors).or(self.hostname);
+            self.poll_frequency =
+                env_override(&prefix, "POLL_FREQUENCY", errors).or(self.poll_frequency);
+            self.connect_timeout_ms = env_override(&prefix, "CONNECT_TIMEOUT_MS", errors)
+                .or(self.connect_timeout_ms);
+            self.read_timeout_ms =
+                env_override(&prefix, "READ_TIMEOUT_MS", errors).or(self.read_timeout_ms);
+            self.circuit_open_duration_secs =
+                env_override(&prefix, "CIRCUIT_OPEN_DURATION_SECS", errors)
+                    .or(self.circuit_open_duration_secs);
+        }
+
+        let missing = [
+            ("tenant", self.tenant.is_none()),
+            ("hostname", self.hostname.is_none()),
+            ("poll_frequency", self.poll_frequency.is_none()),
+        ];
+        errors.extend(
+            missing
+                .into_iter()
+                .filter(|(_, missing)| *missing)
+                .map(|(field, _)| ConfigError::MissingField(field)),
+        );
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let config = Config {
+            tenant: self.tenant.unwrap_or_default(),
+            hostname: self.hostname.unwrap_or_default(),
+            poll_frequency: self.poll_frequency.unwrap_or_default(),
+            connect_timeout_ms: self
+                .connect_timeout_ms
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT_MS),
+            read_timeout_ms: self.read_timeout_ms.unwrap_or(DEFAULT_READ_TIMEOUT_MS),
+            circuit_open_duration_secs: self
+                .circuit_open_duration_secs
+                .unwrap_or(DEFAULT_CIRCUIT_OPEN_DURATION_SECS),
+            observe: self.observe,
+        };
+        config.validate()?;
+        Ok(config)
+    }
 }
 
-pub type Experiments = Vec<Experiment>;
+/// Reads `{prefix}_{name}`, recording an error when it is set but unparsable.
+fn env_override<T: FromStr>(
+    prefix: &str,
+    name: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<T> {
+    let var = format!("{prefix}_{name}");
+    let value = env::var(&var).ok()?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            errors.push(ConfigError::InvalidEnvVar { var, value });
+            None
+        }
+    }
+}
 
-pub(crate) type ExperimentStore = HashMap<String, Experiment>;
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    #[error("hostname is not a valid url: {0}")]
+    InvalidHostname(String),
+    #[error("poll_frequency should be greater than 0")]
+    ZeroPollFrequency,
+    #[error("{0} should be greater than 0")]
+    ZeroTimeout(&'static str),
+    #[error("{0} is not set")]
+    MissingField(&'static str),
+    #[error("{var} has an invalid value {value}")]
+    InvalidEnvVar { var: String, value: String },
+}
+
+pub(crate) use superposition_core::ExperimentStatusType;
+pub use superposition_core::{Experiment, Experiments, Variants};
+
+/// Narrows down the experiments considered by
+/// `Client::get_applicable_variant_filtered`. A `None` field does not filter.
+#[derive(Clone, Debug, Default)]
+pub struct ExperimentFilter {
+    pub namespaces: Option<Vec<String>>,
+    /// experiments overriding at least one of these keys pass the filter
+    pub override_keys: Option<Vec<String>>,
+}
+
+impl ExperimentFilter {
+    pub(crate) fn matches(&self, experiment: &Experiment) -> bool {
+        let namespace_matches = self.namespaces.as_ref().map_or(true, |namespaces| {
+            namespaces.contains(&experiment.namespace)
+        });
+        let override_keys_match = self.override_keys.as_ref().map_or(true, |keys| {
+            experiment
+                .override_keys
+                .iter()
+                .any(|key| keys.contains(key))
+        });
+        namespace_matches && override_keys_match
+    }
+}
+
+/// An experiment along with its context compiled for evaluation.
+#[derive(Clone, Debug)]
+pub(crate) struct StoredExperiment {
+    pub(crate) experiment: Experiment,
+    pub(crate) matcher: ContextMatcher,
+}
+
+impl From<Experiment> for StoredExperiment {
+    fn from(experiment: Experiment) -> Self {
+        StoredExperiment {
+            matcher: ContextMatcher::new(&experiment.context),
+            experiment,
+        }
+    }
+}
+
+pub(crate) type ExperimentStore = HashMap<String, StoredExperiment>;
 
 #[derive(Serialize, Deserialize, Default)]
 pub(crate) struct ListExperimentsResponse {
@@ -54,3 +283,32 @@ pub(crate) struct ListExperimentsResponse {
     pub(crate) total_pages: i64,
     pub(crate) data: Experiments,
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClientError {
+    #[error("http request failed: {0}")]
+    HttpError(#[from] reqwest::Error),
+    #[error("failed to parse response: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("server returned {status}: {body}")]
+    ServerError { status: u16, body: String },
+    #[error("no client found for tenant {0}")]
+    TenantNotFound(String),
+    #[error(
+        "invalid client config: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    InvalidConfig(Vec<ConfigError>),
+}
+
+impl ClientError {
+    /// Whether the error points at the experiment server being unreachable or
+    /// unhealthy, as opposed to a problem with the request or response.
+    pub(crate) fn is_server_failure(&self) -> bool {
+        match self {
+            ClientError::HttpError(_) => true,
+            ClientError::ServerError { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
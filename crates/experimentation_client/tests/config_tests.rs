@@ -0,0 +1,56 @@
+use std::env;
+
+use experimentation_client::{ConfigBuilder, ConfigError, DEFAULT_READ_TIMEOUT_MS};
+
+#[test]
+fn test_config_builder_applies_defaults() {
+    let config = ConfigBuilder::new()
+        .tenant("test")
+        .hostname("http://localhost:8080")
+        .poll_frequency(10)
+        .build()
+        .unwrap();
+    assert_eq!(config.tenant, "test");
+    assert_eq!(config.read_timeout_ms, DEFAULT_READ_TIMEOUT_MS);
+
+    let errors = ConfigBuilder::new().tenant("test").build().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ConfigError::MissingField("hostname"),
+            ConfigError::MissingField("poll_frequency")
+        ]
+    );
+}
+
+#[test]
+fn test_config_builder_env_overrides_take_precedence() {
+    env::set_var("EXP_ENV_TEST_HOSTNAME", "http://experiments.internal");
+    env::set_var("EXP_ENV_TEST_POLL_FREQUENCY", "30");
+    let config = ConfigBuilder::new()
+        .tenant("test")
+        .hostname("http://localhost:8080")
+        .poll_frequency(10)
+        .with_env_overrides("EXP_ENV_TEST")
+        .build()
+        .unwrap();
+    assert_eq!(config.tenant, "test");
+    assert_eq!(config.hostname, "http://experiments.internal");
+    assert_eq!(config.poll_frequency, 30);
+
+    env::set_var("EXP_INVALID_ENV_TEST_READ_TIMEOUT_MS", "soon");
+    let errors = ConfigBuilder::new()
+        .tenant("test")
+        .hostname("http://localhost:8080")
+        .poll_frequency(10)
+        .with_env_overrides("EXP_INVALID_ENV_TEST")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![ConfigError::InvalidEnvVar {
+            var: "EXP_INVALID_ENV_TEST_READ_TIMEOUT_MS".to_string(),
+            value: "soon".to_string()
+        }]
+    );
+}
@@ -0,0 +1,113 @@
+use experimentation_client::ContextMatcher;
+use serde_json::{json, Value};
+
+fn conditions() -> Vec<Value> {
+    vec![
+        json!({ "==": [{ "var": "os" }, "android"] }),
+        json!({ "!=": [{ "var": "os" }, "android"] }),
+        json!({ "===": [{ "var": "version" }, 2] }),
+        json!({
+            "and": [
+                { "==": [{ "var": "city" }, "Bangalore"] },
+                { "in": ["v1", { "var": "variantIds" }] }
+            ]
+        }),
+        json!({
+            "or": [
+                { "<": [10, { "var": "version" }, 20] },
+                { ">=": [{ "var": "user.age" }, 18] }
+            ]
+        }),
+        json!({ "in": [{ "var": "city" }, ["Bangalore", "Chennai"]] }),
+        json!({ "in": ["gal", { "var": "city" }] }),
+        json!({ "!": [{ "var": "beta" }] }),
+        json!({ "!!": [{ "var": "variantIds" }] }),
+        json!({ "==": [{ "var": ["missing", "default"] }, "default"] }),
+        json!({ "and": [{ "var": "city" }, { "var": "os" }] }),
+        // coercing comparisons are left to jsonlogic
+        json!({ "==": [{ "var": "version" }, "2"] }),
+        json!({ "<": [{ "var": "os" }, "b"] }),
+        json!({ "==": [{ "cat": [{ "var": "os" }, "-x"] }, "android-x"] }),
+    ]
+}
+
+fn contexts() -> Vec<Value> {
+    vec![
+        json!({}),
+        json!({ "os": "android", "version": 2, "city": "Bangalore", "variantIds": ["v1"] }),
+        json!({ "os": "ios", "version": 15, "city": "Chennai", "variantIds": [] }),
+        json!({ "os": "android", "version": "2", "user": { "age": 21 }, "beta": false }),
+        json!({ "os": null, "version": 2.0, "city": "Delhi", "beta": true, "missing": null }),
+    ]
+}
+
+#[test]
+fn test_matcher_agrees_with_jsonlogic() {
+    for condition in conditions() {
+        let matcher = ContextMatcher::new(&condition);
+        for context in contexts() {
+            assert_eq!(
+                matcher.matches(&context),
+                jsonlogic::apply(&condition, &context) == Ok(Value::Bool(true)),
+                "condition {condition} on context {context}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_experiment_contexts_are_fully_compiled() {
+    let condition = json!({
+        "and": [
+            { "==": [{ "var": "city" }, "Bangalore"] },
+            { "in": ["v1", { "var": "variantIds" }] }
+        ]
+    });
+    assert!(ContextMatcher::new(&condition).is_fully_compiled());
+
+    let condition = json!({ "==": [{ "cat": [{ "var": "os" }, "-x"] }, "android-x"] });
+    assert!(!ContextMatcher::new(&condition).is_fully_compiled());
+}
+
+/// Contexts shaped the way the experimentation platform builds them: one
+/// condition per dimension, with either operand order, joined by `and` and
+/// extended with the `variantIds` condition of a variant.
+fn experiment_conditions() -> Vec<Value> {
+    let os = json!({ "==": [{ "var": "os" }, "android"] });
+    let client = json!({ "==": ["testClient", { "var": "clientId" }] });
+    let variant =
+        json!({ "in": ["7259558160762015744-control", { "var": "variantIds" }] });
+    vec![
+        os.clone(),
+        client.clone(),
+        json!({ "and": [os.clone(), client.clone()] }),
+        json!({ "and": [os.clone(), variant.clone()] }),
+        json!({ "and": [os, client, variant] }),
+    ]
+}
+
+#[test]
+fn test_matcher_agrees_with_jsonlogic_on_experiment_contexts() {
+    let experiment_contexts = vec![
+        json!({ "os": "android", "clientId": "testClient" }),
+        json!({ "os": "ios", "clientId": "testClient" }),
+        json!({
+            "os": "android",
+            "clientId": "testClient",
+            "variantIds": ["7259558160762015744-control"]
+        }),
+        json!({ "os": "android", "clientId": "otherClient", "variantIds": [] }),
+        json!({ "clientId": 42, "variantIds": "7259558160762015744-control" }),
+    ];
+    for condition in experiment_conditions() {
+        let matcher = ContextMatcher::new(&condition);
+        assert!(matcher.is_fully_compiled(), "condition {condition}");
+        for context in experiment_contexts.iter().chain(contexts().iter()) {
+            assert_eq!(
+                matcher.matches(context),
+                jsonlogic::apply(&condition, context) == Ok(Value::Bool(true)),
+                "condition {condition} on context {context}"
+            );
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use experimentation_client::{Client, ClientFactory, Config};
+use serde_json::{json, Value};
+use tokio::{task, time};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn experiment_gen(id: &str, status: &str, traffic_percentage: u8) -> Value {
+    json!({
+        "id": id,
+        "name": format!("experiment-{id}"),
+        "status": status,
+        "traffic_percentage": traffic_percentage,
+        "context": { "==": [{ "var": "os" }, "android"] },
+        "variants": [
+            {
+                "id": format!("{id}-control"),
+                "variant_type": "CONTROL",
+                "overrides": { "key": "control" }
+            },
+            {
+                "id": format!("{id}-experimental"),
+                "variant_type": "EXPERIMENTAL",
+                "overrides": { "key": "experimental" }
+            }
+        ]
+    })
+}
+
+fn list_response(experiments: Vec<Value>) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "total_items": experiments.len(),
+        "total_pages": 1,
+        "data": experiments
+    }))
+}
+
+/// Polls the mock server as tenant `test` every `poll_frequency` seconds.
+fn config(server: &MockServer, poll_frequency: u64) -> Config {
+    Config {
+        tenant: "test".to_string(),
+        hostname: server.uri(),
+        poll_frequency,
+        ..Default::default()
+    }
+}
+
+async fn stored_traffic(client: &Client) -> Vec<(String, u64)> {
+    let mut experiments: Vec<(String, u64)> = client
+        .get_running_experiments()
+        .await
+        .into_iter()
+        .map(|experiment| {
+            let experiment = serde_json::to_value(experiment).unwrap();
+            (
+                experiment["id"].as_str().unwrap().to_string(),
+                experiment["traffic_percentage"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    experiments.sort();
+    experiments
+}
+
+const MAX_YIELDS: usize = 1_000_000;
+
+/// Yields until `condition` holds. The runtime always has this task to poll in
+/// the meantime, so a paused clock does not auto-advance while a request to the
+/// mock server is in flight and only moves through `time::advance`.
+async fn wait_until<F, Fut>(mut condition: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    for _ in 0..MAX_YIELDS {
+        if condition().await {
+            return;
+        }
+        task::yield_now().await;
+    }
+    panic!("condition still false after {MAX_YIELDS} yields");
+}
+
+async fn wait_for_requests(server: &MockServer, count: usize) {
+    wait_until(|| async { server.received_requests().await.unwrap().len() >= count })
+        .await;
+}
+
+async fn wait_for_traffic(client: &Client, expected: &[(&str, u64)]) {
+    let expected: Vec<(String, u64)> = expected
+        .iter()
+        .map(|(id, traffic)| (id.to_string(), *traffic))
+        .collect();
+    wait_until(|| async { stored_traffic(client).await == expected }).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_polling_reflects_server_state() {
+    let server = MockServer::start().await;
+
+    // first poll: two running experiments
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .and(header("x-tenant", "test"))
+        .respond_with(list_response(vec![
+            experiment_gen("1", "INPROGRESS", 10),
+            experiment_gen("2", "INPROGRESS", 20),
+        ]))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    // later polls: experiment 1 is ramped, 2 concluded and 3 created
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .and(header("x-tenant", "test"))
+        .respond_with(list_response(vec![
+            experiment_gen("1", "INPROGRESS", 30),
+            experiment_gen("2", "CONCLUDED", 20),
+            experiment_gen("3", "CREATED", 0),
+        ]))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(Client::new(config(&server, 1)).unwrap());
+    let polling = tokio::spawn(client.clone().run_polling_updates());
+
+    wait_for_traffic(&client, &[("1", 10), ("2", 20)]).await;
+    // nothing else is fetched until the clock reaches the next tick
+    task::yield_now().await;
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+    time::advance(Duration::from_secs(1)).await;
+    wait_for_traffic(&client, &[("1", 30), ("3", 0)]).await;
+
+    polling.abort();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_client_factory_polls_until_evicted() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .and(header("x-tenant", "test"))
+        .respond_with(list_response(vec![experiment_gen("1", "INPROGRESS", 10)]))
+        .mount(&server)
+        .await;
+
+    let factory = ClientFactory::new();
+    let config_fn = || config(&server, 1);
+    let client = factory.get_or_create("test", config_fn).await.unwrap();
+    let same_client = factory
+        .get_or_create("test", || panic!("config requested for a known tenant"))
+        .await
+        .unwrap();
+    assert!(Arc::ptr_eq(&client, &same_client));
+
+    wait_for_traffic(&client, &[("1", 10)]).await;
+    time::advance(Duration::from_secs(1)).await;
+    wait_for_requests(&server, 2).await;
+
+    let evicted = factory.evict("test").await.unwrap();
+    assert!(Arc::ptr_eq(&client, &evicted));
+    assert!(factory.read().await.is_empty());
+
+    drop((same_client, evicted));
+
+    // the polling loop keeps a reference to the client until it returns
+    wait_until(|| async { Arc::strong_count(&client) == 1 }).await;
+    time::advance(Duration::from_secs(3)).await;
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_polling_sends_etag_and_keeps_store_on_not_modified() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304).insert_header("etag", "\"v1\""))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .respond_with(
+            list_response(vec![experiment_gen("1", "INPROGRESS", 10)])
+                .insert_header("etag", "\"v1\""),
+        )
+        .expect(1)
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(Client::new(config(&server, 1)).unwrap());
+    let polling = tokio::spawn(client.clone().run_polling_updates());
+
+    wait_for_traffic(&client, &[("1", 10)]).await;
+    time::advance(Duration::from_secs(1)).await;
+    wait_until(|| async {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .any(|request| {
+                request
+                    .headers
+                    .keys()
+                    .any(|name| name.as_str() == "if-none-match")
+            })
+    })
+    .await;
+    assert_eq!(stored_traffic(&client).await, vec![("1".to_string(), 10)]);
+
+    polling.abort();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_refresh_now_updates_store_immediately() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .and(header("x-tenant", "test"))
+        .respond_with(list_response(vec![
+            experiment_gen("1", "INPROGRESS", 10),
+            experiment_gen("2", "CONCLUDED", 20),
+        ]))
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(Client::new(config(&server, 60)).unwrap());
+    // the first poll happens right away, after which the loop waits for a tick
+    // that never comes as the clock is not advanced
+    let polling = tokio::spawn(client.clone().run_polling_updates());
+    wait_for_traffic(&client, &[("1", 10)]).await;
+
+    let refresh = || {
+        let client = client.clone();
+        tokio::spawn(async move { client.refresh_now().await })
+    };
+    let (first, second) = (refresh(), refresh());
+    wait_until(|| async { first.is_finished() && second.is_finished() }).await;
+    assert_eq!(first.await.unwrap().unwrap(), 2);
+    assert_eq!(second.await.unwrap().unwrap(), 2);
+    assert_eq!(stored_traffic(&client).await, vec![("1".to_string(), 10)]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+    polling.abort();
+}
+
+#[tokio::test]
+async fn test_observe_is_called_for_each_evaluation() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .respond_with(list_response(vec![experiment_gen("1", "INPROGRESS", 10)]))
+        .mount(&server)
+        .await;
+
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let recorder = observed.clone();
+    let client = Client::new(Config {
+        observe: Some(Arc::new(move |elapsed| {
+            recorder.lock().unwrap().push(elapsed)
+        })),
+        ..config(&server, 60)
+    })
+    .unwrap();
+    client.refresh_now().await.unwrap();
+
+    let context = json!({ "os": "android" });
+    client.get_applicable_variant(&context, 5).await;
+    client
+        .get_applicable_variant(&json!({ "os": "ios" }), 5)
+        .await;
+    assert_eq!(observed.lock().unwrap().len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_force_refresh_polls_before_the_next_tick() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .respond_with(list_response(vec![experiment_gen("1", "INPROGRESS", 10)]))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .respond_with(list_response(vec![experiment_gen("1", "INPROGRESS", 30)]))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(Client::new(config(&server, 60)).unwrap());
+    let polling = tokio::spawn(client.clone().run_polling_updates());
+
+    wait_for_traffic(&client, &[("1", 10)]).await;
+
+    // the clock stays put, so only the forced refresh can fetch again
+    client.force_refresh_sender().try_send(()).unwrap();
+    wait_for_traffic(&client, &[("1", 30)]).await;
+
+    polling.abort();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_notify_shutdown_stops_polling() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/experiments"))
+        .respond_with(list_response(vec![experiment_gen("1", "INPROGRESS", 10)]))
+        .mount(&server)
+        .await;
+
+    let client = Arc::new(Client::new(config(&server, 60)).unwrap());
+    let polling = tokio::spawn(client.clone().run_polling_updates());
+
+    wait_for_traffic(&client, &[("1", 10)]).await;
+
+    // the loop returns without the clock reaching the next tick
+    client.notify_shutdown();
+    wait_until(|| async { polling.is_finished() }).await;
+    polling.await.unwrap();
+}
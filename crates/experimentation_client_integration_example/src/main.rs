@@ -13,8 +13,12 @@ async fn main() -> std::io::Result<()> {
         tenant: "tenant".to_string(),
         hostname: "http://localhost:8080".to_string(),
         poll_frequency: 10,
+        ..Default::default()
     };
-    let client = std::sync::Arc::new(exp::Client::new(client_configuration));
+    let client = std::sync::Arc::new(
+        exp::Client::new(client_configuration)
+            .expect("failed to create experimentation client"),
+    );
     rt::spawn(client.clone().run_polling_updates());
     HttpServer::new(move || {
         App::new()
@@ -0,0 +1,89 @@
+//! Experiment evaluation for browsers, exported to JavaScript through
+//! `wasm-bindgen`. Fetching experiments is left to the caller, the
+//! `@superposition/client` package in `clients/wasm` polls them with `fetch`.
+//!
+//! Values cross the boundary as JSON strings, so that the exported functions
+//! do not depend on how the bundler represents JavaScript objects.
+use std::collections::HashMap;
+
+use serde_json::Value;
+use superposition_core::{Experiment, ExperimentStatusType};
+use wasm_bindgen::prelude::*;
+
+fn matches(experiment: &Experiment, context: &Value) -> bool {
+    jsonlogic::apply(&experiment.context, context) == Ok(Value::Bool(true))
+}
+
+/// The running experiments of a tenant, kept up to date by the caller.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct ExperimentStore {
+    experiments: HashMap<String, Experiment>,
+}
+
+#[wasm_bindgen]
+impl ExperimentStore {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the experiments in `experiments_json`, a JSON array
+    /// of experiments as listed by `GET /experiments`, and drops the
+    /// concluded ones. Returns the number of experiments applied.
+    #[wasm_bindgen(js_name = applyExperiments)]
+    pub fn apply_experiments(
+        &mut self,
+        experiments_json: &str,
+    ) -> Result<usize, JsError> {
+        let experiments: Vec<Experiment> = serde_json::from_str(experiments_json)?;
+        let applied = experiments.len();
+        for experiment in experiments {
+            if experiment.status == ExperimentStatusType::CONCLUDED {
+                self.experiments.remove(&experiment.id);
+            } else {
+                self.experiments.insert(experiment.id.clone(), experiment);
+            }
+        }
+        Ok(applied)
+    }
+
+    /// JSON array of the ids of the variants applicable to `context_json`
+    /// for the given `toss`.
+    #[wasm_bindgen(js_name = applicableVariants)]
+    pub fn applicable_variants(
+        &self,
+        context_json: &str,
+        toss: i8,
+    ) -> Result<String, JsError> {
+        let context: Value = serde_json::from_str(context_json)?;
+        let variants = self
+            .experiments
+            .values()
+            .filter(|experiment| matches(experiment, &context))
+            .filter_map(|experiment| experiment.decide_variant(toss))
+            .map(|variant| &variant.id)
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_string(&variants)?)
+    }
+
+    /// JSON array of the experiments whose context `context_json` satisfies.
+    #[wasm_bindgen(js_name = satisfiedExperiments)]
+    pub fn satisfied_experiments(&self, context_json: &str) -> Result<String, JsError> {
+        let context: Value = serde_json::from_str(context_json)?;
+        let experiments = self
+            .experiments
+            .values()
+            .filter(|experiment| matches(experiment, &context))
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_string(&experiments)?)
+    }
+
+    /// JSON array of all the experiments in the store.
+    #[wasm_bindgen(js_name = runningExperiments)]
+    pub fn running_experiments(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(
+            &self.experiments.values().collect::<Vec<_>>(),
+        )?)
+    }
+}
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE public.webhooks (
+    id BIGINT PRIMARY KEY,
+    tenant TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS webhooks_tenant_index ON public.webhooks(tenant);
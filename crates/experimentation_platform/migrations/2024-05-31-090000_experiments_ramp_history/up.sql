@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE public.experiments ADD COLUMN IF NOT EXISTS ramp_history json NOT NULL DEFAULT '[]'::json;
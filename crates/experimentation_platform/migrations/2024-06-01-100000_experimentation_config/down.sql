@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS experimentation_config_audit ON public.experimentation_config;
+DROP TABLE public.experimentation_config;
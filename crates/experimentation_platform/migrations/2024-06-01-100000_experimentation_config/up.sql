@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE public.experimentation_config (
+    tenant_id TEXT NOT NULL,
+    flag_name TEXT NOT NULL,
+    flag_value BOOLEAN NOT NULL,
+    last_modified_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_modified_by TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, flag_name)
+);
+CREATE TRIGGER experimentation_config_audit AFTER INSERT OR DELETE OR UPDATE ON public.experimentation_config FOR EACH ROW EXECUTE FUNCTION public.event_logger();
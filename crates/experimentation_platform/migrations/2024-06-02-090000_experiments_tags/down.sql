@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS experiments_tags_index;
+ALTER TABLE public.experiments DROP COLUMN IF EXISTS tags;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE public.experiments ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS experiments_tags_index ON public.experiments USING gin (tags);
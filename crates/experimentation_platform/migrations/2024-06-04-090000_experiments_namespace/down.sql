@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS experiments_namespace_index;
+ALTER TABLE public.experiments DROP COLUMN IF EXISTS namespace;
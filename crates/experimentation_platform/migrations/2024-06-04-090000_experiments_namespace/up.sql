@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE public.experiments ADD COLUMN IF NOT EXISTS namespace TEXT NOT NULL DEFAULT 'default';
+CREATE INDEX IF NOT EXISTS experiments_namespace_index ON public.experiments (namespace);
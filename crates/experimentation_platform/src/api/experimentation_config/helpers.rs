@@ -0,0 +1,82 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+    time::{Duration, Instant},
+};
+
+use diesel::{
+    r2d2::{ConnectionManager, PooledConnection},
+    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+};
+use service_utils::{
+    result as superposition,
+    service::types::{ExperimentationFlags, Tenant},
+};
+
+use super::types::set_flag;
+use crate::db::{models::ExperimentationConfig, schema::experimentation_config::dsl};
+
+/// How long flags loaded for a tenant are served before reading the table again
+const FLAGS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+type FlagsCache = RwLock<HashMap<String, (Instant, ExperimentationFlags)>>;
+
+fn flags_cache() -> &'static FlagsCache {
+    static CACHE: OnceLock<FlagsCache> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+pub fn merge_flag_overrides(
+    defaults: &ExperimentationFlags,
+    overrides: &[ExperimentationConfig],
+) -> ExperimentationFlags {
+    let mut flags = *defaults;
+    for row in overrides {
+        if !set_flag(&mut flags, &row.flag_name, row.flag_value) {
+            log::warn!(
+                "ignoring unknown experimentation flag {} for {}",
+                row.flag_name,
+                row.tenant_id
+            );
+        }
+    }
+    flags
+}
+
+pub fn fetch_flag_overrides(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tenant: &Tenant,
+) -> superposition::Result<Vec<ExperimentationConfig>> {
+    Ok(dsl::experimentation_config
+        .filter(dsl::tenant_id.eq(tenant.as_str()))
+        .order(dsl::flag_name.asc())
+        .load::<ExperimentationConfig>(conn)?)
+}
+
+/// Resolves the experimentation flags of `tenant` by applying its overrides
+/// on top of `defaults`, serving recently resolved flags from memory.
+pub fn get_experimentation_flags(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tenant: &Tenant,
+    defaults: &ExperimentationFlags,
+) -> superposition::Result<ExperimentationFlags> {
+    if let Ok(cache) = flags_cache().read() {
+        if let Some((loaded_at, flags)) = cache.get(tenant.as_str()) {
+            if loaded_at.elapsed() < FLAGS_CACHE_TTL {
+                return Ok(*flags);
+            }
+        }
+    }
+
+    let flags = merge_flag_overrides(defaults, &fetch_flag_overrides(conn, tenant)?);
+    if let Ok(mut cache) = flags_cache().write() {
+        cache.insert(tenant.to_string(), (Instant::now(), flags));
+    }
+    Ok(flags)
+}
+
+pub fn invalidate_cached_flags(tenant: &Tenant) {
+    if let Ok(mut cache) = flags_cache().write() {
+        cache.remove(tenant.as_str());
+    }
+}
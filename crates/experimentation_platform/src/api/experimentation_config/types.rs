@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use service_utils::service::types::ExperimentationFlags;
+
+pub const ALLOW_SAME_KEYS_OVERLAPPING_CTX: &str = "allow_same_keys_overlapping_ctx";
+pub const ALLOW_DIFF_KEYS_OVERLAPPING_CTX: &str = "allow_diff_keys_overlapping_ctx";
+pub const ALLOW_SAME_KEYS_NON_OVERLAPPING_CTX: &str =
+    "allow_same_keys_non_overlapping_ctx";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentationFlagsPatch {
+    pub allow_same_keys_overlapping_ctx: Option<bool>,
+    pub allow_diff_keys_overlapping_ctx: Option<bool>,
+    pub allow_same_keys_non_overlapping_ctx: Option<bool>,
+}
+
+impl ExperimentationFlagsPatch {
+    /// The flags set in the patch as `(flag_name, flag_value)` pairs.
+    pub fn flags(&self) -> Vec<(&'static str, bool)> {
+        [
+            (
+                ALLOW_SAME_KEYS_OVERLAPPING_CTX,
+                self.allow_same_keys_overlapping_ctx,
+            ),
+            (
+                ALLOW_DIFF_KEYS_OVERLAPPING_CTX,
+                self.allow_diff_keys_overlapping_ctx,
+            ),
+            (
+                ALLOW_SAME_KEYS_NON_OVERLAPPING_CTX,
+                self.allow_same_keys_non_overlapping_ctx,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+}
+
+/// Sets the flag called `name` on `flags`, returning false for unknown names.
+pub fn set_flag(flags: &mut ExperimentationFlags, name: &str, value: bool) -> bool {
+    let flag = match name {
+        ALLOW_SAME_KEYS_OVERLAPPING_CTX => &mut flags.allow_same_keys_overlapping_ctx,
+        ALLOW_DIFF_KEYS_OVERLAPPING_CTX => &mut flags.allow_diff_keys_overlapping_ctx,
+        ALLOW_SAME_KEYS_NON_OVERLAPPING_CTX => {
+            &mut flags.allow_same_keys_non_overlapping_ctx
+        }
+        _ => return false,
+    };
+    *flag = value;
+    true
+}
@@ -1,44 +1,61 @@
 use std::collections::{HashMap, HashSet};
 
+use actix_web::http::header;
 use actix_web::{
     get, patch, post, put,
-    web::{self, Data, Json, Query},
+    web::{self, Bytes, Data, Json, Query},
     HttpRequest, HttpResponse, Scope,
 };
 use chrono::{DateTime, Duration, NaiveDateTime, Utc};
 use diesel::{
+    dsl::sql,
     r2d2::{ConnectionManager, PooledConnection},
-    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+    sql_types::{Bool, Text},
+    Connection, ExpressionMethods, OptionalExtension, PgArrayExpressionMethods,
+    PgConnection, PgTextExpressionMethods, QueryDsl, RunQueryDsl,
 };
 
 use service_utils::{
-    bad_argument, response_error, result as superposition, unexpected_error,
+    bad_argument, not_found, response_error, result as superposition, unexpected_error,
 };
 
 use superposition_types::{SuperpositionUser, User};
 
 use reqwest::{Response, StatusCode};
-use service_utils::service::types::{AppState, DbConnection, Tenant};
+use service_utils::helpers::{escape_like_pattern, validate_admin, CompositeDimensions};
+use service_utils::service::types::{AppEnv, AppScope, AppState, DbConnection, Tenant};
 
 use super::{
     helpers::{
         add_variant_dimension_to_ctx, check_variant_types,
-        check_variants_override_coverage, extract_override_keys, validate_experiment,
-        validate_override_keys,
+        check_variants_override_coverage, etag_matches, experiment_csv_row,
+        experiment_list_etag, experiment_timeline, extract_override_keys,
+        trace_jsonlogic, validate_experiment, validate_namespace, validate_override_keys,
+        validate_tags, DimensionCardinalities, EXPERIMENT_CSV_HEADER,
     },
     types::{
-        AuditQueryFilters, ConcludeExperimentRequest, ContextAction, ContextBulkResponse,
-        ContextMoveReq, ContextPutReq, ExperimentCreateRequest, ExperimentCreateResponse,
-        ExperimentResponse, ExperimentsResponse, ListFilters, OverrideKeysUpdateRequest,
-        RampRequest, Variant,
+        ActiveExperiment, ActiveForContextRequest, AuditQueryFilters,
+        BulkConcludeFailure, BulkConcludeQuery, BulkConcludeResponse,
+        ConcludeExperimentRequest, ConcludeWithRolloutRequest,
+        ConcludeWithRolloutResponse, ContextAction, ContextBulkResponse, ContextMoveReq,
+        ContextPutReq, ContextPutResp, DebugContextRequest, DebugContextResponse,
+        ExperimentCreateRequest, ExperimentCreateResponse, ExperimentResponse,
+        ExperimentSortBy, ExperimentsResponse, ExportFormat, ExportQuery, ListFilters,
+        OverrideKeysUpdateRequest, RampHistoryEntry, RampRequest, SimulateRequest,
+        TimelineEvent, TossStrategy, Variant, VariantResponse, VariantType,
     },
 };
 
 use crate::{
+    api::experimentation_config::helpers::get_experimentation_flags,
+    api::webhooks::{helpers::dispatch_event, types::WebhookEvent},
+    db::cac_schema,
     db::models::{EventLog, Experiment, ExperimentStatusType},
     db::schema::{event_log::dsl as event_log, experiments::dsl as experiments},
 };
 
+use futures_util::{stream, StreamExt};
+use rand::Rng;
 use serde_json::{json, Map, Value};
 
 pub fn endpoints(scope: Scope) -> Scope {
@@ -46,10 +63,18 @@ pub fn endpoints(scope: Scope) -> Scope {
         .service(get_audit_logs)
         .service(create)
         .service(conclude_handler)
+        .service(conclude_with_rollout)
+        .service(bulk_conclude)
         .service(list_experiments)
+        .service(export_experiments)
         .service(get_experiment_handler)
+        .service(get_experiment_timeline)
+        .service(get_variant)
         .service(ramp)
         .service(update_overrides)
+        .service(simulate)
+        .service(active_for_context)
+        .service(debug_context)
 }
 
 async fn parse_error_response(
@@ -96,6 +121,46 @@ async fn process_cac_http_response(
     }
 }
 
+/// Reads the tenant's composite dimensions from its CAC schema.
+fn fetch_composite_dimensions(
+    state: &AppState,
+    tenant: &Tenant,
+) -> superposition::Result<CompositeDimensions> {
+    let DbConnection(mut conn) = DbConnection::for_scope(state, tenant, AppScope::CAC)?;
+    let composite_dimensions = cac_schema::composite_dimensions::table
+        .select((
+            cac_schema::composite_dimensions::name,
+            cac_schema::composite_dimensions::component_dimensions,
+        ))
+        .load::<(String, Vec<String>)>(&mut conn)?;
+
+    Ok(composite_dimensions.into_iter().collect())
+}
+
+/// Counts the allowed values of the tenant's enum dimensions, read from its
+/// CAC schema.
+fn fetch_dimension_cardinalities(
+    state: &AppState,
+    tenant: &Tenant,
+) -> superposition::Result<DimensionCardinalities> {
+    let DbConnection(mut conn) = DbConnection::for_scope(state, tenant, AppScope::CAC)?;
+    let dimensions = cac_schema::dimensions::table
+        .select((
+            cac_schema::dimensions::dimension,
+            cac_schema::dimensions::allowed_values,
+        ))
+        .filter(cac_schema::dimensions::allowed_values.is_not_null())
+        .load::<(String, Option<Value>)>(&mut conn)?;
+
+    Ok(dimensions
+        .into_iter()
+        .filter_map(|(dimension, allowed_values)| match allowed_values {
+            Some(Value::Array(values)) => Some((dimension, values.len())),
+            _ => None,
+        })
+        .collect())
+}
+
 #[post("")]
 async fn create(
     state: Data<AppState>,
@@ -124,6 +189,8 @@ async fn create(
         ));
     }
     validate_override_keys(&unique_override_keys)?;
+    validate_tags(&req.tags)?;
+    validate_namespace(&req.namespace)?;
 
     // Checking if all the variants are overriding the mentioned keys
     let variant_overrides = variants
@@ -146,12 +213,21 @@ async fn create(
     }
 
     // validating experiment against other active experiments based on permission flags
-    let flags = &state.experimentation_flags;
+    let flags =
+        get_experimentation_flags(&mut conn, &tenant, &state.experimentation_flags)?;
+    let composite_dimensions = fetch_composite_dimensions(&state, &tenant)?;
+    let cardinalities = match flags.overlap_threshold {
+        Some(_) => fetch_dimension_cardinalities(&state, &tenant)?,
+        None => DimensionCardinalities::new(),
+    };
     let (valid, reason) = validate_experiment(
         &req.context,
+        &req.namespace,
         &unique_override_keys,
         None,
         &flags,
+        &composite_dimensions,
+        &cardinalities,
         &mut conn,
     )?;
     if !valid {
@@ -164,11 +240,14 @@ async fn create(
 
     //create overrides in CAC, if successfull then create experiment in DB
     let mut cac_operations: Vec<ContextAction> = vec![];
+    let created_at = Utc::now();
     for variant in &mut variants {
         let variant_id = experiment_id.to_string() + "-" + &variant.id;
 
         // updating variant.id to => experiment_id + variant.id
         variant.id = variant_id.to_string();
+        variant.last_modified_by = Some(user.get_recorded_email());
+        variant.last_modified_at = Some(created_at);
 
         let updated_cacccontext =
             add_variant_dimension_to_ctx(&req.context, variant_id.to_string())?;
@@ -225,7 +304,7 @@ async fn create(
     // inserting experiment in db
     let new_experiment = Experiment {
         id: experiment_id,
-        created_by: user.get_email(),
+        created_by: user.get_recorded_email(),
         created_at: Utc::now(),
         last_modified: Utc::now(),
         name: req.name.to_string(),
@@ -234,8 +313,12 @@ async fn create(
         status: ExperimentStatusType::CREATED,
         context: req.context.clone(),
         variants: serde_json::to_value(variants).unwrap(),
-        last_modified_by: user.get_email(),
+        last_modified_by: user.get_recorded_email(),
         chosen_variant: None,
+        ramp_history: json!([]),
+        tags: req.tags.clone(),
+        namespace: req.namespace.clone(),
+        description: req.description.clone(),
     };
 
     let mut inserted_experiments = diesel::insert_into(experiments)
@@ -257,12 +340,12 @@ async fn conclude_handler(
     tenant: Tenant,
     user: User,
 ) -> superposition::Result<Json<ExperimentResponse>> {
-    let DbConnection(conn) = db_conn;
+    let DbConnection(mut conn) = db_conn;
     let response = conclude(
         state,
         path.into_inner(),
         req.into_inner(),
-        conn,
+        &mut conn,
         tenant,
         user,
     )
@@ -270,21 +353,115 @@ async fn conclude_handler(
     return Ok(Json(ExperimentResponse::from(response)));
 }
 
+/// Concludes every active experiment carrying `tag` in favour of its control
+/// variant, reporting the experiments that could not be concluded. Available
+/// to admins only.
+#[post("/bulk_conclude")]
+async fn bulk_conclude(
+    state: Data<AppState>,
+    query: Query<BulkConcludeQuery>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<BulkConcludeResponse>> {
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+    let tag = query.into_inner().tag;
+
+    let tagged_experiments = experiments::experiments
+        .filter(experiments::tags.contains(vec![tag]))
+        .filter(experiments::status.ne(ExperimentStatusType::CONCLUDED))
+        .order(experiments::id.asc())
+        .load::<Experiment>(&mut conn)?;
+
+    let mut response = BulkConcludeResponse {
+        concluded: Vec::new(),
+        failed: Vec::new(),
+    };
+    for experiment in tagged_experiments {
+        let control_variant = serde_json::from_value::<Vec<Variant>>(experiment.variants)
+            .ok()
+            .and_then(|variants| {
+                variants.into_iter().find(|variant| {
+                    variant.variant_type == VariantType::CONTROL
+                        && variant.holdout_control_for.is_none()
+                })
+            });
+        let result = match control_variant {
+            Some(variant) => {
+                conclude(
+                    state.clone(),
+                    experiment.id,
+                    ConcludeExperimentRequest {
+                        chosen_variant: variant.id,
+                    },
+                    &mut conn,
+                    tenant.clone(),
+                    user.clone(),
+                )
+                .await
+            }
+            None => Err(unexpected_error!(
+                "control variant not found for experiment {}",
+                experiment.id
+            )),
+        };
+        match result {
+            Ok(concluded) => response.concluded.push(ExperimentResponse::from(concluded)),
+            Err(err) => {
+                log::error!("failed to conclude experiment {}: {err}", experiment.id);
+                response.failed.push(BulkConcludeFailure {
+                    experiment_id: experiment.id.to_string(),
+                    error: err.to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(Json(response))
+}
+
 pub async fn conclude(
     state: Data<AppState>,
     experiment_id: i64,
     req: ConcludeExperimentRequest,
-    mut conn: PooledConnection<ConnectionManager<PgConnection>>,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
     tenant: Tenant,
     user: User,
 ) -> superposition::Result<Experiment> {
-    use crate::db::schema::experiments::dsl;
+    let (concluded_experiment, _) = conclude_experiment(
+        &state,
+        experiment_id,
+        req.chosen_variant,
+        false,
+        conn,
+        &tenant,
+        &user,
+    )
+    .await?;
+    Ok(concluded_experiment)
+}
 
-    let winner_variant_id: String = req.chosen_variant.to_owned();
+/// Concludes the experiment in favour of `winner_variant_id`. The conclusion is
+/// claimed in the DB before CAC is called, so concurrent conclusions of one
+/// experiment cannot both reach CAC, and is given back if the CAC call fails.
+/// With `rollout` the winner's overrides are written as a regular context
+/// override on the experiment's context, instead of moving the winner's context
+/// there, and the promotion is recorded in the audit log.
+async fn conclude_experiment(
+    state: &AppState,
+    experiment_id: i64,
+    winner_variant_id: String,
+    rollout: bool,
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tenant: &Tenant,
+    user: &User,
+) -> superposition::Result<(Experiment, Option<ContextPutResp>)> {
+    use crate::db::schema::experiments::dsl;
 
     let experiment: Experiment = dsl::experiments
         .find(experiment_id)
-        .get_result::<Experiment>(&mut conn)?;
+        .get_result::<Experiment>(conn)?;
 
     if matches!(experiment.status, ExperimentStatusType::CONCLUDED) {
         return Err(bad_argument!(
@@ -299,42 +476,94 @@ pub async fn conclude(
     })?;
 
     let mut operations: Vec<ContextAction> = vec![];
-    let experiment_variants: Vec<Variant> = serde_json::from_value(experiment.variants)
-        .map_err(|err| {
-        log::error!(
+    let experiment_variants: Vec<Variant> =
+        serde_json::from_value(experiment.variants.clone()).map_err(|err| {
+            log::error!(
             "failed parse eixisting experiment variant while concluding with error: {}",
             err
         );
-        unexpected_error!("Something went wrong, failed to conclude experiment")
-    })?;
+            unexpected_error!("Something went wrong, failed to conclude experiment")
+        })?;
 
-    let mut is_valid_winner_variant = false;
+    let mut winner_variant = None;
     for variant in experiment_variants {
-        let context_id = variant.context_id.ok_or_else(|| {
+        let context_id = variant.context_id.clone().ok_or_else(|| {
             log::error!("context id not available for variant {:?}", variant.id);
             unexpected_error!("Something went wrong, failed to conclude experiment")
         })?;
 
-        if variant.id == winner_variant_id {
+        if variant.id == winner_variant_id && !rollout {
             let context_move_req = ContextMoveReq {
                 context: experiment_context.clone(),
             };
-
-            is_valid_winner_variant = true;
-
             operations.push(ContextAction::MOVE((context_id, context_move_req)));
         } else {
             // delete this context
             operations.push(ContextAction::DELETE(context_id));
         }
+        if variant.id == winner_variant_id {
+            winner_variant = Some(variant);
+        }
     }
 
-    if !is_valid_winner_variant {
+    let Some(winner_variant) = winner_variant else {
         return Err(bad_argument!(
             "winner variant not found. A wrong variant id may have been sent, check and try again"
         ));
+    };
+    if rollout {
+        operations.push(ContextAction::PUT(ContextPutReq {
+            context: experiment_context.clone(),
+            r#override: json!(winner_variant.overrides),
+        }));
     }
 
+    // everything that can fail in the DB happens here, before CAC is called,
+    // and only one conclusion of the experiment gets past the status filter
+    let promotion_id = rollout.then(uuid::Uuid::new_v4);
+    let updated_experiment =
+        conn.transaction::<_, superposition::AppError, _>(|conn| {
+            let updated_experiment = diesel::update(dsl::experiments)
+                .filter(dsl::id.eq(experiment_id))
+                .filter(dsl::status.ne(ExperimentStatusType::CONCLUDED))
+                .set((
+                    dsl::status.eq(ExperimentStatusType::CONCLUDED),
+                    dsl::last_modified.eq(Utc::now()),
+                    dsl::last_modified_by.eq(user.get_recorded_email()),
+                    dsl::chosen_variant.eq(Some(&winner_variant_id)),
+                ))
+                .get_result::<Experiment>(conn)
+                .optional()?
+                .ok_or_else(|| {
+                    bad_argument!(
+                        "experiment with id {} is already concluded",
+                        experiment_id
+                    )
+                })?;
+            if let Some(promotion_id) = promotion_id {
+                diesel::insert_into(event_log::event_log)
+                    .values(EventLog {
+                        id: promotion_id,
+                        table_name: String::from("experiments"),
+                        user_name: user.get_recorded_email(),
+                        timestamp: Utc::now().naive_utc(),
+                        action: String::from("PROMOTE"),
+                        original_data: None,
+                        new_data: Some(json!({
+                            "experiment_id": experiment_id.to_string(),
+                            "variant_id": winner_variant_id,
+                            "context": experiment.context,
+                            "override": winner_variant.overrides,
+                        })),
+                        query: format!(
+                            "POST /experiments/{experiment_id}/conclude_with_rollout"
+                        ),
+                    })
+                    .execute(conn)?;
+            }
+            Ok(updated_experiment)
+        })?;
+
     // calling CAC bulk api with operations as payload
     let http_client = reqwest::Client::new();
     let url = state.cac_host.clone() + "/context/bulk-operations";
@@ -349,20 +578,114 @@ pub async fn conclude(
         .send()
         .await;
 
-    let _ = process_cac_http_response(response).await?;
+    let cac_response = match process_cac_http_response(response).await {
+        Ok(cac_response) => cac_response,
+        Err(err) => {
+            // the bulk operation is applied atomically by CAC, so the variant
+            // contexts are untouched and the experiment can run on
+            restore_unconcluded_experiment(conn, &experiment, promotion_id);
+            return Err(err);
+        }
+    };
 
-    // updating experiment status in db
-    let updated_experiment = diesel::update(dsl::experiments)
-        .filter(dsl::id.eq(experiment_id))
-        .set((
-            dsl::status.eq(ExperimentStatusType::CONCLUDED),
-            dsl::last_modified.eq(Utc::now()),
-            dsl::last_modified_by.eq(user.get_email()),
-            dsl::chosen_variant.eq(Some(winner_variant_id)),
-        ))
-        .get_result::<Experiment>(&mut conn)?;
+    dispatch_event(
+        conn,
+        tenant,
+        WebhookEvent::ExperimentConcluded,
+        &updated_experiment,
+    );
+
+    let rolled_out_context = if rollout {
+        let rolled_out_context = cac_response
+            .into_iter()
+            .find_map(|item| match item {
+                ContextBulkResponse::PUT(context) => Some(context),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                log::error!(
+                    "CAC did not return the rolled out context of {experiment_id}"
+                );
+                unexpected_error!("Something went wrong, failed to conclude experiment")
+            })?;
+        Some(rolled_out_context)
+    } else {
+        None
+    };
+
+    Ok((updated_experiment, rolled_out_context))
+}
 
-    return Ok(updated_experiment);
+/// Gives back the conclusion claimed by `conclude_experiment` when CAC could
+/// not apply it, along with the promotion recorded for it.
+fn restore_unconcluded_experiment(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    experiment: &Experiment,
+    promotion_id: Option<uuid::Uuid>,
+) {
+    use crate::db::schema::experiments::dsl;
+
+    let restored = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::update(dsl::experiments)
+            .filter(dsl::id.eq(experiment.id))
+            .filter(dsl::status.eq(ExperimentStatusType::CONCLUDED))
+            .set((
+                dsl::status.eq(experiment.status),
+                dsl::last_modified.eq(experiment.last_modified),
+                dsl::last_modified_by.eq(&experiment.last_modified_by),
+                dsl::chosen_variant.eq(&experiment.chosen_variant),
+            ))
+            .execute(conn)?;
+        if let Some(promotion_id) = promotion_id {
+            diesel::delete(event_log::event_log.filter(event_log::id.eq(promotion_id)))
+                .execute(conn)?;
+        }
+        Ok(())
+    });
+    if let Err(err) = restored {
+        log::error!(
+            "experiment {} is marked concluded but CAC did not conclude it: {err}",
+            experiment.id
+        );
+    }
+}
+
+/// Concludes the experiment and turns the overrides of the winning variant into
+/// a regular context override on the experiment's context. The variant contexts
+/// are removed and the override is written in one CAC bulk operation, and the
+/// promotion is recorded in the audit log along with the conclusion.
+#[post("/{experiment_id}/conclude_with_rollout")]
+async fn conclude_with_rollout(
+    state: Data<AppState>,
+    path: web::Path<i64>,
+    req: web::Json<ConcludeWithRolloutRequest>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<ConcludeWithRolloutResponse>> {
+    let DbConnection(mut conn) = db_conn;
+    let experiment_id = path.into_inner();
+
+    let (updated_experiment, rolled_out_context) = conclude_experiment(
+        &state,
+        experiment_id,
+        req.into_inner().winning_variant_id,
+        true,
+        &mut conn,
+        &tenant,
+        &user,
+    )
+    .await?;
+    let rolled_out_context = rolled_out_context.ok_or_else(|| {
+        log::error!("rollout of {experiment_id} returned no context");
+        unexpected_error!("Something went wrong, failed to conclude experiment")
+    })?;
+
+    Ok(Json(ConcludeWithRolloutResponse {
+        experiment: ExperimentResponse::from(updated_experiment),
+        context_id: rolled_out_context.context_id,
+        override_id: rolled_out_context.override_id,
+    }))
 }
 
 #[get("")]
@@ -397,6 +720,28 @@ async fn list_experiments(
         if let Some(states) = filters.status.clone() {
             builder = builder.filter(experiments::status.eq_any(states.0.clone()));
         }
+        if let Some(tag) = filters.tag.clone() {
+            builder = builder.filter(experiments::tags.contains(vec![tag]));
+        }
+        if let Some(key) = filters.has_override_key.clone() {
+            // matches the key itself and JSON pointers into it, `ui` matches an
+            // experiment overriding `ui#/theme/primary_color`
+            builder = builder.filter(
+                sql::<Bool>(
+                    "EXISTS (SELECT 1 FROM unnest(override_keys) AS override_key \
+                     WHERE override_key = ",
+                )
+                .bind::<Text, _>(key.clone())
+                .sql(" OR starts_with(override_key, ")
+                .bind::<Text, _>(key)
+                .sql(" || '#/'))"),
+            );
+        }
+        if let Some(search) = filters.description_contains.clone() {
+            let search = escape_like_pattern(&search);
+            builder =
+                builder.filter(experiments::description.ilike(format!("%{search}%")));
+        }
         let now = Utc::now();
         builder
             .filter(
@@ -411,8 +756,26 @@ async fn list_experiments(
 
     let limit = filters.count.unwrap_or(10);
     let offset = (filters.page.unwrap_or(1) - 1) * limit;
+    let base_query = match filters.sort_by {
+        None => base_query.order(experiments::last_modified.desc()),
+        Some(ExperimentSortBy::CreatedAtDesc) => {
+            base_query.order(experiments::created_at.desc())
+        }
+        Some(ExperimentSortBy::CreatedAtAsc) => {
+            base_query.order(experiments::created_at.asc())
+        }
+        Some(ExperimentSortBy::TrafficDesc) => {
+            base_query.order(experiments::traffic_percentage.desc())
+        }
+        Some(ExperimentSortBy::TrafficAsc) => {
+            base_query.order(experiments::traffic_percentage.asc())
+        }
+        Some(ExperimentSortBy::NameAsc) => base_query.order(experiments::name.asc()),
+        Some(ExperimentSortBy::Status) => base_query.order(experiments::status.asc()),
+    };
+    // tie-break on id so that pagination stays stable
     let query = base_query
-        .order(experiments::last_modified.desc())
+        .then_order_by(experiments::id.desc())
         .limit(limit)
         .offset(offset);
 
@@ -420,16 +783,79 @@ async fn list_experiments(
 
     let experiment_list = query.load::<Experiment>(&mut conn)?;
 
+    let etag = experiment_list_etag(&experiment_list, number_of_experiments);
+    let is_unchanged = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|header_val| header_val.to_str().ok())
+        .map_or(false, |if_none_match| etag_matches(if_none_match, &etag));
+    if is_unchanged {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish());
+    }
+
     let total_pages = (number_of_experiments as f64 / limit as f64).ceil() as i64;
 
-    Ok(HttpResponse::Ok().json(ExperimentsResponse {
-        total_pages,
-        total_items: number_of_experiments,
-        data: experiment_list
-            .into_iter()
-            .map(|entry| ExperimentResponse::from(entry))
-            .collect(),
-    }))
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, etag))
+        .json(ExperimentsResponse {
+            total_pages,
+            total_items: number_of_experiments,
+            data: experiment_list
+                .into_iter()
+                .map(|entry| ExperimentResponse::from(entry))
+                .collect(),
+        }))
+}
+
+/// Number of experiments read from the database per streamed chunk.
+const EXPORT_BATCH_SIZE: i64 = 100;
+
+#[get("/export")]
+async fn export_experiments(
+    query: Query<ExportQuery>,
+    db_conn: DbConnection,
+) -> superposition::Result<HttpResponse> {
+    let DbConnection(conn) = db_conn;
+    let ExportFormat::Csv = query.into_inner().format;
+
+    let header = stream::once(async {
+        Ok::<_, superposition::AppError>(Bytes::from_static(
+            EXPERIMENT_CSV_HEADER.as_bytes(),
+        ))
+    });
+    let rows = stream::try_unfold(
+        (conn, Some(0)),
+        |(mut conn, offset): (
+            PooledConnection<ConnectionManager<PgConnection>>,
+            Option<i64>,
+        )| async move {
+            let Some(offset) = offset else {
+                return Ok::<_, superposition::AppError>(None);
+            };
+            let batch = experiments::experiments
+                .order(experiments::id.asc())
+                .limit(EXPORT_BATCH_SIZE)
+                .offset(offset)
+                .load::<Experiment>(&mut conn)?;
+            if batch.is_empty() {
+                return Ok(None);
+            }
+            let next_offset = (batch.len() as i64 == EXPORT_BATCH_SIZE)
+                .then_some(offset + EXPORT_BATCH_SIZE);
+            let chunk: String = batch.iter().map(experiment_csv_row).collect();
+            Ok(Some((Bytes::from(chunk), (conn, next_offset))))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv")
+        .insert_header((
+            "Content-Disposition",
+            "attachment; filename=\"experiments.csv\"",
+        ))
+        .streaming(header.chain(rows)))
 }
 
 #[get("/{id}")]
@@ -442,6 +868,40 @@ async fn get_experiment_handler(
     return Ok(Json(ExperimentResponse::from(response)));
 }
 
+#[get("/{id}/timeline")]
+async fn get_experiment_timeline(
+    params: web::Path<i64>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Vec<TimelineEvent>>> {
+    let DbConnection(mut conn) = db_conn;
+    let experiment = get_experiment(params.into_inner(), &mut conn)?;
+    Ok(Json(experiment_timeline(&experiment)))
+}
+
+#[get("/{id}/variant/{variant_id}")]
+async fn get_variant(
+    params: web::Path<(i64, String)>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<VariantResponse>> {
+    let DbConnection(mut conn) = db_conn;
+    let (experiment_id, variant_id) = params.into_inner();
+    let experiment = get_experiment(experiment_id, &mut conn)?;
+    let variants: Vec<Variant> =
+        serde_json::from_value(experiment.variants).map_err(|e| {
+            log::error!("failed to parse variants of {experiment_id}: {e}");
+            unexpected_error!("Something went wrong")
+        })?;
+    variants
+        .into_iter()
+        .find(|variant| variant.id == variant_id)
+        .map(|variant| Json(VariantResponse::from(variant)))
+        .ok_or(not_found!(
+            "Variant {} not found in experiment {}",
+            variant_id,
+            experiment_id
+        ))
+}
+
 pub fn get_experiment(
     experiment_id: i64,
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
@@ -454,30 +914,99 @@ pub fn get_experiment(
     return Ok(result);
 }
 
+diesel::sql_function! {
+    /// Takes an advisory lock on `key` held until the end of the transaction,
+    /// returning false instead of waiting when it is already taken.
+    fn pg_try_advisory_xact_lock(key: diesel::sql_types::BigInt) -> diesel::sql_types::Bool;
+}
+
+/// How long a ramp waits for a concurrent ramp of the same experiment
+const RAMP_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+const RAMP_LOCK_RETRY_INTERVAL: std::time::Duration =
+    std::time::Duration::from_millis(50);
+
 #[patch("/{id}/ramp")]
 async fn ramp(
     params: web::Path<i64>,
     req: web::Json<RampRequest>,
     db_conn: DbConnection,
+    tenant: Tenant,
     user: User,
 ) -> superposition::Result<Json<ExperimentResponse>> {
     let DbConnection(mut conn) = db_conn;
     let exp_id = params.into_inner();
+    let traffic_percentage = req.into_inner().traffic_percentage;
+
+    // the read-compute-write below runs under a lock on the experiment, so
+    // concurrent ramps cannot overwrite each other's update
+    let started_at = std::time::Instant::now();
+    let (experiment, updated_experiment) = loop {
+        let ramped = conn.transaction::<_, superposition::AppError, _>(|conn| {
+            let locked = diesel::select(pg_try_advisory_xact_lock(exp_id))
+                .get_result::<bool>(conn)?;
+            if !locked {
+                return Ok(None);
+            }
+            ramp_experiment(conn, exp_id, traffic_percentage, &user).map(Some)
+        })?;
+        match ramped {
+            Some(ramped) => break ramped,
+            None if started_at.elapsed() < RAMP_LOCK_TIMEOUT => {
+                actix_web::rt::time::sleep(RAMP_LOCK_RETRY_INTERVAL).await
+            }
+            None => {
+                return Err(response_error!(
+                    StatusCode::CONFLICT,
+                    "Another ramp of this experiment is in progress, try again"
+                ))
+            }
+        }
+    };
 
+    if matches!(experiment.status, ExperimentStatusType::CREATED) {
+        dispatch_event(
+            &mut conn,
+            &tenant,
+            WebhookEvent::ExperimentStarted,
+            &updated_experiment,
+        );
+    }
+    dispatch_event(
+        &mut conn,
+        &tenant,
+        WebhookEvent::ExperimentRamped,
+        &updated_experiment,
+    );
+
+    return Ok(Json(ExperimentResponse::from(updated_experiment)));
+}
+
+/// Sets the traffic of `exp_id`, returning the experiment before and after.
+fn ramp_experiment(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    exp_id: i64,
+    traffic_percentage: u64,
+    user: &User,
+) -> superposition::Result<(Experiment, Experiment)> {
     let experiment: Experiment = experiments::experiments
         .find(exp_id)
-        .get_result::<Experiment>(&mut conn)?;
+        .get_result::<Experiment>(conn)?;
 
+    if traffic_percentage > 100 {
+        return Err(bad_argument!(
+            "The traffic_percentage should be between 0 and 100"
+        ));
+    }
     let old_traffic_percentage = experiment.traffic_percentage as u8;
-    let new_traffic_percentage = req.traffic_percentage as u8;
-    let experiment_variants: Vec<Variant> = serde_json::from_value(experiment.variants)
-        .map_err(|e| {
-        log::error!(
-            "failed to parse existing experiment variants while ramping {}",
-            e
-        );
-        unexpected_error!("Something went wrong, failed to ramp traffic percentage")
-    })?;
+    let new_traffic_percentage = traffic_percentage as u8;
+    let experiment_variants: Vec<Variant> =
+        serde_json::from_value(experiment.variants.clone()).map_err(|e| {
+            log::error!(
+                "failed to parse existing experiment variants while ramping {}",
+                e
+            );
+            unexpected_error!("Something went wrong, failed to ramp traffic percentage")
+        })?;
     let variants_count = experiment_variants.len() as u8;
     let max = 100 / variants_count;
 
@@ -494,17 +1023,31 @@ async fn ramp(
     {
         return Err(bad_argument!("The traffic_percentage is same as provided"))?;
     }
+
+    let now = Utc::now();
+    let mut ramp_history = match &experiment.ramp_history {
+        Value::Array(entries) => entries.clone(),
+        _ => Vec::new(),
+    };
+    ramp_history.push(json!(RampHistoryEntry {
+        old_traffic: experiment.traffic_percentage,
+        new_traffic: new_traffic_percentage as i32,
+        actor: user.get_recorded_email(),
+        timestamp: now,
+    }));
+
     let updated_experiment: Experiment = diesel::update(experiments::experiments)
         .filter(experiments::id.eq(exp_id))
         .set((
-            experiments::traffic_percentage.eq(req.traffic_percentage as i32),
-            experiments::last_modified.eq(Utc::now()),
-            experiments::last_modified_by.eq(user.get_email()),
+            experiments::traffic_percentage.eq(new_traffic_percentage as i32),
+            experiments::last_modified.eq(now),
+            experiments::last_modified_by.eq(user.get_recorded_email()),
             experiments::status.eq(ExperimentStatusType::INPROGRESS),
+            experiments::ramp_history.eq(Value::Array(ramp_history)),
         ))
-        .get_result(&mut conn)?;
+        .get_result(conn)?;
 
-    return Ok(Json(ExperimentResponse::from(updated_experiment)));
+    Ok((experiment, updated_experiment))
 }
 
 #[put("/{id}/overrides")]
@@ -521,6 +1064,9 @@ async fn update_overrides(
 
     let payload = req.into_inner();
     let variants = payload.variants;
+    if let Some(tags) = &payload.tags {
+        validate_tags(tags)?;
+    }
 
     let first_variant = variants.get(0).ok_or(bad_argument!(
         "Variant not found in request. Provide at least one entry in variant's list",
@@ -583,6 +1129,9 @@ async fn update_overrides(
                 overrides: variant.overrides,
                 override_id: None,
                 context_id: None,
+                holdout_control_for: existing_variant.holdout_control_for.clone(),
+                last_modified_by: Some(user.get_recorded_email()),
+                last_modified_at: Some(Utc::now()),
             }
         })
         .collect();
@@ -603,12 +1152,21 @@ async fn update_overrides(
     }
 
     // validating experiment against other active experiments based on permission flags
-    let flags = &state.experimentation_flags;
+    let flags =
+        get_experimentation_flags(&mut conn, &tenant, &state.experimentation_flags)?;
+    let composite_dimensions = fetch_composite_dimensions(&state, &tenant)?;
+    let cardinalities = match flags.overlap_threshold {
+        Some(_) => fetch_dimension_cardinalities(&state, &tenant)?,
+        None => DimensionCardinalities::new(),
+    };
     let (valid, reason) = validate_experiment(
         &experiment.context,
+        &experiment.namespace,
         &override_keys,
         Some(experiment_id),
         &flags,
+        &composite_dimensions,
+        &cardinalities,
         &mut conn,
     )?;
     if !valid {
@@ -694,8 +1252,10 @@ async fn update_overrides(
         .set((
             experiments::variants.eq(new_variants_json),
             experiments::override_keys.eq(override_keys),
+            experiments::tags.eq(payload.tags.unwrap_or(experiment.tags)),
+            experiments::description.eq(payload.description.or(experiment.description)),
             experiments::last_modified.eq(Utc::now()),
-            experiments::last_modified_by.eq(user.get_email()),
+            experiments::last_modified_by.eq(user.get_recorded_email()),
         ))
         .get_result::<Experiment>(&mut conn)?;
 
@@ -751,3 +1311,140 @@ async fn get_audit_logs(
         "data": logs
     })))
 }
+
+fn hash_toss(context: &Map<String, Value>) -> superposition::Result<i8> {
+    let user_id = match context.get("user_id") {
+        Some(Value::String(user_id)) => user_id.clone(),
+        Some(user_id) => user_id.to_string(),
+        None => {
+            return Err(bad_argument!(
+                "user_id is required in every context for the hash_user_id toss strategy"
+            ))
+        }
+    };
+    let hash = blake3::hash(user_id.as_bytes());
+    let bytes: [u8; 8] = hash.as_bytes()[..8].try_into().unwrap_or_default();
+    Ok((u64::from_le_bytes(bytes) % 100) as i8)
+}
+
+#[post("/{id}/simulate")]
+async fn simulate(
+    params: web::Path<i64>,
+    req: web::Json<SimulateRequest>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<HashMap<String, u64>>> {
+    let DbConnection(mut conn) = db_conn;
+    let SimulateRequest {
+        contexts,
+        toss_strategy,
+    } = req.into_inner();
+
+    let experiment = get_experiment(params.into_inner(), &mut conn)?;
+    let variants: Vec<Variant> =
+        serde_json::from_value(experiment.variants).map_err(|e| {
+            log::error!("failed to parse experiment variants: {e}");
+            unexpected_error!("Something went wrong")
+        })?;
+    let traffic = u8::try_from(experiment.traffic_percentage).map_err(|e| {
+        log::error!("invalid traffic percentage for experiment: {e}");
+        unexpected_error!("Something went wrong")
+    })?;
+
+    let mut distribution: HashMap<String, u64> = variants
+        .iter()
+        .map(|variant| (variant.id.clone(), 0))
+        .collect();
+    let mut rng = rand::thread_rng();
+    for context in contexts {
+        let toss = match toss_strategy {
+            TossStrategy::HashUserId => hash_toss(&context)?,
+            TossStrategy::Random => rng.gen_range(0..100),
+        };
+        let context = Value::Object(context);
+        if jsonlogic::apply(&experiment.context, &context) != Ok(Value::Bool(true)) {
+            continue;
+        }
+        if let Some(variant) =
+            superposition_core::decide_variant(traffic, &variants, toss, |v| {
+                v.variant_type == VariantType::EXPERIMENTAL
+            })
+        {
+            *distribution.entry(variant.id.clone()).or_default() += 1;
+        }
+    }
+
+    Ok(Json(distribution))
+}
+
+#[post("/active_for_context")]
+async fn active_for_context(
+    req: web::Json<ActiveForContextRequest>,
+    db_conn: DbConnection,
+) -> superposition::Result<Json<Vec<ActiveExperiment>>> {
+    let DbConnection(mut conn) = db_conn;
+    let ActiveForContextRequest { context, toss } = req.into_inner();
+    let context = Value::Object(context);
+
+    let running_experiments: Vec<Experiment> = experiments::experiments
+        .filter(experiments::status.eq_any([
+            ExperimentStatusType::CREATED,
+            ExperimentStatusType::INPROGRESS,
+        ]))
+        .order(experiments::created_at.desc())
+        .load(&mut conn)?;
+
+    let mut active_experiments = Vec::new();
+    for experiment in running_experiments {
+        if jsonlogic::apply(&experiment.context, &context) != Ok(Value::Bool(true)) {
+            continue;
+        }
+        let variants: Vec<Variant> = serde_json::from_value(experiment.variants.clone())
+            .map_err(|e| {
+                log::error!("failed to parse variants of {}: {e}", experiment.id);
+                unexpected_error!("Something went wrong")
+            })?;
+        let traffic = u8::try_from(experiment.traffic_percentage).map_err(|e| {
+            log::error!("invalid traffic percentage for {}: {e}", experiment.id);
+            unexpected_error!("Something went wrong")
+        })?;
+        let variant_id =
+            superposition_core::decide_variant(traffic, &variants, toss, |v| {
+                v.variant_type == VariantType::EXPERIMENTAL
+            })
+            .map(|variant| variant.id.clone());
+        active_experiments.push(ActiveExperiment {
+            experiment: ExperimentResponse::from(experiment),
+            variant_id,
+        });
+    }
+
+    Ok(Json(active_experiments))
+}
+
+/// Traces the evaluation of the context of an experiment against a user
+/// context, for finding out why an experiment does not get any traffic.
+/// Available to admins, or to everyone in `DEV`.
+#[post("/{id}/debug_context")]
+async fn debug_context(
+    state: Data<AppState>,
+    params: web::Path<i64>,
+    req: web::Json<DebugContextRequest>,
+    db_conn: DbConnection,
+    user: User,
+) -> superposition::Result<Json<DebugContextResponse>> {
+    if !matches!(state.app_env, AppEnv::DEV) {
+        validate_admin(&state, &user)?;
+    }
+    let DbConnection(mut conn) = db_conn;
+    let experiment = get_experiment(params.into_inner(), &mut conn)?;
+    let context = Value::Object(req.into_inner().context);
+
+    let trace = trace_jsonlogic(&experiment.context, &context);
+    let matched =
+        jsonlogic::apply(&experiment.context, &context) == Ok(Value::Bool(true));
+    Ok(Json(DebugContextResponse {
+        experiment_id: experiment.id.to_string(),
+        matched,
+        trace,
+    }))
+}
@@ -1,26 +1,53 @@
-use super::types::{Variant, VariantType};
+use super::types::{
+    EvaluationStep, RampHistoryEntry, TimelineEvent, TimelineEventType, Variant,
+    VariantType,
+};
 use crate::db::models::{Experiment, ExperimentStatusType};
 use diesel::pg::PgConnection;
 use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
 use serde_json::{Map, Value};
-use service_utils::helpers::extract_dimensions;
+use service_utils::helpers::{
+    extract_dimensions, extract_dimensions_with_composites, CompositeDimensions,
+};
 use service_utils::service::types::ExperimentationFlags;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use service_utils::{bad_argument, result as superposition};
 
 pub fn check_variant_types(variants: &Vec<Variant>) -> superposition::Result<()> {
     let mut experimental_variant_cnt = 0;
     let mut control_variant_cnt = 0;
+    let mut holdout_keys: HashSet<&str> = HashSet::new();
 
     for variant in variants {
-        match variant.variant_type {
-            VariantType::CONTROL => {
+        match (&variant.variant_type, &variant.holdout_control_for) {
+            (VariantType::CONTROL, None) => {
                 control_variant_cnt += 1;
             }
-            VariantType::EXPERIMENTAL => {
+            (VariantType::CONTROL, Some(key)) => {
+                if !variant.overrides.contains_key(key) {
+                    return Err(bad_argument!(
+                        "Holdout control variant {} does not override its holdout_control_for key {}",
+                        variant.id,
+                        key
+                    ));
+                }
+                if !holdout_keys.insert(key) {
+                    return Err(bad_argument!(
+                        "Multiple holdout control variants found for key {}. Ensure each holdout control variant has a distinct holdout_control_for key",
+                        key
+                    ));
+                }
+            }
+            (VariantType::EXPERIMENTAL, None) => {
                 experimental_variant_cnt += 1;
             }
+            (VariantType::EXPERIMENTAL, Some(_)) => {
+                return Err(bad_argument!(
+                    "holdout_control_for can only be set on control variants, found it on {}",
+                    variant.id
+                ));
+            }
         }
     }
 
@@ -50,12 +77,48 @@ pub fn validate_override_keys(override_keys: &Vec<String>) -> superposition::Res
     Ok(())
 }
 
+pub fn validate_tags(tags: &[String]) -> superposition::Result<()> {
+    let mut tag_set: HashSet<&str> = HashSet::new();
+    for tag in tags {
+        if tag.trim().is_empty() {
+            return Err(bad_argument!("tags cannot be empty strings"));
+        }
+        if !tag_set.insert(tag) {
+            return Err(bad_argument!(
+                "tags are not unique. Remove duplicate entries in tags"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_namespace(namespace: &str) -> superposition::Result<()> {
+    if namespace.trim().is_empty() {
+        return Err(bad_argument!("namespace cannot be an empty string"));
+    }
+    if namespace.contains(':') {
+        return Err(bad_argument!("namespace cannot contain ':'"));
+    }
+
+    Ok(())
+}
+
+/// Override keys only conflict with keys of the same namespace, so overlap
+/// checks compare `namespace:key` pairs.
+pub fn namespaced_key(namespace: &str, key: &str) -> String {
+    format!("{namespace}:{key}")
+}
+
 pub fn are_overlapping_contexts(
     context_a: &Value,
     context_b: &Value,
+    composite_dimensions: &CompositeDimensions,
 ) -> superposition::Result<bool> {
-    let dimensions_a = extract_dimensions(context_a)?;
-    let dimensions_b = extract_dimensions(context_b)?;
+    let dimensions_a =
+        extract_dimensions_with_composites(context_a, composite_dimensions)?;
+    let dimensions_b =
+        extract_dimensions_with_composites(context_b, composite_dimensions)?;
 
     let dim_a_keys = dimensions_a.keys();
     let dim_b_keys = dimensions_b.keys();
@@ -80,6 +143,66 @@ pub fn are_overlapping_contexts(
     Ok(is_overlapping)
 }
 
+/// Dimension names mapped to the number of values they can take, known for
+/// enum dimensions only.
+pub type DimensionCardinalities = HashMap<String, usize>;
+
+/// Estimates the share of users matched by both contexts, assuming dimensions
+/// are independent and their values equally likely. Contexts requiring
+/// different values of a dimension share no users. A dimension of unknown
+/// cardinality is assumed to take the values either context mentions plus one
+/// more, the fewest it can take for a context to narrow it down.
+pub fn estimate_intersection(
+    context_a: &Value,
+    context_b: &Value,
+    cardinalities: &DimensionCardinalities,
+) -> superposition::Result<f32> {
+    let dimensions_a = extract_dimensions(context_a)?;
+    let dimensions_b = extract_dimensions(context_b)?;
+    let only_in_b = dimensions_b
+        .keys()
+        .filter(|dimension| !dimensions_a.contains_key(*dimension));
+
+    let mut share = 1.0;
+    for dimension in dimensions_a.keys().chain(only_in_b) {
+        let values_a = dimensions_a.get(dimension).map(dimension_values);
+        let values_b = dimensions_b.get(dimension).map(dimension_values);
+        let matched = match (&values_a, &values_b) {
+            (Some(values_a), Some(values_b)) => values_a
+                .iter()
+                .filter(|value| values_b.contains(value))
+                .count(),
+            (Some(values), None) | (None, Some(values)) => values.len(),
+            (None, None) => continue,
+        };
+        if matched == 0 {
+            return Ok(0.0);
+        }
+
+        let mut mentioned: Vec<&Value> = Vec::new();
+        for value in values_a.iter().chain(values_b.iter()).flatten() {
+            if !mentioned.contains(value) {
+                mentioned.push(value);
+            }
+        }
+        let cardinality = cardinalities
+            .get(dimension)
+            .copied()
+            .filter(|cardinality| *cardinality > 0)
+            .unwrap_or(mentioned.len() + 1);
+        share *= (matched as f32 / cardinality as f32).min(1.0);
+    }
+    Ok(share)
+}
+
+/// The values a dimension is matched against, several for an `in` condition.
+fn dimension_values(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    }
+}
+
 pub fn check_variant_override_coverage(
     variant_override: &Map<String, Value>,
     override_keys: &Vec<String>,
@@ -111,9 +234,12 @@ pub fn check_variants_override_coverage(
 
 pub fn is_valid_experiment(
     context: &Value,
+    namespace: &str,
     override_keys: &Vec<String>,
     flags: &ExperimentationFlags,
     active_experiments: &Vec<Experiment>,
+    composite_dimensions: &CompositeDimensions,
+    cardinalities: &DimensionCardinalities,
 ) -> superposition::Result<(bool, String)> {
     let mut valid_experiment = true;
     let mut invalid_reason = String::new();
@@ -121,24 +247,45 @@ pub fn is_valid_experiment(
         || !flags.allow_diff_keys_overlapping_ctx
         || !flags.allow_same_keys_non_overlapping_ctx
     {
-        let override_keys_set: HashSet<_> = override_keys.iter().collect();
+        let override_keys_set: HashSet<String> = override_keys
+            .iter()
+            .map(|key| namespaced_key(namespace, key))
+            .collect();
         for active_experiment in active_experiments.iter() {
             let are_overlapping =
-                are_overlapping_contexts(context, &active_experiment.context)
+                are_overlapping_contexts(
+                    context,
+                    &active_experiment.context,
+                    composite_dimensions,
+                )
                     .map_err(|e| {
                         log::info!("experiment validation failed with error: {e}");
                         bad_argument!(
                             "Context overlap validation failed, given context overlaps with a running experiment's context. Overlapping contexts are not allowed currently as per your configuration"
                         )
                     })?;
-
-            let have_intersecting_key_set = active_experiment
+            let are_overlapping = match flags.overlap_threshold {
+                Some(threshold) if are_overlapping => {
+                    estimate_intersection(
+                        context,
+                        &active_experiment.context,
+                        cardinalities,
+                    )? > threshold
+                }
+                _ => are_overlapping,
+            };
+
+            let active_keys: Vec<String> = active_experiment
                 .override_keys
+                .iter()
+                .map(|key| namespaced_key(&active_experiment.namespace, key))
+                .collect();
+
+            let have_intersecting_key_set = active_keys
                 .iter()
                 .any(|key| override_keys_set.contains(key));
 
-            let same_key_set = active_experiment
-                .override_keys
+            let same_key_set = active_keys
                 .iter()
                 .all(|key| override_keys_set.contains(key));
 
@@ -167,9 +314,12 @@ pub fn is_valid_experiment(
 
 pub fn validate_experiment(
     context: &Value,
+    namespace: &str,
     override_keys: &Vec<String>,
     experiment_id: Option<i64>,
     flags: &ExperimentationFlags,
+    composite_dimensions: &CompositeDimensions,
+    cardinalities: &DimensionCardinalities,
     conn: &mut PgConnection,
 ) -> superposition::Result<(bool, String)> {
     use crate::db::schema::experiments::dsl as experiments_dsl;
@@ -185,7 +335,15 @@ pub fn validate_experiment(
         )
         .load(conn)?;
 
-    is_valid_experiment(context, override_keys, flags, &active_experiments)
+    is_valid_experiment(
+        context,
+        namespace,
+        override_keys,
+        flags,
+        &active_experiments,
+        composite_dimensions,
+        cardinalities,
+    )
 }
 
 pub fn add_variant_dimension_to_ctx(
@@ -228,3 +386,158 @@ pub fn add_variant_dimension_to_ctx(
 pub fn extract_override_keys(overrides: &Map<String, Value>) -> HashSet<String> {
     overrides.keys().map(String::from).collect()
 }
+
+pub const EXPERIMENT_CSV_HEADER: &str = "id,name,status,created_at,created_by,\
+started_at,concluded_at,concluded_variant_id,final_traffic_pct\n";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One CSV line for `experiment`, matching the columns of `EXPERIMENT_CSV_HEADER`.
+/// An experiment is considered started at its first ramp.
+pub fn experiment_csv_row(experiment: &Experiment) -> String {
+    let started_at = experiment
+        .ramp_history
+        .as_array()
+        .and_then(|entries| entries.first())
+        .and_then(|entry| entry.get("timestamp"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let is_concluded = experiment.status == ExperimentStatusType::CONCLUDED;
+    let concluded_at = if is_concluded {
+        experiment.last_modified.to_rfc3339()
+    } else {
+        String::new()
+    };
+    let fields = [
+        experiment.id.to_string(),
+        csv_field(&experiment.name),
+        experiment.status.to_string(),
+        experiment.created_at.to_rfc3339(),
+        csv_field(&experiment.created_by),
+        started_at.to_string(),
+        concluded_at,
+        csv_field(experiment.chosen_variant.as_deref().unwrap_or_default()),
+        experiment.traffic_percentage.to_string(),
+    ];
+    fields.join(",") + "\n"
+}
+
+/// Quoted `ETag` of a page of the experiment list, derived from the ids and
+/// modification times of its experiments and the total number of matches.
+pub fn experiment_list_etag(experiments: &[Experiment], total_items: i64) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(total_items.to_string().as_bytes());
+    for experiment in experiments {
+        hasher.update(
+            format!(
+                ";{}:{}",
+                experiment.id,
+                experiment.last_modified.timestamp_micros()
+            )
+            .as_bytes(),
+        );
+    }
+    format!("\"{}\"", hasher.finalize().to_hex())
+}
+
+/// Whether an `If-None-Match` header value covers `etag`.
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|candidate| candidate.trim().trim_start_matches("W/"))
+        .any(|candidate| candidate == "*" || candidate == etag)
+}
+
+/// Lifecycle events of `experiment` in chronological order: its creation,
+/// every traffic change recorded in `ramp_history` and its conclusion.
+pub fn experiment_timeline(experiment: &Experiment) -> Vec<TimelineEvent> {
+    let created = TimelineEvent {
+        event: TimelineEventType::Created,
+        actor: experiment.created_by.clone(),
+        timestamp: experiment.created_at,
+        old_value: None,
+        new_value: Some(Value::from(0)),
+    };
+    let ramps = experiment
+        .ramp_history
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            serde_json::from_value::<RampHistoryEntry>(entry.clone())
+                .map_err(|e| {
+                    log::error!("skipping malformed ramp history entry {entry}: {e}")
+                })
+                .ok()
+        })
+        .map(|entry| TimelineEvent {
+            event: TimelineEventType::Ramped,
+            actor: entry.actor,
+            timestamp: entry.timestamp,
+            old_value: Some(Value::from(entry.old_traffic)),
+            new_value: Some(Value::from(entry.new_traffic)),
+        });
+    let concluded =
+        (experiment.status == ExperimentStatusType::CONCLUDED).then(|| TimelineEvent {
+            event: TimelineEventType::Concluded,
+            actor: experiment.last_modified_by.clone(),
+            timestamp: experiment.last_modified,
+            old_value: None,
+            new_value: experiment.chosen_variant.clone().map(Value::String),
+        });
+
+    let mut timeline = std::iter::once(created)
+        .chain(ramps)
+        .chain(concluded)
+        .collect::<Vec<TimelineEvent>>();
+    // stable, so events sharing a timestamp keep their lifecycle order
+    timeline.sort_by_key(|event| event.timestamp);
+    timeline
+}
+
+/// Evaluates every operator of the JSON logic `expression` against `data` on
+/// its own, innermost first, to show which part of a context fails to match.
+/// Operands skipped by short-circuiting operators are evaluated as well.
+pub fn trace_jsonlogic(expression: &Value, data: &Value) -> Vec<EvaluationStep> {
+    let mut trace = Vec::new();
+    trace_expression(expression, data, String::new(), &mut trace);
+    trace
+}
+
+fn trace_expression(
+    expression: &Value,
+    data: &Value,
+    path: String,
+    trace: &mut Vec<EvaluationStep>,
+) {
+    match expression {
+        Value::Object(operation) if operation.len() == 1 => {
+            for (operator, operands) in operation {
+                let operator = operator.replace('~', "~0").replace('/', "~1");
+                trace_expression(operands, data, format!("{path}/{operator}"), trace);
+            }
+            let (result, error) = match jsonlogic::apply(expression, data) {
+                Ok(result) => (Some(result), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            trace.push(EvaluationStep {
+                path,
+                expression: expression.clone(),
+                result,
+                error,
+            });
+        }
+        Value::Array(operands) => {
+            for (index, operand) in operands.iter().enumerate() {
+                trace_expression(operand, data, format!("{path}/{index}"), trace);
+            }
+        }
+        _ => (),
+    }
+}
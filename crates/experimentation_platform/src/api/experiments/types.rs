@@ -1,7 +1,7 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use service_utils::helpers::deserialize_stringified_list;
+use service_utils::helpers::{deserialize_parsed_list, deserialize_stringified_list};
 
 use crate::db::models::{self, ExperimentStatusType};
 
@@ -18,6 +18,32 @@ pub struct Variant {
     pub context_id: Option<String>,
     pub override_id: Option<String>,
     pub overrides: Map<String, Value>,
+    /// Marks a CONTROL variant as the holdout control for a single override
+    /// key. Such variants come in addition to the experiment's global control.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holdout_control_for: Option<String>,
+    /// Unset for variants stored before modifications were recorded.
+    #[serde(default)]
+    pub last_modified_by: Option<String>,
+    #[serde(default)]
+    pub last_modified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct VariantResponse {
+    pub id: String,
+    pub variant_type: VariantType,
+    pub overrides: Map<String, Value>,
+}
+
+impl From<Variant> for VariantResponse {
+    fn from(variant: Variant) -> Self {
+        VariantResponse {
+            id: variant.id,
+            variant_type: variant.variant_type,
+            overrides: variant.overrides,
+        }
+    }
 }
 
 /********** Experiment Create Req Types ************/
@@ -28,6 +54,18 @@ pub struct ExperimentCreateRequest {
 
     pub context: Value,
     pub variants: Vec<Variant>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    pub description: Option<String>,
+}
+
+/// Namespace of experiments created without one.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+pub fn default_namespace() -> String {
+    String::from(DEFAULT_NAMESPACE)
 }
 
 #[derive(Serialize)]
@@ -62,6 +100,14 @@ pub struct ExperimentResponse {
     pub context: Value,
     pub variants: Value,
     pub chosen_variant: Option<String>,
+    #[serde(default)]
+    pub ramp_history: Value,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl From<models::Experiment> for ExperimentResponse {
@@ -80,6 +126,10 @@ impl From<models::Experiment> for ExperimentResponse {
             context: experiment.context,
             variants: experiment.variants,
             chosen_variant: experiment.chosen_variant,
+            ramp_history: experiment.ramp_history,
+            tags: experiment.tags,
+            namespace: experiment.namespace,
+            description: experiment.description,
         }
     }
 }
@@ -98,6 +148,35 @@ pub struct ConcludeExperimentRequest {
     pub chosen_variant: String,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ConcludeWithRolloutRequest {
+    pub winning_variant_id: String,
+}
+
+#[derive(Serialize)]
+pub struct ConcludeWithRolloutResponse {
+    pub experiment: ExperimentResponse,
+    pub context_id: String,
+    pub override_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BulkConcludeQuery {
+    pub tag: String,
+}
+
+#[derive(Serialize)]
+pub struct BulkConcludeFailure {
+    pub experiment_id: String,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+pub struct BulkConcludeResponse {
+    pub concluded: Vec<ExperimentResponse>,
+    pub failed: Vec<BulkConcludeFailure>,
+}
+
 /********** Context Bulk API Type *************/
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -131,8 +210,7 @@ pub enum ContextBulkResponse {
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct StatusTypes(
-    #[serde(deserialize_with = "deserialize_stringified_list")]
-    pub  Vec<ExperimentStatusType>,
+    #[serde(deserialize_with = "deserialize_parsed_list")] pub Vec<ExperimentStatusType>,
 );
 
 #[derive(Deserialize, Debug)]
@@ -142,6 +220,34 @@ pub struct ListFilters {
     pub to_date: Option<DateTime<Utc>>,
     pub page: Option<i64>,
     pub count: Option<i64>,
+    pub sort_by: Option<ExperimentSortBy>,
+    pub tag: Option<String>,
+    pub has_override_key: Option<String>,
+    pub description_contains: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentSortBy {
+    CreatedAtDesc,
+    CreatedAtAsc,
+    TrafficDesc,
+    TrafficAsc,
+    NameAsc,
+    Status,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
 }
 
 /********** Ramp API type **********/
@@ -150,6 +256,33 @@ pub struct RampRequest {
     pub traffic_percentage: u64,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RampHistoryEntry {
+    pub old_traffic: i32,
+    pub new_traffic: i32,
+    pub actor: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/********** Timeline API type **********/
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventType {
+    Created,
+    Ramped,
+    Concluded,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TimelineEvent {
+    pub event: TimelineEventType,
+    pub actor: String,
+    pub timestamp: DateTime<Utc>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+}
+
 /********** Update API type ********/
 
 #[derive(Deserialize, Debug)]
@@ -161,6 +294,8 @@ pub struct VariantUpdateRequest {
 #[derive(Deserialize, Debug)]
 pub struct OverrideKeysUpdateRequest {
     pub variants: Vec<VariantUpdateRequest>,
+    pub tags: Option<Vec<String>>,
+    pub description: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -185,3 +320,61 @@ pub struct AuditQueryFilters {
     pub count: Option<i64>,
     pub page: Option<i64>,
 }
+
+/********** Simulate Types *************/
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TossStrategy {
+    HashUserId,
+    Random,
+}
+
+#[derive(Deserialize)]
+pub struct SimulateRequest {
+    pub contexts: Vec<Map<String, Value>>,
+    pub toss_strategy: TossStrategy,
+}
+
+/********** Active For Context Types *************/
+
+#[derive(Deserialize)]
+pub struct ActiveForContextRequest {
+    pub context: Map<String, Value>,
+    pub toss: i8,
+}
+
+#[derive(Serialize)]
+pub struct ActiveExperiment {
+    #[serde(flatten)]
+    pub experiment: ExperimentResponse,
+    /// the variant the toss falls into, `None` when it is outside the traffic
+    pub variant_id: Option<String>,
+}
+
+/********** Debug Context Types *************/
+
+#[derive(Deserialize)]
+pub struct DebugContextRequest {
+    pub context: Map<String, Value>,
+}
+
+/// The outcome of one operator of an experiment context, evaluated on its own.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct EvaluationStep {
+    /// JSON pointer to the operator within the experiment context
+    pub path: String,
+    pub expression: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DebugContextResponse {
+    pub experiment_id: String,
+    pub matched: bool,
+    /// innermost operators first, ending with the whole context
+    pub trace: Vec<EvaluationStep>,
+}
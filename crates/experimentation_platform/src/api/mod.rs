@@ -1 +1,3 @@
+pub mod experimentation_config;
 pub mod experiments;
+pub mod webhooks;
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use actix_web::{
+    delete, get, post,
+    web::{Data, Json, Path},
+    HttpResponse, Scope,
+};
+use chrono::Utc;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use service_utils::{
+    bad_argument,
+    helpers::validate_admin,
+    not_found, result as superposition,
+    service::types::{AppState, DbConnection, Tenant},
+};
+use superposition_types::User;
+
+use super::types::WebhookCreateRequest;
+use crate::db::{models::Webhook, schema::webhooks::dsl};
+
+pub fn endpoints() -> Scope {
+    Scope::new("")
+        .service(create)
+        .service(list_webhooks)
+        .service(delete_webhook)
+}
+
+#[post("")]
+async fn create(
+    state: Data<AppState>,
+    req: Json<WebhookCreateRequest>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<Webhook>> {
+    // deliveries are sent from the server, so only admins get to pick where
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+    let req = req.into_inner();
+
+    let url = reqwest::Url::parse(&req.url)
+        .map_err(|err| bad_argument!("Invalid webhook url {}: {}", req.url, err))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(bad_argument!("Webhook url should use http or https"));
+    }
+    if req.secret.trim().is_empty() {
+        return Err(bad_argument!("Webhook secret cannot be empty"));
+    }
+    let events: HashSet<&str> = req.events.iter().map(|event| event.as_str()).collect();
+    if events.is_empty() {
+        return Err(bad_argument!("Provide at least one event to subscribe to"));
+    }
+
+    let id = state
+        .snowflake_generator
+        .lock()
+        .unwrap()
+        .real_time_generate();
+    let webhook = Webhook {
+        id,
+        tenant: tenant.as_str().to_string(),
+        url: url.to_string(),
+        secret: req.secret,
+        events: events.into_iter().map(String::from).collect(),
+        created_at: Utc::now(),
+        created_by: user.get_recorded_email(),
+    };
+
+    let inserted: Webhook = diesel::insert_into(dsl::webhooks)
+        .values(&webhook)
+        .get_result(&mut conn)?;
+    log::info!(
+        "webhook {} for {} registered by {}",
+        inserted.id,
+        inserted.url,
+        inserted.created_by
+    );
+
+    Ok(Json(inserted))
+}
+
+#[get("")]
+async fn list_webhooks(
+    state: Data<AppState>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<Json<Vec<Webhook>>> {
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+    let registered: Vec<Webhook> = dsl::webhooks
+        .filter(dsl::tenant.eq(tenant.as_str()))
+        .order(dsl::created_at.desc())
+        .load(&mut conn)?;
+    Ok(Json(registered))
+}
+
+#[delete("/{id}")]
+async fn delete_webhook(
+    state: Data<AppState>,
+    path: Path<i64>,
+    db_conn: DbConnection,
+    tenant: Tenant,
+    user: User,
+) -> superposition::Result<HttpResponse> {
+    validate_admin(&state, &user)?;
+    let DbConnection(mut conn) = db_conn;
+    let id = path.into_inner();
+    let deleted = diesel::delete(
+        dsl::webhooks
+            .filter(dsl::id.eq(id))
+            .filter(dsl::tenant.eq(tenant.as_str())),
+    )
+    .execute(&mut conn)?;
+    if deleted == 0 {
+        return Err(not_found!("Webhook {} doesn't exist", id));
+    }
+    log::info!("webhook {id} deleted by {}", user.get_recorded_email());
+    Ok(HttpResponse::NoContent().finish())
+}
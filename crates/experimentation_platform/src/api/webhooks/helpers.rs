@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::{
+    r2d2::{ConnectionManager, PooledConnection},
+    ExpressionMethods, PgArrayExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+};
+use hmac::{Hmac, Mac, NewMac};
+use serde_json::json;
+use service_utils::service::types::Tenant;
+use sha2::Sha256;
+
+use super::types::WebhookEvent;
+use crate::{
+    api::experiments::types::ExperimentResponse,
+    db::{
+        models::{Experiment, Webhook},
+        schema::webhooks::dsl,
+    },
+};
+
+/// How long a receiver gets to accept a delivery, so that a hanging receiver
+/// does not hold up the subscribers after it.
+const DELIVERY_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Looks up the tenant's webhooks subscribed to `event` and delivers the
+/// experiment payload to each of them in the background. Delivery failures
+/// are logged and never fail the request that triggered the event.
+pub fn dispatch_event(
+    conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
+    tenant: &Tenant,
+    event: WebhookEvent,
+    experiment: &Experiment,
+) {
+    let subscribers: Vec<Webhook> = match dsl::webhooks
+        .filter(dsl::tenant.eq(tenant.as_str()))
+        .filter(dsl::events.contains(vec![event.as_str()]))
+        .load(conn)
+    {
+        Ok(subscribers) => subscribers,
+        Err(err) => {
+            log::error!("failed to fetch webhooks for {}: {}", event.as_str(), err);
+            return;
+        }
+    };
+    if subscribers.is_empty() {
+        return;
+    }
+
+    let payload = json!({
+        "event": event,
+        "tenant": tenant.as_str(),
+        "timestamp": Utc::now(),
+        "experiment": ExperimentResponse::from(experiment.clone()),
+    })
+    .to_string();
+
+    actix_web::rt::spawn(async move {
+        let http_client = match reqwest::Client::builder()
+            .connect_timeout(DELIVERY_CONNECT_TIMEOUT)
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+        {
+            Ok(http_client) => http_client,
+            Err(err) => {
+                log::error!("failed to build the webhook http client: {}", err);
+                return;
+            }
+        };
+        for webhook in subscribers {
+            let signature = sign_payload(&webhook.secret, payload.as_bytes());
+            let response = http_client
+                .post(&webhook.url)
+                .header("Content-Type", "application/json")
+                .header("X-Signature", format!("sha256={signature}"))
+                .body(payload.clone())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            if let Err(err) = response {
+                log::error!(
+                    "failed to deliver {} to webhook {}: {}",
+                    event.as_str(),
+                    webhook.id,
+                    err
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        assert_eq!(
+            sign_payload("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "experiment.started")]
+    ExperimentStarted,
+    #[serde(rename = "experiment.ramped")]
+    ExperimentRamped,
+    #[serde(rename = "experiment.concluded")]
+    ExperimentConcluded,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ExperimentStarted => "experiment.started",
+            Self::ExperimentRamped => "experiment.ramped",
+            Self::ExperimentConcluded => "experiment.concluded",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct WebhookCreateRequest {
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+}
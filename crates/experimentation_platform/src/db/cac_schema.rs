@@ -0,0 +1,16 @@
+//! Read-only view of the CAC tables experiments are validated against, which
+//! live in the tenant's CAC schema. Only the columns read here are declared.
+
+diesel::table! {
+    composite_dimensions (name) {
+        name -> Text,
+        component_dimensions -> Array<Text>,
+    }
+}
+
+diesel::table! {
+    dimensions (dimension) {
+        dimension -> Varchar,
+        allowed_values -> Nullable<Json>,
+    }
+}
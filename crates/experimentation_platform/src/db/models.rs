@@ -1,5 +1,6 @@
 use crate::db::schema::*;
 use chrono::{DateTime, NaiveDateTime, Utc};
+use std::{fmt, str::FromStr};
 
 use diesel::{Insertable, Queryable, QueryableByName, Selectable};
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,45 @@ pub enum ExperimentStatusType {
     INPROGRESS,
 }
 
+impl fmt::Display for ExperimentStatusType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::CREATED => "CREATED",
+            Self::CONCLUDED => "CONCLUDED",
+            Self::INPROGRESS => "INPROGRESS",
+        };
+        f.write_str(status)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownStatusError(pub String);
+
+impl fmt::Display for UnknownStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown experiment status `{}`, expected one of CREATED, INPROGRESS, CONCLUDED",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownStatusError {}
+
+impl FromStr for ExperimentStatusType {
+    type Err = UnknownStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "CREATED" => Ok(Self::CREATED),
+            "CONCLUDED" => Ok(Self::CONCLUDED),
+            "INPROGRESS" => Ok(Self::INPROGRESS),
+            other => Err(UnknownStatusError(other.to_string())),
+        }
+    }
+}
+
 #[derive(QueryableByName, Queryable, Selectable, Insertable, Serialize, Clone, Debug)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 #[diesel(primary_key(id))]
@@ -34,6 +74,10 @@ pub struct Experiment {
     pub variants: Value,
     pub last_modified_by: String,
     pub chosen_variant: Option<String>,
+    pub ramp_history: Value,
+    pub tags: Vec<String>,
+    pub namespace: String,
+    pub description: Option<String>,
 }
 
 pub type Experiments = Vec<Experiment>;
@@ -52,3 +96,29 @@ pub struct EventLog {
     pub new_data: Option<Value>,
     pub query: String,
 }
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Clone, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(primary_key(id))]
+pub struct Webhook {
+    pub id: i64,
+    pub tenant: String,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Clone, Debug)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(table_name = experimentation_config)]
+#[diesel(primary_key(tenant_id, flag_name))]
+pub struct ExperimentationConfig {
+    pub tenant_id: String,
+    pub flag_name: String,
+    pub flag_value: bool,
+    pub last_modified_at: DateTime<Utc>,
+    pub last_modified_by: String,
+}
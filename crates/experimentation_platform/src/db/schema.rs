@@ -552,6 +552,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    experimentation_config (tenant_id, flag_name) {
+        tenant_id -> Text,
+        flag_name -> Text,
+        flag_value -> Bool,
+        last_modified_at -> Timestamptz,
+        last_modified_by -> Text,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ExperimentStatusType;
@@ -569,6 +579,22 @@ diesel::table! {
         variants -> Json,
         last_modified_by -> Text,
         chosen_variant -> Nullable<Text>,
+        ramp_history -> Json,
+        tags -> Array<Text>,
+        namespace -> Text,
+        description -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Int8,
+        tenant -> Text,
+        url -> Text,
+        secret -> Text,
+        events -> Array<Text>,
+        created_at -> Timestamptz,
+        created_by -> Text,
     }
 }
 
@@ -615,5 +641,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     event_log_y2026m10,
     event_log_y2026m11,
     event_log_y2026m12,
+    experimentation_config,
     experiments,
+    webhooks,
 );
@@ -1,8 +1,11 @@
 use chrono::Utc;
-use experimentation_platform::api::experiments::helpers;
-use experimentation_platform::db::models::{Experiment, ExperimentStatusType};
+use experimentation_platform::api::experiments::helpers::{self, DimensionCardinalities};
+use experimentation_platform::api::experiments::types::Variant;
+use experimentation_platform::db::models::{
+    Experiment, ExperimentStatusType, UnknownStatusError,
+};
 use serde_json::{json, Map, Value};
-use service_utils::helpers::extract_dimensions;
+use service_utils::helpers::{extract_dimensions, CompositeDimensions};
 use service_utils::result::AppError;
 use service_utils::service::types::ExperimentationFlags;
 
@@ -57,6 +60,10 @@ fn experiment_gen(
         context: context.clone(),
         variants: variants.clone(),
         chosen_variant: None,
+        ramp_history: json!([]),
+        tags: vec![],
+        namespace: "default".to_string(),
+        description: None,
     }
 }
 
@@ -116,29 +123,82 @@ fn test_are_overlapping_contexts() -> Result<(), AppError> {
 
     // both contexts with same dimensions
     assert_eq!(
-        helpers::are_overlapping_contexts(&context_a, &context_a)?,
+        helpers::are_overlapping_contexts(
+            &context_a,
+            &context_a,
+            &CompositeDimensions::new()
+        )?,
         true
     );
     // contexts with one different dimension
     assert_eq!(
-        helpers::are_overlapping_contexts(&context_a, &context_b)?,
+        helpers::are_overlapping_contexts(
+            &context_a,
+            &context_b,
+            &CompositeDimensions::new()
+        )?,
         false
     );
     // one context dimensions are subset of other
     assert_eq!(
-        helpers::are_overlapping_contexts(&context_a, &context_c)?,
+        helpers::are_overlapping_contexts(
+            &context_a,
+            &context_c,
+            &CompositeDimensions::new()
+        )?,
         true
     );
     // one context dimensions not a subset of other but have less dimensions that other
     assert_eq!(
-        helpers::are_overlapping_contexts(&context_a, &context_d)?,
+        helpers::are_overlapping_contexts(
+            &context_a,
+            &context_d,
+            &CompositeDimensions::new()
+        )?,
         false
     );
     // disjoint contexts
     assert_eq!(
-        helpers::are_overlapping_contexts(&context_c, &context_d)?,
+        helpers::are_overlapping_contexts(
+            &context_c,
+            &context_d,
+            &CompositeDimensions::new()
+        )?,
+        false
+    );
+    Ok(())
+}
+
+#[test]
+fn test_are_overlapping_contexts_with_composite_dimensions() -> Result<(), AppError> {
+    let composite_dimensions = CompositeDimensions::from_iter(vec![(
+        "device".to_string(),
+        vec!["os".to_string(), "clientId".to_string()],
+    )]);
+    let context_a = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("os1".to_string()),
+        Dimensions::CLIENT("testclient1".to_string()),
+    ]);
+    let context_b = single_dimension_ctx_gen(Dimensions::OS("os1".to_string()));
+
+    // subset of the components overlaps when they are independent dimensions
+    assert_eq!(
+        helpers::are_overlapping_contexts(
+            &context_a,
+            &context_b,
+            &CompositeDimensions::new()
+        )?,
+        true
+    );
+    // but not when they are compared as one composite dimension
+    assert_eq!(
+        helpers::are_overlapping_contexts(&context_a, &context_b, &composite_dimensions)?,
         false
     );
+    assert_eq!(
+        helpers::are_overlapping_contexts(&context_a, &context_a, &composite_dimensions)?,
+        true
+    );
     Ok(())
 }
 
@@ -181,6 +241,77 @@ fn test_check_variants_override_coverage() {
     );
 }
 
+#[test]
+fn test_variant_modification_is_unset_for_older_variants() {
+    let variant: Variant = serde_json::from_value(json!({
+        "id": "1-control",
+        "variant_type": "CONTROL",
+        "context_id": null,
+        "override_id": null,
+        "overrides": {}
+    }))
+    .unwrap();
+    assert_eq!(variant.last_modified_by, None);
+    assert_eq!(variant.last_modified_at, None);
+
+    let serialized = serde_json::to_value(&variant).unwrap();
+    assert_eq!(serialized["last_modified_at"], Value::Null);
+}
+
+#[test]
+fn test_check_variant_types_with_holdout_controls() {
+    let variants = |holdouts: Vec<(&str, Option<&str>)>| -> Vec<Variant> {
+        holdouts
+            .into_iter()
+            .map(|(variant_type, holdout_control_for)| {
+                serde_json::from_value(json!({
+                    "id": format!("{variant_type}-{holdout_control_for:?}"),
+                    "variant_type": variant_type,
+                    "overrides": { "key1": "value1", "key2": "value2" },
+                    "holdout_control_for": holdout_control_for
+                }))
+                .unwrap()
+            })
+            .collect()
+    };
+
+    // a holdout control per override key, on top of the global control
+    assert!(helpers::check_variant_types(&variants(vec![
+        ("CONTROL", None),
+        ("CONTROL", Some("key1")),
+        ("CONTROL", Some("key2")),
+        ("EXPERIMENTAL", None),
+    ]))
+    .is_ok());
+    // holdout controls do not replace the global control
+    assert!(helpers::check_variant_types(&variants(vec![
+        ("CONTROL", Some("key1")),
+        ("EXPERIMENTAL", None),
+    ]))
+    .is_err());
+    // two holdout controls for the same key
+    assert!(helpers::check_variant_types(&variants(vec![
+        ("CONTROL", None),
+        ("CONTROL", Some("key1")),
+        ("CONTROL", Some("key1")),
+        ("EXPERIMENTAL", None),
+    ]))
+    .is_err());
+    // a holdout control for a key that is not overridden
+    assert!(helpers::check_variant_types(&variants(vec![
+        ("CONTROL", None),
+        ("CONTROL", Some("key3")),
+        ("EXPERIMENTAL", None),
+    ]))
+    .is_err());
+    // only control variants can be holdouts
+    assert!(helpers::check_variant_types(&variants(vec![
+        ("CONTROL", None),
+        ("EXPERIMENTAL", Some("key1")),
+    ]))
+    .is_err());
+}
+
 /************************* No Restrictions *****************************************/
 
 #[test]
@@ -195,6 +326,7 @@ fn test_is_valid_experiment_no_restrictions_overlapping_experiment(
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -207,9 +339,12 @@ fn test_is_valid_experiment_no_restrictions_overlapping_experiment(
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (true, "".to_string())
     );
@@ -229,6 +364,7 @@ fn test_is_valid_experiment_no_restrictions_non_overlapping_experiment(
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -244,9 +380,12 @@ fn test_is_valid_experiment_no_restrictions_non_overlapping_experiment(
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (true, "".to_string())
     );
@@ -268,6 +407,7 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: false,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -280,9 +420,12 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -302,6 +445,7 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: false,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -314,9 +458,12 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -324,6 +471,44 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
     Ok(())
 }
 
+#[test]
+fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_experiment_other_namespace(
+) -> Result<(), AppError> {
+    let experiment_context = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("os1".to_string()),
+        Dimensions::CLIENT("testclient1".to_string()),
+    ]);
+    let experiment_override_keys = vec!["key1".to_string(), "key2".to_string()];
+    let flags = ExperimentationFlags {
+        allow_same_keys_overlapping_ctx: false,
+        allow_diff_keys_overlapping_ctx: true,
+        allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
+    };
+
+    let active_experiments = vec![experiment_gen(
+        &vec!["key1".to_string(), "key2".to_string()],
+        &experiment_context,
+        ExperimentStatusType::CREATED,
+        &json!(""),
+    )];
+
+    assert_eq!(
+        helpers::is_valid_experiment(
+            &experiment_context,
+            "checkout",
+            &experiment_override_keys,
+            &flags,
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
+        )?,
+        (true, "".to_string())
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_experiment_diff_keys(
 ) -> Result<(), AppError> {
@@ -336,6 +521,7 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: false,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -348,9 +534,12 @@ fn test_is_valid_experiment_restrict_same_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (true, "".to_string())
     );
@@ -372,6 +561,7 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: false,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -384,9 +574,12 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (true, "".to_string())
     );
@@ -406,6 +599,7 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: false,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -418,9 +612,12 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -440,6 +637,7 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: false,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -452,9 +650,12 @@ fn test_is_valid_experiment_restrict_diff_keys_overlapping_ctx_overlapping_exper
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -476,6 +677,7 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: false,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -491,9 +693,12 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -513,6 +718,7 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: true,
         allow_same_keys_non_overlapping_ctx: false,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -528,9 +734,12 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (false, "This current context overlaps with an existing experiment or the keys in the context are overlapping".to_string())
     );
@@ -550,6 +759,7 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
         allow_same_keys_overlapping_ctx: true,
         allow_diff_keys_overlapping_ctx: false,
         allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: None,
     };
 
     let active_experiments = vec![experiment_gen(
@@ -565,12 +775,270 @@ fn test_is_valid_experiment_restrict_same_keys_non_overlapping_ctx_non_overlappi
     assert_eq!(
         helpers::is_valid_experiment(
             &experiment_context,
+            "default",
             &experiment_override_keys,
             &flags,
-            &active_experiments
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &DimensionCardinalities::new()
         )?,
         (true, "".to_string())
     );
 
     Ok(())
 }
+
+#[test]
+fn test_experiment_csv_row_escapes_fields() {
+    let mut experiment = experiment_gen(
+        &vec!["key1".to_string()],
+        &single_dimension_ctx_gen(Dimensions::OS("os1".to_string())),
+        ExperimentStatusType::CONCLUDED,
+        &json!([]),
+    );
+    experiment.name = "checkout, \"v2\"".to_string();
+    experiment.traffic_percentage = 20;
+    experiment.chosen_variant = Some("variant-1".to_string());
+    experiment.ramp_history = json!([{
+        "old_traffic": 0,
+        "new_traffic": 20,
+        "actor": "test",
+        "timestamp": "2024-05-01T00:00:00Z"
+    }]);
+
+    let row = helpers::experiment_csv_row(&experiment);
+    let expected = format!(
+        "123456789,\"checkout, \"\"v2\"\"\",CONCLUDED,{},test,2024-05-01T00:00:00Z,{},variant-1,20\n",
+        experiment.created_at.to_rfc3339(),
+        experiment.last_modified.to_rfc3339(),
+    );
+    assert_eq!(row, expected);
+}
+
+#[test]
+fn test_experiment_status_type_round_trips_through_strings() {
+    for status in [
+        ExperimentStatusType::CREATED,
+        ExperimentStatusType::INPROGRESS,
+        ExperimentStatusType::CONCLUDED,
+    ] {
+        assert_eq!(
+            status.to_string().parse::<ExperimentStatusType>(),
+            Ok(status)
+        );
+    }
+    assert_eq!(
+        "RUNNING".parse::<ExperimentStatusType>(),
+        Err(UnknownStatusError("RUNNING".to_string()))
+    );
+}
+
+#[test]
+fn test_experiment_timeline_orders_lifecycle_events() {
+    let mut experiment = experiment_gen(
+        &vec!["key1".to_string()],
+        &single_dimension_ctx_gen(Dimensions::OS("android".to_string())),
+        ExperimentStatusType::CONCLUDED,
+        &json!([]),
+    );
+    let created_at = Utc::now() - chrono::Duration::hours(3);
+    experiment.created_at = created_at;
+    experiment.last_modified = created_at + chrono::Duration::hours(2);
+    experiment.last_modified_by = "concluder".to_string();
+    experiment.chosen_variant = Some("123456789-control".to_string());
+    // recorded out of order to check the sorting
+    experiment.ramp_history = json!([
+        {
+            "old_traffic": 10,
+            "new_traffic": 20,
+            "actor": "ramper",
+            "timestamp": created_at + chrono::Duration::minutes(90)
+        },
+        {
+            "old_traffic": 0,
+            "new_traffic": 10,
+            "actor": "ramper",
+            "timestamp": created_at + chrono::Duration::minutes(30)
+        }
+    ]);
+
+    let timeline = helpers::experiment_timeline(&experiment)
+        .into_iter()
+        .map(|event| serde_json::to_value(event).unwrap())
+        .map(|event| (event["event"].clone(), event["new_value"].clone()))
+        .collect::<Vec<(Value, Value)>>();
+    assert_eq!(
+        timeline,
+        vec![
+            (json!("created"), json!(0)),
+            (json!("ramped"), json!(10)),
+            (json!("ramped"), json!(20)),
+            (json!("concluded"), json!("123456789-control")),
+        ]
+    );
+}
+
+/************************* Overlap Threshold *****************************************/
+
+#[test]
+fn test_estimate_intersection_uses_dimension_cardinalities() -> Result<(), AppError> {
+    let context_a = single_dimension_ctx_gen(Dimensions::OS("os1".to_string()));
+    let context_b = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("os1".to_string()),
+        Dimensions::CLIENT("testclient1".to_string()),
+    ]);
+    let cardinalities = DimensionCardinalities::from([
+        ("os".to_string(), 2),
+        ("clientId".to_string(), 5),
+    ]);
+
+    assert_eq!(
+        helpers::estimate_intersection(&context_a, &context_b, &cardinalities)?,
+        0.1
+    );
+    // dimensions of unknown cardinality take at least one value more than the
+    // contexts mention
+    assert_eq!(
+        helpers::estimate_intersection(
+            &context_a,
+            &context_b,
+            &DimensionCardinalities::new()
+        )?,
+        0.25
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_intersection_of_differing_values() -> Result<(), AppError> {
+    let context_a = single_dimension_ctx_gen(Dimensions::OS("os1".to_string()));
+    let context_b = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("os2".to_string()),
+        Dimensions::CLIENT("testclient1".to_string()),
+    ]);
+    let cardinalities = DimensionCardinalities::from([("os".to_string(), 2)]);
+
+    assert_eq!(
+        helpers::estimate_intersection(&context_a, &context_b, &cardinalities)?,
+        0.0
+    );
+    assert_eq!(
+        helpers::estimate_intersection(&context_b, &context_a, &cardinalities)?,
+        0.0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_intersection_of_value_lists() -> Result<(), AppError> {
+    let context_a = json!({"in": [{"var": "os"}, ["os1", "os2", "os3"]]});
+    let context_b = json!({"in": [{"var": "os"}, ["os2", "os3", "os4"]]});
+    let cardinalities = DimensionCardinalities::from([("os".to_string(), 8)]);
+
+    assert_eq!(
+        helpers::estimate_intersection(&context_a, &context_b, &cardinalities)?,
+        0.25
+    );
+    // os1 to os4 are mentioned, so os takes at least five values
+    assert_eq!(
+        helpers::estimate_intersection(
+            &context_a,
+            &context_b,
+            &DimensionCardinalities::new()
+        )?,
+        0.4
+    );
+
+    let context_c = json!({"in": [{"var": "os"}, ["os5", "os6"]]});
+    assert_eq!(
+        helpers::estimate_intersection(&context_a, &context_c, &cardinalities)?,
+        0.0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_is_valid_experiment_overlap_below_threshold() -> Result<(), AppError> {
+    let experiment_context = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("os1".to_string()),
+        Dimensions::CLIENT("testclient1".to_string()),
+    ]);
+    let experiment_override_keys = vec!["key1".to_string(), "key2".to_string()];
+    let mut flags = ExperimentationFlags {
+        allow_same_keys_overlapping_ctx: false,
+        allow_diff_keys_overlapping_ctx: true,
+        allow_same_keys_non_overlapping_ctx: true,
+        overlap_threshold: Some(0.2),
+    };
+    let cardinalities = DimensionCardinalities::from([
+        ("os".to_string(), 2),
+        ("clientId".to_string(), 5),
+    ]);
+
+    let active_experiments = vec![experiment_gen(
+        &vec!["key1".to_string(), "key2".to_string()],
+        &single_dimension_ctx_gen(Dimensions::OS("os1".to_string())),
+        ExperimentStatusType::CREATED,
+        &json!(""),
+    )];
+
+    assert_eq!(
+        helpers::is_valid_experiment(
+            &experiment_context,
+            "default",
+            &experiment_override_keys,
+            &flags,
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &cardinalities
+        )?,
+        (true, "".to_string())
+    );
+
+    flags.overlap_threshold = Some(0.05);
+    assert!(
+        !helpers::is_valid_experiment(
+            &experiment_context,
+            "default",
+            &experiment_override_keys,
+            &flags,
+            &active_experiments,
+            &CompositeDimensions::new(),
+            &cardinalities
+        )?
+        .0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_trace_jsonlogic_records_each_operator() {
+    let context = multiple_dimension_ctx_gen(vec![
+        Dimensions::OS("android".to_string()),
+        Dimensions::CLIENT("testClient".to_string()),
+    ]);
+    let trace = helpers::trace_jsonlogic(
+        &context,
+        &json!({ "os": "android", "clientId": "otherClient" }),
+    );
+
+    let steps = trace
+        .iter()
+        .map(|step| (step.path.as_str(), step.result.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        vec![
+            ("/and/0/==/0", Some(json!("android"))),
+            ("/and/0", Some(json!(true))),
+            ("/and/1/==/1", Some(json!("otherClient"))),
+            ("/and/1", Some(json!(false))),
+            ("", Some(json!(false))),
+        ]
+    );
+    assert_eq!(trace.last().unwrap().expression, context);
+}
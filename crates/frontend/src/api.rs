@@ -2,8 +2,8 @@ use leptos::ServerFnError;
 
 use crate::{
     types::{
-        Config, DefaultConfig, Dimension, Experiment, ExperimentsResponse,
-        FunctionResponse, ListFilters,
+        Config, DefaultConfig, Dimension, DimensionsResponse, Experiment,
+        ExperimentsResponse, FunctionResponse, ListFilters,
     },
     utils::use_host_server,
 };
@@ -14,7 +14,7 @@ pub async fn fetch_dimensions(tenant: String) -> Result<Vec<Dimension>, ServerFn
     let host = use_host_server();
 
     let url = format!("{}/dimension", host);
-    let response: Vec<Dimension> = client
+    let response: DimensionsResponse = client
         .get(url)
         .header("x-tenant", &tenant)
         .send()
@@ -24,7 +24,7 @@ pub async fn fetch_dimensions(tenant: String) -> Result<Vec<Dimension>, ServerFn
         .await
         .map_err(|e| ServerFnError::ServerError(e.to_string()))?;
 
-    Ok(response)
+    Ok(response.data)
 }
 
 // #[server(GetDefaultConfig, "/fxn", "GetJson")]
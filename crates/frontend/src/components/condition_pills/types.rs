@@ -1,6 +1,94 @@
+use std::fmt::Display;
+
+use serde_json::{Map, Value};
+
 #[derive(Clone)]
 pub struct Condition {
     pub left_operand: String,
     pub operator: String,
     pub right_operand: String,
 }
+
+/// JSON-logic expression tree, every operator is kept as is so that
+/// conditions can be rendered without losing information.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Var(String),
+    Literal(Value),
+    Operation {
+        operator: String,
+        operands: Vec<Expression>,
+    },
+}
+
+impl Expression {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Expression::Var(name) => {
+                Value::Object(Map::from_iter([("var".to_string(), name.clone().into())]))
+            }
+            Expression::Literal(value) => value.clone(),
+            Expression::Operation { operator, operands } => {
+                Value::Object(Map::from_iter([(
+                    operator.clone(),
+                    Value::Array(operands.iter().map(Expression::to_json).collect()),
+                )]))
+            }
+        }
+    }
+
+    /// Renders literals without quotes, used for the value side of a pill.
+    pub fn to_plain_string(&self) -> String {
+        match self {
+            Expression::Literal(Value::String(s)) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl From<&Value> for Expression {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Object(obj) if obj.len() == 1 => {
+                let (operator, args) = obj.iter().next().unwrap();
+                match (operator.as_str(), args) {
+                    ("var", Value::String(name)) => Expression::Var(name.clone()),
+                    (_, Value::Array(args)) => Expression::Operation {
+                        operator: operator.clone(),
+                        operands: args.iter().map(Expression::from).collect(),
+                    },
+                    // unary operators may skip the array, e.g. {"!": {"var": "x"}}
+                    (_, arg) => Expression::Operation {
+                        operator: operator.clone(),
+                        operands: vec![Expression::from(arg)],
+                    },
+                }
+            }
+            other => Expression::Literal(other.clone()),
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Var(name) => write!(f, "{name}"),
+            Expression::Literal(value) => write!(f, "{value}"),
+            Expression::Operation { operator, operands } => {
+                let rendered: Vec<String> =
+                    operands.iter().map(|operand| operand.to_string()).collect();
+                match (operator.as_str(), rendered.as_slice()) {
+                    ("!" | "!!", [operand]) => write!(f, "{operator}{operand}"),
+                    ("and" | "or", _) => {
+                        write!(f, "({})", rendered.join(&format!(" {operator} ")))
+                    }
+                    ("<" | "<=", [low, mid, high]) => {
+                        write!(f, "{low} {operator} {mid} {operator} {high}")
+                    }
+                    (_, [left, right]) => write!(f, "{left} {operator} {right}"),
+                    _ => write!(f, "{operator}({})", rendered.join(", ")),
+                }
+            }
+        }
+    }
+}
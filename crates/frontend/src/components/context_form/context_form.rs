@@ -1,9 +1,28 @@
 use crate::components::dropdown::dropdown::{Dropdown, DropdownDirection};
 use crate::types::Dimension;
 use leptos::*;
+use serde_json::Value;
 use std::collections::HashSet;
 use web_sys::MouseEvent;
 
+// dimensions with a restricted set of values are rendered as a select
+// instead of a free text input
+fn get_value_options(dimension: Dimension) -> Option<Vec<String>> {
+    match (dimension.value_type.as_deref(), dimension.allowed_values) {
+        (Some("enum"), Some(values)) => Some(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                })
+                .collect(),
+        ),
+        (Some("boolean"), _) => Some(vec!["true".to_string(), "false".to_string()]),
+        _ => None,
+    }
+}
+
 #[component]
 pub fn context_form<NF>(
     handle_change: NF,
@@ -86,6 +105,11 @@ where
                             children=move |(idx, (dimension, operator, value))| {
                                 let dimension_label = dimension.to_string();
                                 let dimension_name = dimension.to_string();
+                                let value_options = dimensions
+                                    .get_value()
+                                    .into_iter()
+                                    .find(|dim| dim.dimension == dimension)
+                                    .and_then(get_value_options);
                                 view! {
                                     <div class="flex gap-x-6">
                                         <div class="form-control">
@@ -141,22 +165,62 @@ where
                                                 <span class="label-text">Value</span>
                                             </label>
                                             <div class="flex gap-x-6 items-center">
-                                                <input
-                                                    disabled=disabled
-                                                    value=value
-                                                    on:input=move |event| {
-                                                        let input_value = event_target_value(&event);
-                                                        set_context
-                                                            .update(|curr_context| {
-                                                                curr_context[idx].2 = input_value;
-                                                            });
+                                                {match value_options {
+                                                    Some(options) => {
+                                                        view! {
+                                                            <select
+                                                                disabled=disabled
+                                                                on:input=move |event| {
+                                                                    let input_value = event_target_value(&event);
+                                                                    set_context
+                                                                        .update(|curr_context| {
+                                                                            curr_context[idx].2 = input_value;
+                                                                        });
+                                                                }
+
+                                                                name="context-dimension-value"
+                                                                class="select select-bordered w-full bg-white text-gray-700 shadow-md"
+                                                            >
+                                                                <option disabled selected=value.is_empty()>
+                                                                    Pick one
+                                                                </option>
+                                                                {options
+                                                                    .into_iter()
+                                                                    .map(|option| {
+                                                                        let is_selected = option == value;
+                                                                        view! {
+                                                                            <option value=option.clone() selected=is_selected>
+                                                                                {option.clone()}
+                                                                            </option>
+                                                                        }
+                                                                    })
+                                                                    .collect_view()}
+                                                            </select>
+                                                        }
+                                                            .into_view()
                                                     }
+                                                    None => {
+                                                        view! {
+                                                            <input
+                                                                disabled=disabled
+                                                                value=value
+                                                                on:input=move |event| {
+                                                                    let input_value = event_target_value(&event);
+                                                                    set_context
+                                                                        .update(|curr_context| {
+                                                                            curr_context[idx].2 = input_value;
+                                                                        });
+                                                                }
 
-                                                    name="context-dimension-value"
-                                                    type="text"
-                                                    placeholder="Type here"
-                                                    class="input input-bordered w-full bg-white text-gray-700 shadow-md"
-                                                />
+                                                                name="context-dimension-value"
+                                                                type="text"
+                                                                placeholder="Type here"
+                                                                class="input input-bordered w-full bg-white text-gray-700 shadow-md"
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                }}
                                                 <button
                                                     class="btn btn-ghost btn-circle btn-sm"
                                                     disabled=disabled
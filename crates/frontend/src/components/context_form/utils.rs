@@ -78,6 +78,7 @@ pub fn construct_request_payload(
     overrides: Map<String, Value>,
     conditions: Vec<(String, String, String)>,
     dimensions: Vec<Dimension>,
+    description: Option<String>,
 ) -> Value {
     // Construct the override section
     let override_section: Map<String, Value> = overrides;
@@ -88,7 +89,8 @@ pub fn construct_request_payload(
     // Construct the entire request payload
     let request_payload = json!({
         "override": override_section,
-        "context": context_section
+        "context": context_section,
+        "description": description
     });
 
     request_payload
@@ -99,10 +101,12 @@ pub async fn create_context(
     overrides: Map<String, Value>,
     conditions: Vec<(String, String, String)>,
     dimensions: Vec<Dimension>,
+    description: Option<String>,
 ) -> Result<serde_json::Value, String> {
     let host = get_host();
     let url = format!("{host}/context");
-    let request_payload = construct_request_payload(overrides, conditions, dimensions);
+    let request_payload =
+        construct_request_payload(overrides, conditions, dimensions, description);
     request(
         url,
         reqwest::Method::PUT,
@@ -23,6 +23,7 @@ pub fn default_config_form<NF>(
     #[prop(default = String::new())] config_pattern: String,
     #[prop(default = String::new())] config_value: String,
     #[prop(default = None)] function_name: Option<Value>,
+    #[prop(default = String::new())] description: String,
     #[prop(default = None)] prefix: Option<String>,
     handle_submit: NF,
 ) -> impl IntoView
@@ -36,6 +37,7 @@ where
     let (config_pattern, set_config_pattern) = create_signal(config_pattern);
     let (config_value, set_config_value) = create_signal(config_value);
     let (function_name, set_function_name) = create_signal(function_name);
+    let (description, set_description) = create_signal(description);
 
     let functions_resource: Resource<String, Vec<crate::types::FunctionResponse>> =
         create_blocking_resource(
@@ -73,6 +75,7 @@ where
         let f_pattern = config_pattern.get();
         let f_value = config_value.get();
         let fun_name = function_name.get();
+        let f_description = description.get();
 
         let f_value = match f_type.as_str() {
             "number" => Value::Number(f_value.parse::<i64>().unwrap().into()),
@@ -129,6 +132,7 @@ where
             schema: f_schema,
             value: f_value,
             function_name: fun_name,
+            description: (!f_description.is_empty()).then_some(f_description),
         };
 
         let handle_submit_clone = handle_submit.clone();
@@ -175,6 +179,19 @@ where
 
             </div>
 
+            <div class="form-control">
+                <label class="label">
+                    <span class="label-text">Description</span>
+                </label>
+                <textarea
+                    placeholder="What this key controls"
+                    class="textarea textarea-bordered w-full max-w-md"
+                    on:change=move |ev| set_description.set(event_target_value(&ev))
+                >
+                    {description.get()}
+                </textarea>
+            </div>
+
             <div class="divider"></div>
 
             <div class="form-control">
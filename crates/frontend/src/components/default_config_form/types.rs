@@ -6,4 +6,5 @@ pub struct DefaultConfigCreateReq {
     pub schema: Value,
     pub value: Value,
     pub function_name: Option<Value>,
+    pub description: Option<String>,
 }
@@ -100,11 +100,24 @@ where
             _ => Value::from_str(&f_pattern).expect("Error parsing JSON"),
         };
 
+        let (value_type, allowed_values) = match f_type.as_str() {
+            "number" | "decimal" => (Some("number".to_string()), None),
+            "boolean" => (Some("boolean".to_string()), None),
+            "enum" => (
+                Some("enum".to_string()),
+                f_schema.get("enum").and_then(Value::as_array).cloned(),
+            ),
+            "pattern" => (Some("string".to_string()), None),
+            _ => (None, None),
+        };
+
         let payload = DimensionCreateReq {
             dimension: f_name,
             priority: f_priority,
             schema: f_schema,
             function_name: fun_name,
+            value_type,
+            allowed_values,
         };
 
         let handle_submit_clone = handle_submit.clone();
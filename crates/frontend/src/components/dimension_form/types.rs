@@ -7,4 +7,6 @@ pub struct DimensionCreateReq {
     pub priority: u16,
     pub schema: Value,
     pub function_name: Option<Value>,
+    pub value_type: Option<String>,
+    pub allowed_values: Option<Vec<Value>>,
 }
@@ -47,6 +47,12 @@ where
                         <h1 class="text-2xl pt-4 font-extrabold">
                             {&exp.name} <span class=class_name>{exp.status.to_string()}</span>
                         </h1>
+                        {exp
+                            .description
+                            .clone()
+                            .map(|description| {
+                                view! { <p class="pt-2 text-gray-600">{description}</p> }
+                            })}
                     }
                 }
             }
@@ -50,6 +50,7 @@ pub fn experiment_form<NF>(
     #[prop(default = false)] edit: bool,
     #[prop(default = String::new())] id: String,
     name: String,
+    #[prop(default = String::new())] description: String,
     context: Vec<(String, String, String)>,
     variants: Vec<Variant>,
     handle_submit: NF,
@@ -64,6 +65,7 @@ where
     let tenant_rs = use_context::<ReadSignal<String>>().unwrap();
 
     let (experiment_name, set_experiment_name) = create_signal(name);
+    let (experiment_description, set_experiment_description) = create_signal(description);
     let (f_context, set_context) = create_signal(context.clone());
     let (f_variants, set_variants) = create_signal(init_variants);
 
@@ -82,6 +84,8 @@ where
         logging::log!("{:?}", f_variants.get());
 
         let f_experiment_name = experiment_name.get();
+        let f_description = experiment_description.get();
+        let f_description = (!f_description.is_empty()).then_some(f_description);
         let f_context = f_context.get();
         let f_variants = f_variants
             .get()
@@ -98,12 +102,14 @@ where
         spawn_local({
             async move {
                 let result = if edit {
-                    update_experiment(experiment_id, f_variants, tenant).await
+                    update_experiment(experiment_id, f_variants, f_description, tenant)
+                        .await
                 } else {
                     create_experiment(
                         f_context,
                         f_variants,
                         f_experiment_name,
+                        f_description,
                         tenant,
                         dimensions.get_value(),
                     )
@@ -141,6 +147,21 @@ where
                 />
             </div>
 
+            <div class="form-control w-full">
+                <label class="label">
+                    <span class="label-text">Description</span>
+                </label>
+                <textarea
+                    on:input=move |ev| set_experiment_description.set(event_target_value(&ev))
+                    name="expDescription"
+                    id="expDescription"
+                    placeholder="ex: checks whether the new checkout flow improves conversion"
+                    class="textarea textarea-bordered w-full max-w-md"
+                >
+                    {experiment_description.get_untracked()}
+                </textarea>
+            </div>
+
             <div class="divider"></div>
 
             <div class="my-4">
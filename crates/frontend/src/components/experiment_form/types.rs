@@ -8,6 +8,7 @@ pub struct ExperimentCreateRequest {
 
     pub context: Value,
     pub variants: Vec<Variant>,
+    pub description: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -19,4 +20,5 @@ pub struct VariantUpdateRequest {
 #[derive(Serialize, Debug)]
 pub struct ExperimentUpdateRequest {
     pub variants: Vec<VariantUpdateRequest>,
+    pub description: Option<String>,
 }
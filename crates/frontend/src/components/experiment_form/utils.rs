@@ -17,6 +17,7 @@ pub async fn create_experiment(
     conditions: Vec<(String, String, String)>,
     variants: Vec<Variant>,
     name: String,
+    description: Option<String>,
     tenant: String,
     dimensions: Vec<Dimension>,
 ) -> Result<Value, String> {
@@ -24,6 +25,7 @@ pub async fn create_experiment(
         name,
         variants,
         context: construct_context(conditions, dimensions),
+        description,
     };
 
     let _ = validate_experiment(&payload)?;
@@ -42,9 +44,11 @@ pub async fn create_experiment(
 pub async fn update_experiment(
     experiment_id: String,
     variants: Vec<Variant>,
+    description: Option<String>,
     tenant: String,
 ) -> Result<Value, String> {
     let payload = ExperimentUpdateRequest {
+        description,
         variants: variants
             .into_iter()
             .map(|variant| VariantUpdateRequest {
@@ -129,6 +129,7 @@ fn modal_component(handle_submit: Rc<dyn Fn()>) -> impl IntoView {
     let (context_condition, set_context_condition) =
         create_signal::<Vec<(String, String, String)>>(vec![]);
     let (overrides, set_overrides) = create_signal::<Map<String, Value>>(Map::new());
+    let (description, set_description) = create_signal(String::new());
 
     let tenant_rs = use_context::<ReadSignal<String>>().unwrap();
 
@@ -159,12 +160,14 @@ fn modal_component(handle_submit: Rc<dyn Fn()>) -> impl IntoView {
                 let overrides = move || overrides.get();
                 let context_conditions = move || context_condition.get();
                 let dimensions = move || dimensions.get();
+                let description = description.get();
                 async move {
                     let result = create_context(
                         current_tenant,
                         overrides(),
                         context_conditions(),
                         dimensions().unwrap().expect("resource not loaded"),
+                        (!description.is_empty()).then_some(description),
                     )
                     .await;
 
@@ -205,6 +208,16 @@ fn modal_component(handle_submit: Rc<dyn Fn()>) -> impl IntoView {
                     <div class="mt-7">
                         <OverrideModalForm handle_change=handle_overrides_change/>
                     </div>
+                    <div class="form-control mt-7">
+                        <label class="label">
+                            <span class="label-text">Description</span>
+                        </label>
+                        <textarea
+                            placeholder="Why these overrides exist"
+                            class="textarea textarea-bordered w-full"
+                            on:change=move |ev| set_description.set(event_target_value(&ev))
+                        ></textarea>
+                    </div>
                     <div class="form-control mt-7">
                         <Button
                             text="Submit".to_string()
@@ -302,6 +315,14 @@ pub fn context_override() -> impl IntoView {
                                                                     <i class="ri-arrow-right-fill ri-xl text-blue-500"></i>
                                                                     <ContextPills context=context.condition.clone()/>
                                                                 </div>
+                                                                {context
+                                                                    .description
+                                                                    .clone()
+                                                                    .map(|description| {
+                                                                        view! {
+                                                                            <p class="text-sm text-gray-600">{description}</p>
+                                                                        }
+                                                                    })}
                                                                 <button class="p-2 rounded hover:bg-gray-200 transition-colors">
                                                                     <i class="ri-edit-line text-blue-500"></i>
                                                                 </button>
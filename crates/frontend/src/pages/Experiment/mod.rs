@@ -129,6 +129,9 @@ pub fn experiment_page() -> impl IntoView {
                                     edit=true
                                     id=experiment.id
                                     name=experiment_ef.name
+                                    description=experiment_ef
+                                        .description
+                                        .unwrap_or_default()
                                     context=extract_conditions(&experiment_ef.context)
                                         .unwrap_or(vec![])
                                     variants=experiment_ef.variants
@@ -18,6 +18,7 @@ pub struct RowData {
     pub pattern: String,
     pub type_: String,
     pub function_name: Option<Value>,
+    pub description: String,
 }
 
 #[component]
@@ -78,6 +79,12 @@ pub fn default_config() -> impl IntoView {
                 _ => Some(json!(function_name.replace("\"", ""))),
             };
 
+            let row_description = row
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+
             let pattern_or_enum = schema_object
                 .keys()
                 .find(|key| {
@@ -138,6 +145,7 @@ pub fn default_config() -> impl IntoView {
                     type_: row_type.clone(),
                     pattern: row_pattern.clone(),
                     function_name: fun_name.clone(),
+                    description: row_description.clone(),
                 };
                 logging::log!("{:?}", row_data);
                 selected_config.set(Some(row_data));
@@ -191,6 +199,7 @@ pub fn default_config() -> impl IntoView {
             Column::default("schema".to_string()),
             Column::default("value".to_string()),
             Column::default("function_name".to_string()),
+            Column::default("description".to_string()),
             Column::default("created_at".to_string()),
             Column::default("created_by".to_string()),
             Column::new("EDIT".to_string(), None, edit_col_formatter),
@@ -224,6 +233,7 @@ pub fn default_config() -> impl IntoView {
                                     config_type=selected_config_data.type_
                                     config_pattern=selected_config_data.pattern
                                     function_name=selected_config_data.function_name
+                                    description=selected_config_data.description
                                     prefix
                                     handle_submit=move || {
                                         default_config_resource.refetch();
@@ -20,6 +20,11 @@ pub fn experiment_table_columns() -> Vec<Column> {
                 let experiment_id = row.get("id").map_or(String::from(""), |value| {
                     value.as_str().unwrap_or("").to_string()
                 });
+                let description = row
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
                 let experiment_id_copy = experiment_id.clone();
                 let handle_copy = move |event: MouseEvent| {
                     event.prevent_default();
@@ -46,6 +51,12 @@ pub fn experiment_table_columns() -> Vec<Column> {
                             <A href=experiment_id.to_string() class="btn-link">
                                 {experiment_name}
                             </A>
+                            <Show when={
+                                let description = description.clone();
+                                move || !description.is_empty()
+                            }>
+                                <div class="text-sm text-gray-600">{description.clone()}</div>
+                            </Show>
                             <div class="text-gray-500">
                                 <span class="text-xs">
                                     {experiment_id}
@@ -96,6 +96,8 @@ pub struct ExperimentResponse {
     pub context: Value,
     pub variants: Value,
     pub chosen_variant: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -148,6 +150,8 @@ pub struct Experiment {
     pub(crate) created_at: DateTime<Utc>,
     pub(crate) last_modified: DateTime<Utc>,
     pub(crate) chosen_variant: Option<String>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
 }
 
 /*************************** Context-Override types ********************************/
@@ -160,6 +164,8 @@ pub struct Dimension {
     pub created_by: String,
     pub schema: Value,
     pub function_name: Option<String>,
+    pub value_type: Option<String>,
+    pub allowed_values: Option<Vec<Value>>,
 }
 
 impl DropdownOption for Dimension {
@@ -171,6 +177,13 @@ impl DropdownOption for Dimension {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DimensionsResponse {
+    pub data: Vec<Dimension>,
+    pub total_count: i64,
+    pub page: i64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DefaultConfig {
     pub key: String,
@@ -179,6 +192,9 @@ pub struct DefaultConfig {
     pub created_by: String,
     pub schema: Value,
     pub function_name: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 impl DropdownOption for DefaultConfig {
@@ -195,6 +211,8 @@ pub struct Context {
     pub id: String,
     pub condition: Value,
     pub override_with_keys: [String; 1],
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -314,10 +314,14 @@ pub fn get_config_type(
             .schema
             .get("type")
             .map(|t| types_mapping(t.as_str())),
-        ConfigType::Dimension(dimension) => dimension
-            .schema
-            .get("type")
-            .map(|t| types_mapping(t.as_str())),
+        ConfigType::Dimension(dimension) => match dimension.value_type.as_deref() {
+            Some("enum") => Some(ConfigValueType::String),
+            Some(value_type) => Some(types_mapping(Some(value_type))),
+            None => dimension
+                .schema
+                .get("type")
+                .map(|t| types_mapping(t.as_str())),
+        },
     })
 }
 
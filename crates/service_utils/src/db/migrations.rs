@@ -0,0 +1,159 @@
+use std::fmt;
+
+use diesel::{
+    connection::BoxableConnection,
+    migration::{
+        self, Migration, MigrationMetadata, MigrationName, MigrationSource,
+        MigrationVersion,
+    },
+    pg::Pg,
+    Connection, PgConnection,
+};
+use diesel_migrations::MigrationHarness;
+use include_dir::Dir;
+
+const DIESEL_SETUP_MIGRATION: &str = "00000000000000_diesel_initial_setup";
+
+/// Migrations compiled into the binary, rewritten to target a single schema.
+///
+/// The migration files are written against `public`, the same way
+/// `scripts/create-tenant.sh` consumes them, so the `public.` qualifiers and
+/// the creation of the `public` schema are retargeted to the schema before the
+/// SQL is executed. Other occurrences of the word, e.g. in comments or string
+/// values, are left untouched.
+pub struct SchemaMigrations {
+    dir: &'static Dir<'static>,
+    schema: String,
+}
+
+impl SchemaMigrations {
+    pub fn new(dir: &'static Dir<'static>, schema: String) -> Self {
+        SchemaMigrations { dir, schema }
+    }
+}
+
+struct SchemaMigration {
+    name: String,
+    up_sql: String,
+    down_sql: Option<String>,
+}
+
+impl fmt::Display for SchemaMigration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl MigrationName for SchemaMigration {
+    fn version(&self) -> MigrationVersion<'_> {
+        // diesel derives the version from the directory prefix,
+        // e.g. `2024-02-19-125126_functions` => `20240219125126`
+        let version = self.name.split('_').next().unwrap_or_default();
+        MigrationVersion::from(version.replace('-', ""))
+    }
+}
+
+impl MigrationMetadata for SchemaMigration {}
+
+impl Migration<Pg> for SchemaMigration {
+    fn run(&self, conn: &mut dyn BoxableConnection<Pg>) -> migration::Result<()> {
+        conn.batch_execute(&self.up_sql)?;
+        Ok(())
+    }
+
+    fn revert(&self, conn: &mut dyn BoxableConnection<Pg>) -> migration::Result<()> {
+        match &self.down_sql {
+            Some(sql) => {
+                conn.batch_execute(sql)?;
+                Ok(())
+            }
+            None => Err(format!("{} has no down.sql", self.name).into()),
+        }
+    }
+
+    fn metadata(&self) -> &dyn MigrationMetadata {
+        self
+    }
+
+    fn name(&self) -> &dyn MigrationName {
+        self
+    }
+}
+
+impl MigrationSource<Pg> for SchemaMigrations {
+    fn migrations(&self) -> migration::Result<Vec<Box<dyn Migration<Pg>>>> {
+        let read_sql = |path: std::path::PathBuf| {
+            self.dir
+                .get_file(path)
+                .and_then(|file| file.contents_utf8())
+                .map(|sql| retarget_schema(sql, &self.schema))
+        };
+
+        let mut migrations = Vec::new();
+        for migration_dir in self.dir.dirs() {
+            let name = migration_dir
+                .path()
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or("invalid migration directory name")?
+                .to_string();
+            if name == DIESEL_SETUP_MIGRATION {
+                continue;
+            }
+            let up_sql = read_sql(migration_dir.path().join("up.sql"))
+                .ok_or_else(|| format!("{} has no up.sql", name))?;
+            let down_sql = read_sql(migration_dir.path().join("down.sql"));
+            migrations.push(Box::new(SchemaMigration {
+                name,
+                up_sql,
+                down_sql,
+            }) as Box<dyn Migration<Pg>>);
+        }
+        migrations.sort_by_key(|m| m.name().version().to_string());
+        Ok(migrations)
+    }
+}
+
+fn retarget_schema(sql: &str, schema: &str) -> String {
+    sql.replace(
+        "CREATE SCHEMA IF NOT EXISTS public;",
+        &format!("CREATE SCHEMA IF NOT EXISTS {schema};"),
+    )
+    .replace("public.", &format!("{schema}."))
+}
+
+/// Runs all pending `migrations` in a single transaction and returns the names
+/// of the applied migrations. Applied versions are tracked in the
+/// `__diesel_schema_migrations` table of the connection's schema.
+pub fn run_pending_migrations(
+    conn: &mut PgConnection,
+    migrations: SchemaMigrations,
+) -> migration::Result<Vec<String>> {
+    conn.transaction(|conn| {
+        let pending = conn.pending_migrations(migrations)?;
+        let mut applied = Vec::with_capacity(pending.len());
+        for pending_migration in pending.iter() {
+            conn.run_migration(pending_migration.as_ref())?;
+            applied.push(pending_migration.name().to_string());
+        }
+        Ok(applied)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retarget_schema_only_rewrites_schema_references() {
+        let sql = "-- Name: contexts; Type: TABLE; Schema: public; Owner: -\n\
+            CREATE SCHEMA IF NOT EXISTS public;\n\
+            CREATE TABLE public.contexts (visibility text DEFAULT 'public');";
+        assert_eq!(
+            retarget_schema(sql, "dev_cac"),
+            "-- Name: contexts; Type: TABLE; Schema: public; Owner: -\n\
+            CREATE SCHEMA IF NOT EXISTS dev_cac;\n\
+            CREATE TABLE dev_cac.contexts (visibility text DEFAULT 'public');"
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod migrations;
 pub mod pgschema_manager;
 pub mod utils;
@@ -1,12 +1,25 @@
 extern crate derive_more;
 use derive_more::{Deref, DerefMut, Display};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use anyhow::anyhow;
+use chrono::{DateTime, Utc};
 use diesel::{
-    r2d2::{ConnectionManager, Pool, PooledConnection},
-    PgConnection,
+    r2d2::{
+        event::CheckinEvent, ConnectionManager, Error as PoolError, HandleError,
+        HandleEvent, Pool, PooledConnection,
+    },
+    sql_types::Text,
+    PgConnection, QueryResult, RunQueryDsl,
 };
+use serde::Serialize;
 
 pub type PgSchemaConnectionPool = Pool<ConnectionManager<PgConnection>>;
 pub type PgSchemaConnection = PooledConnection<ConnectionManager<PgConnection>>;
@@ -45,21 +58,95 @@ impl ConnectionConfig {
     }
 }
 
+/// Sets the `app.actor` setting of the session of `conn`, see
+/// `PgSchemaManager::get_conn_as`.
+pub fn set_actor(conn: &mut PgConnection, actor: &str) -> QueryResult<()> {
+    diesel::sql_query("SELECT set_config('app.actor', $1, false)")
+        .bind::<Text, _>(actor)
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Connections held longer than this are counted as slow queries.
+const SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// In-process usage tracker for a single pool. A connection checkout is
+/// treated as one query, timed from checkout to checkin.
+#[derive(Debug, Default)]
+pub struct QueryTracker {
+    pending_requests: AtomicU32,
+    slow_queries: AtomicU64,
+    last_query_at: Mutex<Option<DateTime<Utc>>>,
+    reconnections: AtomicU64,
+}
+
+#[derive(Debug)]
+struct QueryTrackerEventHandler(Arc<QueryTracker>);
+
+impl HandleEvent for QueryTrackerEventHandler {
+    fn handle_checkin(&self, event: CheckinEvent) {
+        if event.duration() >= SLOW_QUERY_THRESHOLD {
+            self.0.slow_queries.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Ok(mut last_query_at) = self.0.last_query_at.lock() {
+            *last_query_at = Some(Utc::now());
+        }
+    }
+}
+
+/// Counts idle connections that failed r2d2's `SELECT 1` check on checkout,
+/// which the pool drops and replaces. Failures to open a connection are only
+/// logged.
+#[derive(Debug)]
+struct QueryTrackerErrorHandler(Arc<QueryTracker>);
+
+impl HandleError<PoolError> for QueryTrackerErrorHandler {
+    fn handle_error(&self, error: PoolError) {
+        if let PoolError::QueryError(_) = error {
+            self.0.reconnections.fetch_add(1, Ordering::Relaxed);
+        }
+        log::error!("db connection error: {error}");
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PoolStats {
+    pub pool_size: u32,
+    pub idle_connections: u32,
+    pub active_connections: u32,
+    pub pending_requests: u32,
+    pub slow_queries: u64,
+    pub last_query_at: Option<DateTime<Utc>>,
+    pub reconnections: u64,
+}
+
 #[derive(Deref, DerefMut, Clone)]
-pub struct PgSchemaManager(HashMap<String, PgSchemaConnectionPool>);
+pub struct PgSchemaManager {
+    #[deref]
+    #[deref_mut]
+    pools: HashMap<String, PgSchemaConnectionPool>,
+    trackers: HashMap<String, Arc<QueryTracker>>,
+}
 
 impl From<Vec<ConnectionConfig>> for PgSchemaManager {
     fn from(value: Vec<ConnectionConfig>) -> Self {
-        let mut schema_manager: PgSchemaManager = PgSchemaManager(HashMap::new());
+        let mut schema_manager: PgSchemaManager = PgSchemaManager {
+            pools: HashMap::new(),
+            trackers: HashMap::new(),
+        };
         for config in value.into_iter() {
             let manager = ConnectionManager::<PgConnection>::new(config.conn_url());
+            let tracker = Arc::new(QueryTracker::default());
             schema_manager.insert(
                 config.name.clone(),
                 Pool::builder()
                     .max_size(config.count)
+                    .event_handler(Box::new(QueryTrackerEventHandler(tracker.clone())))
+                    .error_handler(Box::new(QueryTrackerErrorHandler(tracker.clone())))
                     .build(manager)
                     .expect(format!("Invalid config provided, {}", config.name).as_str()),
             );
+            schema_manager.trackers.insert(config.name.clone(), tracker);
         }
         schema_manager
     }
@@ -67,10 +154,67 @@ impl From<Vec<ConnectionConfig>> for PgSchemaManager {
 
 impl PgSchemaManager {
     pub fn get_conn(&self, name: String) -> anyhow::Result<PgSchemaConnection> {
-        let conn = self
+        self.get_conn_as(name, "")
+    }
+
+    /// Checks out a connection with the `app.actor` setting of its session set
+    /// to `actor`, which the CAC changelog records as the author of a change.
+    /// Pooled connections are shared across requests, so it is set, or
+    /// cleared with an empty `actor`, on every checkout.
+    pub fn get_conn_as(
+        &self,
+        name: String,
+        actor: &str,
+    ) -> anyhow::Result<PgSchemaConnection> {
+        let pool = self
             .get(&name) // gets the pool for the given namespace
-            .ok_or_else(|| anyhow!("Invalid connection name provided: {}", name))?
-            .get()?; // fetches the connection from the pool
+            .ok_or_else(|| anyhow!("Invalid connection name provided: {}", name))?;
+        let tracker = self.trackers.get(&name);
+        if let Some(tracker) = tracker {
+            tracker.pending_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        let conn = pool.get(); // fetches the connection from the pool
+        if let Some(tracker) = tracker {
+            tracker.pending_requests.fetch_sub(1, Ordering::Relaxed);
+        }
+        let mut conn = conn?;
+        set_actor(&mut conn, actor)?;
         Ok(conn)
     }
+
+    pub fn get_stats(&self) -> HashMap<String, PoolStats> {
+        self.pools
+            .iter()
+            .map(|(name, pool)| {
+                let state = pool.state();
+                let tracker = self.trackers.get(name);
+                let stats = PoolStats {
+                    pool_size: pool.max_size(),
+                    idle_connections: state.idle_connections,
+                    active_connections: state.connections - state.idle_connections,
+                    pending_requests: tracker
+                        .map(|t| t.pending_requests.load(Ordering::Relaxed))
+                        .unwrap_or_default(),
+                    slow_queries: tracker
+                        .map(|t| t.slow_queries.load(Ordering::Relaxed))
+                        .unwrap_or_default(),
+                    last_query_at: tracker
+                        .and_then(|t| t.last_query_at.lock().ok().and_then(|v| *v)),
+                    reconnections: tracker
+                        .map(|t| t.reconnections.load(Ordering::Relaxed))
+                        .unwrap_or_default(),
+                };
+                (name.clone(), stats)
+            })
+            .collect()
+    }
+
+    /// Checks out one idle connection of each pool, without waiting, so that
+    /// r2d2 validates it with `SELECT 1`. A connection failing the check is
+    /// replaced by the pool and counted as a reconnection.
+    pub fn ping_idle_connections(&self) {
+        for pool in self.pools.values() {
+            drop(pool.try_get());
+        }
+    }
 }
@@ -1,15 +1,23 @@
-use actix_web::{error::ErrorInternalServerError, Error};
+use actix_web::{
+    error::{
+        ErrorInternalServerError, InternalError, JsonPayloadError, QueryPayloadError,
+    },
+    http::StatusCode,
+    Error, HttpRequest, HttpResponse,
+};
 use jsonschema::{error::ValidationErrorKind, ValidationError};
 use log::info;
 use serde::de::{self, IntoDeserializer};
 use std::{
+    collections::HashMap,
     env::VarError,
     fmt::{self, Display},
     str::FromStr,
 };
 
-use super::result;
+use super::{result, service::types::AppState};
 use serde_json::{Map, Value};
+use superposition_types::{SuperpositionUser, User};
 
 //WARN Do NOT use this fxn inside api requests, instead add the required
 //env to AppState and get value from there. As this panics, it should
@@ -109,6 +117,23 @@ where
     deserializer.deserialize_any(StringVecVisitor(std::marker::PhantomData::<I>))
 }
 
+/// Like `deserialize_stringified_list`, but parses every item with `FromStr`
+/// so that the error names the offending item.
+pub fn deserialize_parsed_list<'de, D, I>(
+    deserializer: D,
+) -> std::result::Result<Vec<I>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    I: FromStr,
+    I::Err: Display,
+{
+    let value = <String as de::Deserialize>::deserialize(deserializer)?;
+    value
+        .split(',')
+        .map(|item| item.parse::<I>().map_err(de::Error::custom))
+        .collect()
+}
+
 pub fn get_pod_info() -> (String, String) {
     let hostname: String = get_from_env_unsafe("HOSTNAME").expect("HOSTNAME is not set");
     let tokens = hostname
@@ -165,6 +190,33 @@ pub fn extract_dimensions(context_json: &Value) -> result::Result<Map<String, Va
     Ok(Map::from_iter(dimension_tuples))
 }
 
+/// Composite dimension names mapped to the dimensions they group together.
+pub type CompositeDimensions = HashMap<String, Vec<String>>;
+
+/// Same as `extract_dimensions`, except that the components of a composite
+/// dimension are folded into one entry, keyed by the composite's name, holding
+/// the components present in the context.
+pub fn extract_dimensions_with_composites(
+    context_json: &Value,
+    composite_dimensions: &CompositeDimensions,
+) -> result::Result<Map<String, Value>> {
+    let mut dimensions = extract_dimensions(context_json)?;
+    for (name, components) in composite_dimensions {
+        let grouped = components
+            .iter()
+            .filter_map(|component| {
+                dimensions
+                    .remove(component)
+                    .map(|value| (component.clone(), value))
+            })
+            .collect::<Map<String, Value>>();
+        if !grouped.is_empty() {
+            dimensions.insert(name.clone(), Value::Object(grouped));
+        }
+    }
+    Ok(dimensions)
+}
+
 pub fn get_variable_name_and_value(
     operands: &Vec<Value>,
 ) -> result::Result<(&str, &Value)> {
@@ -326,3 +378,76 @@ pub fn validation_err_to_str(errors: Vec<ValidationError>) -> Vec<String> {
         }
     }).collect()
 }
+
+/// Error handler for `web::JsonConfig`, rejecting bodies that are not sent as
+/// `application/json` with a 415 and bodies over the configured limit with a
+/// 413, instead of a generic deserialization error.
+pub fn json_error_handler(err: JsonPayloadError, _: &HttpRequest) -> Error {
+    match err {
+        JsonPayloadError::ContentType => {
+            let response =
+                HttpResponse::UnsupportedMediaType().json(result::ErrorResponse {
+                    message: "Content-Type must be application/json".to_string(),
+                });
+            InternalError::from_response(err, response).into()
+        }
+        JsonPayloadError::Overflow { limit }
+        | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+            let response = HttpResponse::PayloadTooLarge().json(result::ErrorResponse {
+                message: format!("Request body exceeds the limit of {limit} bytes"),
+            });
+            InternalError::from_response(err, response).into()
+        }
+        err => err.into(),
+    }
+}
+
+/// Error handler for `web::QueryConfig`, answering invalid query parameters
+/// with a 400 carrying the deserialization error.
+pub fn query_error_handler(err: QueryPayloadError, _: &HttpRequest) -> Error {
+    let response = HttpResponse::BadRequest().json(result::ErrorResponse {
+        message: err.to_string(),
+    });
+    InternalError::from_response(err, response).into()
+}
+
+/// Rejects users missing from `ADMIN_USERS`, for the admin only APIs of both
+/// CAC and experimentation.
+pub fn validate_admin(state: &AppState, user: &User) -> result::Result<()> {
+    if state.admin_users.contains(&user.get_email()) {
+        return Ok(());
+    }
+    log::error!(
+        "{} is not allowed to access admin APIs",
+        user.get_recorded_email()
+    );
+    Err(result::AppError::ResponseError(result::ResponseError {
+        status_code: StatusCode::FORBIDDEN,
+        message: "admin access is required for this operation".to_string(),
+    }))
+}
+
+/// Escapes `\`, `%` and `_` in `value` so it matches literally inside a `LIKE`
+/// or `ILIKE` pattern, which use `\` as their default escape character.
+pub fn escape_like_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_like_pattern() {
+        assert_eq!(escape_like_pattern("checkout flow"), "checkout flow");
+        assert_eq!(escape_like_pattern("50%_off"), r"50\%\_off");
+        assert_eq!(escape_like_pattern(r"a\b"), r"a\\b");
+    }
+}
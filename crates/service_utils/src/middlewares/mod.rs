@@ -1,2 +1,3 @@
 pub mod app_scope;
+pub mod request_id;
 pub mod tenant;
@@ -0,0 +1,88 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use derive_more::{Deref, DerefMut};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use std::rc::Rc;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+#[derive(Deref, DerefMut, Clone, Debug)]
+pub struct RequestId(pub String);
+
+pub struct RequestIdMiddlewareFactory;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddlewareFactory
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+
+        Box::pin(async move {
+            let request_id = req
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            req.extensions_mut().insert(RequestId(request_id.clone()));
+
+            // errors from inner middlewares are turned into responses here so
+            // that they carry the request id as well
+            let http_req = req.request().clone();
+            let mut res = match srv.call(req).await {
+                Ok(res) => res.map_into_left_body(),
+                Err(err) => {
+                    log::error!("request {request_id} failed with error: {err}");
+                    ServiceResponse::from_err(err, http_req).map_into_right_body()
+                }
+            };
+
+            if let Ok(header_value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), header_value);
+            }
+            Ok(res)
+        })
+    }
+}
@@ -5,11 +5,12 @@ use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
     error,
     http::header::{HeaderMap, HeaderValue},
-    web::Data,
+    web::{Data, Query},
     Error, HttpMessage,
 };
 use futures_util::future::LocalBoxFuture;
 use log::debug;
+use serde::Deserialize;
 use std::rc::Rc;
 
 pub struct TenantMiddlewareFactory;
@@ -62,12 +63,16 @@ fn extract_tenant_from_url<'a>(
         .flatten()
 }
 
-fn extract_tenant_from_query_params(query_str: &str) -> Option<&str> {
-    query_str
-        .split("&")
-        .find(|segment| segment.contains("tenant="))
-        .map(|tenant_query_param| tenant_query_param.split("=").nth(1))
-        .flatten()
+#[derive(Deserialize)]
+struct TenantQuery {
+    tenant: Option<String>,
+}
+
+/// For callers that cannot set headers, e.g. `<img src="...?tenant=foo">`.
+fn extract_tenant_from_query_params(query_str: &str) -> Option<String> {
+    Query::<TenantQuery>::from_query(query_str)
+        .ok()
+        .and_then(|query| query.into_inner().tenant)
 }
 
 impl<S, B> Service<ServiceRequest> for TenantMiddleware<S>
@@ -118,19 +123,21 @@ where
                     extract_tenant_from_query_params(req.query_string())
                 );
 
+                // the header takes precedence over the path and the query string
                 let tenant = extract_tenant_from_header(req.headers())
                     .or_else(|| extract_tenant_from_url(req.path(), req.match_pattern()))
+                    .map(String::from)
                     .or_else(|| extract_tenant_from_query_params(req.query_string()));
 
                 let validated_tenant: Tenant = match tenant {
-                    Some(val) if app_state.tenants.contains(val) => {
-                        Tenant(String::from(val))
-                    }
+                    Some(val) if app_state.tenants.contains(&val) => Tenant(val),
                     Some(_) => {
                         return Err(error::ErrorBadRequest("invalid x-tenant value"));
                     }
                     None => {
-                        return Err(error::ErrorBadRequest("x-tenant not set"));
+                        return Err(error::ErrorBadRequest(
+                            "x-tenant header or tenant query parameter not set",
+                        ));
                     }
                 };
 
@@ -143,3 +150,22 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tenant_from_query_params() {
+        assert_eq!(
+            extract_tenant_from_query_params("page=1&tenant=mjos&count=10"),
+            Some("mjos".to_string())
+        );
+        assert_eq!(
+            extract_tenant_from_query_params("tenant=dev%2Dtenant"),
+            Some("dev-tenant".to_string())
+        );
+        assert_eq!(extract_tenant_from_query_params("subtenant=mjos"), None);
+        assert_eq!(extract_tenant_from_query_params(""), None);
+    }
+}
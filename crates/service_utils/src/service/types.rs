@@ -1,6 +1,8 @@
 use crate::db::pgschema_manager::{PgSchemaConnection, PgSchemaManager};
+use crate::result::{self, AppError};
 use derive_more::{Deref, DerefMut};
 use jsonschema::JSONSchema;
+use serde::Serialize;
 use serde_json::json;
 
 use std::{
@@ -13,11 +15,16 @@ use actix_web::{error, web::Data, Error, FromRequest, HttpMessage};
 
 use snowflake::SnowflakeIdGenerator;
 use std::sync::Mutex;
+use superposition_types::User;
 
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct ExperimentationFlags {
     pub allow_same_keys_overlapping_ctx: bool,
     pub allow_diff_keys_overlapping_ctx: bool,
     pub allow_same_keys_non_overlapping_ctx: bool,
+    /// Overlapping contexts only count as overlapping when the estimated share
+    /// of users matched by both exceeds this, see `estimate_intersection`.
+    pub overlap_threshold: Option<f32>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -41,6 +48,7 @@ pub struct AppState {
     pub enable_tenant_and_scope: bool,
     pub tenant_middleware_exclusion_list: HashSet<String>,
     pub service_prefix: String,
+    pub admin_users: HashSet<String>,
 }
 
 impl FromStr for AppEnv {
@@ -82,6 +90,14 @@ impl FromRequest for AppScope {
 #[derive(Deref, DerefMut, Clone, Debug)]
 pub struct AppExecutionNamespace(pub String);
 impl AppExecutionNamespace {
+    pub fn for_scope(app_state: &AppState, tenant: &Tenant, scope: AppScope) -> Self {
+        if app_state.enable_tenant_and_scope {
+            AppExecutionNamespace(format!("{}_{}", tenant.as_str(), scope))
+        } else {
+            AppExecutionNamespace("cac_v1".to_string())
+        }
+    }
+
     pub fn from_request_sync(req: &actix_web::HttpRequest) -> Result<Self, Error> {
         let app_state = match req.app_data::<Data<AppState>>() {
             Some(val) => val,
@@ -197,7 +213,12 @@ impl FromRequest for DbConnection {
             }
         };
 
-        let result = match app_state.db_pool.get_conn(namespace) {
+        let actor = req
+            .extensions()
+            .get::<User>()
+            .map(User::get_recorded_email)
+            .unwrap_or_default();
+        let result = match app_state.db_pool.get_conn_as(namespace, &actor) {
             Ok(conn) => Ok(DbConnection(conn)),
             Err(e) => {
                 log::info!("Unable to get db connection from pool, error: {e}");
@@ -208,3 +229,22 @@ impl FromRequest for DbConnection {
         ready(result)
     }
 }
+
+impl DbConnection {
+    /// Fetches a connection for `scope` of `tenant`, for handlers that need to
+    /// read across CAC and experimentation schemas.
+    pub fn for_scope(
+        app_state: &AppState,
+        tenant: &Tenant,
+        scope: AppScope,
+    ) -> result::Result<Self> {
+        app_state
+            .db_pool
+            .get_conn(AppExecutionNamespace::for_scope(app_state, tenant, scope).0)
+            .map(DbConnection)
+            .map_err(|e| {
+                log::error!("Unable to get db connection from pool, error: {e}");
+                AppError::UnexpectedError(e)
+            })
+    }
+}
@@ -0,0 +1,137 @@
+//! Read-only GraphQL view over a tenant's experiments, default configs and
+//! contexts, so that clients can fetch them in one round-trip. Built only
+//! with the `graphql` feature, the REST API is left untouched.
+
+use actix_web::web::Data;
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Json, Object, Result, Schema,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use context_aware_config::db::{
+    models::{Context as CacContext, DefaultConfig},
+    schema::{contexts, default_configs},
+};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use experimentation_platform::{
+    api::experiments::types::ExperimentResponse,
+    db::{
+        models::{Experiment, ExperimentStatusType},
+        schema::experiments,
+    },
+};
+use service_utils::service::types::{AppScope, AppState, DbConnection, Tenant};
+
+pub type SuperpositionSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+struct RequestScope {
+    state: Data<AppState>,
+    tenant: Tenant,
+}
+
+impl RequestScope {
+    fn connection(&self, scope: AppScope) -> Result<DbConnection> {
+        Ok(DbConnection::for_scope(&self.state, &self.tenant, scope)?)
+    }
+}
+
+fn pagination(page: Option<i64>, count: Option<i64>) -> (i64, i64) {
+    let limit = count.unwrap_or(10).clamp(1, 100);
+    let offset = (page.unwrap_or(1).max(1) - 1) * limit;
+    (limit, offset)
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn experiment(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<Option<Json<ExperimentResponse>>> {
+        let id: i64 = id.parse()?;
+        let DbConnection(mut conn) = ctx
+            .data::<RequestScope>()?
+            .connection(AppScope::EXPERIMENTATION)?;
+        let experiment = experiments::table
+            .find(id)
+            .get_result::<Experiment>(&mut conn)
+            .optional()?;
+        Ok(experiment.map(|experiment| Json(ExperimentResponse::from(experiment))))
+    }
+
+    async fn experiments(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<Vec<String>>,
+        page: Option<i64>,
+        count: Option<i64>,
+    ) -> Result<Vec<Json<ExperimentResponse>>> {
+        let DbConnection(mut conn) = ctx
+            .data::<RequestScope>()?
+            .connection(AppScope::EXPERIMENTATION)?;
+        let mut query = experiments::table.into_boxed();
+        if let Some(status) = status {
+            let status = status
+                .into_iter()
+                .map(|s| serde_json::from_value(serde_json::Value::String(s)))
+                .collect::<serde_json::Result<Vec<ExperimentStatusType>>>()?;
+            query = query.filter(experiments::status.eq_any(status));
+        }
+        let (limit, offset) = pagination(page, count);
+        let experiments = query
+            .order(experiments::last_modified.desc())
+            .limit(limit)
+            .offset(offset)
+            .load::<Experiment>(&mut conn)?;
+        Ok(experiments
+            .into_iter()
+            .map(|experiment| Json(ExperimentResponse::from(experiment)))
+            .collect())
+    }
+
+    async fn default_config(
+        &self,
+        ctx: &Context<'_>,
+        key: String,
+    ) -> Result<Option<Json<DefaultConfig>>> {
+        let DbConnection(mut conn) =
+            ctx.data::<RequestScope>()?.connection(AppScope::CAC)?;
+        let default_config = default_configs::table
+            .find(key)
+            .get_result::<DefaultConfig>(&mut conn)
+            .optional()?;
+        Ok(default_config.map(Json))
+    }
+
+    async fn contexts(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<i64>,
+        count: Option<i64>,
+    ) -> Result<Vec<Json<CacContext>>> {
+        let DbConnection(mut conn) =
+            ctx.data::<RequestScope>()?.connection(AppScope::CAC)?;
+        let (limit, offset) = pagination(page, count);
+        let contexts = contexts::table
+            .order((contexts::priority.asc(), contexts::created_at.asc()))
+            .limit(limit)
+            .offset(offset)
+            .load::<CacContext>(&mut conn)?;
+        Ok(contexts.into_iter().map(Json).collect())
+    }
+}
+
+pub fn schema() -> SuperpositionSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish()
+}
+
+pub async fn handler(
+    schema: Data<SuperpositionSchema>,
+    state: Data<AppState>,
+    tenant: Tenant,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let request = request.into_inner().data(RequestScope { state, tenant });
+    schema.execute(request).await.into()
+}
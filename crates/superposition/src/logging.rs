@@ -0,0 +1,46 @@
+use actix_web::{dev::ServiceRequest, HttpMessage};
+use service_utils::{middlewares::request_id::RequestId, service::types::Tenant};
+use tracing::Span;
+use tracing_subscriber::EnvFilter;
+
+/// Installs the global subscriber, respecting `RUST_LOG`. Logs are emitted as
+/// JSON when built with the `json-log` feature, human readable otherwise.
+/// `log` records from dependencies are forwarded into the subscriber.
+pub fn init() {
+    let builder =
+        tracing_subscriber::fmt().with_env_filter(EnvFilter::from_default_env());
+
+    #[cfg(feature = "json-log")]
+    builder.json().with_current_span(true).init();
+
+    #[cfg(not(feature = "json-log"))]
+    builder.init();
+}
+
+fn experiment_id_from_path(path: &str) -> Option<&str> {
+    let mut segments = path.split('/');
+    segments.find(|segment| *segment == "experiments")?;
+    segments
+        .next()
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Span carrying the request level fields attached to every log line.
+pub fn request_span(req: &ServiceRequest) -> Span {
+    let tenant = req
+        .extensions()
+        .get::<Tenant>()
+        .map(|tenant| tenant.as_str().to_string());
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|request_id| request_id.as_str().to_string());
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        path = req.path(),
+        tenant = tenant.as_deref().unwrap_or_default(),
+        experiment_id = experiment_id_from_path(req.path()).unwrap_or_default(),
+        request_id = request_id.as_deref().unwrap_or_default(),
+    )
+}
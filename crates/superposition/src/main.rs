@@ -1,14 +1,26 @@
+#[cfg(feature = "graphql")]
+mod graphql;
+mod logging;
+
+use actix_cors::Cors;
 use actix_web::dev::Service;
+use actix_web::middleware::{Compress, DefaultHeaders};
 use actix_web::HttpMessage;
-use actix_web::{web, web::get, web::scope, web::Data, App, HttpResponse, HttpServer};
+use actix_web::{
+    web, web::get, web::scope, web::Data, web::JsonConfig, web::QueryConfig, App,
+    HttpResponse, HttpServer,
+};
 use context_aware_config::api::*;
+use context_aware_config::config_cache;
 use context_aware_config::helpers::{
     get_default_config_validation_schema, get_meta_schema,
 };
+use context_aware_config::middlewares::tenant_activity;
 use dotenv;
 use experimentation_platform::api::*;
 use std::{collections::HashSet, io::Result};
 use superposition_types::User;
+use tracing::Instrument;
 
 use snowflake::SnowflakeIdGenerator;
 use std::{sync::Mutex, time::Duration};
@@ -21,9 +33,14 @@ use leptos_actix::{generate_route_list, LeptosRoutes};
 use service_utils::{
     db::pgschema_manager::PgSchemaManager,
     db::utils::init_pool_manager,
-    helpers::{get_from_env_or_default, get_from_env_unsafe},
+    helpers::{
+        get_from_env_or_default, get_from_env_unsafe, json_error_handler,
+        query_error_handler,
+    },
     middlewares::{
-        app_scope::AppExecutionScopeMiddlewareFactory, tenant::TenantMiddlewareFactory,
+        app_scope::AppExecutionScopeMiddlewareFactory,
+        request_id::{RequestIdMiddlewareFactory, REQUEST_ID_HEADER},
+        tenant::TenantMiddlewareFactory,
     },
     service::types::{AppEnv, AppScope, AppState, ExperimentationFlags},
 };
@@ -39,10 +56,124 @@ async fn favicon(
     ))?)
 }
 
+#[derive(Clone)]
+struct CorsConfig {
+    allowed_origins: Vec<String>,
+    allowed_methods: Vec<String>,
+    allowed_headers: Vec<String>,
+    allow_wildcard: bool,
+}
+
+fn env_list(name: &str, default: &str) -> Vec<String> {
+    get_from_env_or_default::<String>(name, default.to_string())
+        .split(",")
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+impl CorsConfig {
+    fn from_env() -> Self {
+        let config = CorsConfig {
+            allowed_origins: env_list("ALLOWED_ORIGINS", ""),
+            allowed_methods: env_list("ALLOWED_METHODS", "GET,POST,PUT,PATCH,DELETE"),
+            allowed_headers: env_list(
+                "ALLOWED_HEADERS",
+                "content-type,authorization,x-tenant",
+            ),
+            allow_wildcard: get_from_env_or_default("CORS_ALLOW_WILDCARD", false),
+        };
+        if config.allowed_origins.iter().any(|origin| origin == "*") {
+            assert!(
+                config.allow_wildcard,
+                "wildcard in ALLOWED_ORIGINS requires CORS_ALLOW_WILDCARD=true"
+            );
+        }
+        config
+    }
+
+    fn middleware(&self) -> Cors {
+        let cors = Cors::default()
+            .allowed_methods(self.allowed_methods.iter().map(String::as_str))
+            .allowed_headers(self.allowed_headers.iter().map(String::as_str))
+            .expose_headers(vec![REQUEST_ID_HEADER])
+            .max_age(3600);
+        if self.allow_wildcard && self.allowed_origins.iter().any(|o| o == "*") {
+            return cors.allow_any_origin();
+        }
+        self.allowed_origins
+            .iter()
+            .fold(cors, |cors, origin| cors.allowed_origin(origin))
+    }
+}
+
+/// The leptos UI hydrates through an inline bootstrap script, loads its wasm
+/// bundle and injects inline styles, so `default-src 'self'` alone breaks it.
+const DEFAULT_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' 'wasm-unsafe-eval'; \
+    style-src 'self' 'unsafe-inline'";
+
+/// Security headers set on every response, each overridable through its env
+/// variable. An empty value drops the header.
+#[derive(Clone)]
+struct SecurityHeadersConfig {
+    content_security_policy: String,
+    frame_options: String,
+    content_type_options: String,
+    strict_transport_security: Option<String>,
+}
+
+impl SecurityHeadersConfig {
+    fn from_env() -> Self {
+        // HSTS only makes sense when the service is reached over HTTPS
+        let hsts_enabled: bool = get_from_env_or_default("HSTS_ENABLED", false);
+        SecurityHeadersConfig {
+            content_security_policy: get_from_env_or_default(
+                "CONTENT_SECURITY_POLICY",
+                String::from(DEFAULT_CONTENT_SECURITY_POLICY),
+            ),
+            frame_options: get_from_env_or_default(
+                "X_FRAME_OPTIONS",
+                String::from("DENY"),
+            ),
+            content_type_options: get_from_env_or_default(
+                "X_CONTENT_TYPE_OPTIONS",
+                String::from("nosniff"),
+            ),
+            strict_transport_security: hsts_enabled.then(|| {
+                get_from_env_or_default(
+                    "STRICT_TRANSPORT_SECURITY",
+                    String::from("max-age=31536000; includeSubDomains"),
+                )
+            }),
+        }
+    }
+
+    fn middleware(&self) -> DefaultHeaders {
+        [
+            (
+                "Content-Security-Policy",
+                Some(&self.content_security_policy),
+            ),
+            ("X-Frame-Options", Some(&self.frame_options)),
+            ("X-Content-Type-Options", Some(&self.content_type_options)),
+            (
+                "Strict-Transport-Security",
+                self.strict_transport_security.as_ref(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.filter(|v| !v.is_empty()).map(|v| (name, v)))
+        .fold(DefaultHeaders::new(), |headers, (name, value)| {
+            headers.add((name, value.as_str()))
+        })
+    }
+}
+
 #[actix_web::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
-    env_logger::init();
+    logging::init();
     let service_prefix: String =
         get_from_env_unsafe("SERVICE_PREFIX").expect("SERVICE_PREFIX is not set");
 
@@ -68,6 +199,8 @@ async fn main() -> Result<()> {
     let cac_version: String = get_from_env_unsafe("CONTEXT_AWARE_CONFIG_VERSION")
         .expect("CONTEXT_AWARE_CONFIG_VERSION is not set");
     let max_pool_size = get_from_env_or_default("MAX_DB_CONNECTION_POOL_SIZE", 2);
+    let max_request_body_bytes: usize =
+        get_from_env_or_default("MAX_REQUEST_BODY_BYTES", 1024 * 1024);
 
     let api_host: String =
         get_from_env_unsafe("API_HOSTNAME").expect("API_HOSTNAME is not set");
@@ -85,6 +218,18 @@ async fn main() -> Result<()> {
             .split(",")
             .map(String::from)
             .collect::<HashSet<String>>();
+    let admin_users: HashSet<String> =
+        get_from_env_or_default::<String>("ADMIN_USERS", String::new())
+            .split(",")
+            .filter(|email| !email.is_empty())
+            .map(String::from)
+            .collect::<HashSet<String>>();
+
+    let cors_config = CorsConfig::from_env();
+    let security_headers = SecurityHeadersConfig::from_env();
+
+    #[cfg(feature = "redis-cache")]
+    let config_cache = config_cache::ConfigCache::from_env().await.map(Data::new);
 
     let schema_manager: PgSchemaManager = init_pool_manager(
         tenants.clone(),
@@ -94,6 +239,79 @@ async fn main() -> Result<()> {
     )
     .await;
 
+    let delete_expired_configs: bool =
+        get_from_env_or_default("DELETE_EXPIRED_CONFIGS", false);
+    let expired_configs_check_interval: u64 =
+        get_from_env_or_default("EXPIRED_CONFIGS_CHECK_INTERVAL", 300);
+    let cac_namespaces = if enable_tenant_and_scope {
+        tenants
+            .iter()
+            .map(|tenant| format!("{}_{}", tenant, AppScope::CAC))
+            .collect::<Vec<String>>()
+    } else {
+        vec!["cac_v1".to_string()]
+    };
+    let expiry_schema_manager = schema_manager.clone();
+    #[cfg(feature = "redis-cache")]
+    let expiry_config_cache = config_cache.clone();
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(
+            expired_configs_check_interval,
+        ));
+        loop {
+            interval.tick().await;
+            for namespace in cac_namespaces.iter() {
+                let manager = expiry_schema_manager.clone();
+                let schema = namespace.to_string();
+                let result = actix_web::rt::task::spawn_blocking(move || {
+                    manager
+                        .get_conn(schema)
+                        .map_err(|e| e.to_string())
+                        .and_then(|mut conn| {
+                            default_config::handle_expired_configs(
+                                &mut conn,
+                                delete_expired_configs,
+                            )
+                            .map_err(|e| e.to_string())
+                        })
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result);
+                match result {
+                    #[cfg(feature = "redis-cache")]
+                    Ok(deleted_keys) if !deleted_keys.is_empty() => {
+                        if let Some(cache) = expiry_config_cache.as_ref() {
+                            cache.invalidate(namespace).await;
+                        }
+                    }
+                    Ok(_) => (),
+                    Err(e) => log::error!(
+                        "failed to handle expired configs for {namespace}: {e}"
+                    ),
+                }
+            }
+        }
+    });
+
+    let db_ping_interval: u64 = get_from_env_or_default("DB_PING_INTERVAL_SECS", 30);
+    let ping_schema_manager = schema_manager.clone();
+    actix_web::rt::spawn(async move {
+        let mut interval =
+            actix_web::rt::time::interval(Duration::from_secs(db_ping_interval));
+        loop {
+            interval.tick().await;
+            let manager = ping_schema_manager.clone();
+            if let Err(e) = actix_web::rt::task::spawn_blocking(move || {
+                manager.ping_idle_connections()
+            })
+            .await
+            {
+                log::error!("db connection health check failed: {e}");
+            }
+        }
+    });
+
     /****** EXPERIMENTATION PLATFORM ENVs *********/
 
     let allow_same_keys_overlapping_ctx: bool =
@@ -105,6 +323,15 @@ async fn main() -> Result<()> {
     let allow_same_keys_non_overlapping_ctx: bool =
         get_from_env_unsafe("ALLOW_SAME_KEYS_NON_OVERLAPPING_CTX")
             .expect("ALLOW_SAME_KEYS_NON_OVERLAPPING_CTX not set");
+    let overlap_threshold: Option<f32> = std::env::var("EXPERIMENT_OVERLAP_THRESHOLD")
+        .ok()
+        .map(|threshold| {
+            threshold
+                .parse::<f32>()
+                .ok()
+                .filter(|threshold| (0.0..=1.0).contains(threshold))
+                .expect("EXPERIMENT_OVERLAP_THRESHOLD should be a number between 0 and 1")
+        });
 
     /****** EXPERIMENTATION PLATFORM ENVs *********/
 
@@ -133,13 +360,24 @@ async fn main() -> Result<()> {
         let site_root = &leptos_options.site_root;
         let leptos_envs = ui_envs.clone();
         let cac_host = cac_host.to_owned() + base.as_str();
-        App::new()
+        let app = App::new()
             .wrap_fn(|req, srv| {
                 let user = User::default();
                 req.extensions_mut().insert::<User>(user);
-                srv.call(req)
+                let span = logging::request_span(&req);
+                srv.call(req).instrument(span)
             })
+            .wrap_fn(tenant_activity::record_tenant_activity)
             .wrap(TenantMiddlewareFactory)
+            .wrap(RequestIdMiddlewareFactory)
+            .wrap(cors_config.middleware())
+            .wrap(security_headers.middleware())
+            .app_data(
+                JsonConfig::default()
+                    .limit(max_request_body_bytes)
+                    .error_handler(json_error_handler),
+            )
+            .app_data(QueryConfig::default().error_handler(query_error_handler))
             .app_data(Data::new(AppState {
                 db_pool: schema_manager.clone(),
                 default_config_validation_schema: get_default_config_validation_schema(),
@@ -153,6 +391,7 @@ async fn main() -> Result<()> {
                         .to_owned(),
                     allow_same_keys_non_overlapping_ctx:
                         allow_same_keys_non_overlapping_ctx.to_owned(),
+                    overlap_threshold,
                 },
 
                 snowflake_generator: Mutex::new(SnowflakeIdGenerator::new(1,1)),
@@ -163,7 +402,18 @@ async fn main() -> Result<()> {
                 tenant_middleware_exclusion_list: tenant_middleware_exclusion_list
                     .to_owned(),
                 service_prefix: service_prefix_str.to_owned(),
-            }))
+                admin_users: admin_users.to_owned(),
+            }));
+        #[cfg(feature = "redis-cache")]
+        let app = match config_cache.clone() {
+            Some(cache) => app.app_data(cache),
+            None => app,
+        };
+        #[cfg(feature = "graphql")]
+        let app = app.app_data(Data::new(graphql::schema())).service(
+            web::resource(format!("{base}/graphql")).route(web::post().to(graphql::handler)),
+        );
+        app
             .wrap(
                 actix_web::middleware::DefaultHeaders::new()
                     .add(("X-SERVER-VERSION", cac_version.to_string()))
@@ -183,29 +433,44 @@ async fn main() -> Result<()> {
                         get().to(|| async { HttpResponse::Ok().body("Health is good :D") }),
                     )
                     /***************************** V1 Routes *****************************/
+                    // only the scopes serving configs, contexts and experiments
+                    // are compressed, their payloads grow with the tenant
                     .service(
                         scope("/context")
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            .wrap_fn(config_cache::invalidate_on_write)
+                            .wrap(Compress::default())
                             .service(context::endpoints()),
                     )
                     .service(
                         scope("/dimension")
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            // priority changes reorder the contexts of the config
+                            .wrap_fn(config_cache::invalidate_on_write)
                             .service(dimension::endpoints()),
                     )
+                    .service(
+                        scope("/composite-dimension")
+                            .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            .service(composite_dimension::endpoints()),
+                    )
                     .service(
                         scope("/default-config")
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            .wrap_fn(config_cache::invalidate_on_write)
+                            .wrap(Compress::default())
                             .service(default_config::endpoints()),
                     )
                     .service(
                         scope("/config")
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            .wrap(Compress::default())
                             .service(config::endpoints()),
                     )
                     .service(
                         scope("/audit")
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
+                            .wrap(Compress::default())
                             .service(audit_log::endpoints()),
                     )
                     .service(
@@ -213,10 +478,21 @@ async fn main() -> Result<()> {
                             .wrap(AppExecutionScopeMiddlewareFactory::new(AppScope::CAC))
                             .service(functions::endpoints()),
                     )
+                    .service(scope("/admin").service(admin::endpoints()))
+                    .service(scope("/api").service(state::endpoints()))
+                    .service(
+                        experiments::endpoints(scope("/experiments"))
+                            .wrap(AppExecutionScopeMiddlewareFactory::new(
+                                AppScope::EXPERIMENTATION,
+                            ))
+                            .wrap(Compress::default()),
+                    )
                     .service(
-                        experiments::endpoints(scope("/experiments")).wrap(
-                            AppExecutionScopeMiddlewareFactory::new(AppScope::EXPERIMENTATION),
-                        ),
+                        scope("/webhooks")
+                            .wrap(AppExecutionScopeMiddlewareFactory::new(
+                                AppScope::EXPERIMENTATION,
+                            ))
+                            .service(webhooks::endpoints()),
                     )
                     /***************************** UI Routes ******************************/
                     .route("/fxn/{tail:.*}", leptos_actix::handle_server_fns())
@@ -0,0 +1,71 @@
+//! The experiment types as served by `GET /experiments`, shared by the
+//! clients that evaluate them.
+use alloc::{string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum ExperimentStatusType {
+    CREATED,
+    INPROGRESS,
+    CONCLUDED,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum VariantType {
+    CONTROL,
+    EXPERIMENTAL,
+}
+
+#[repr(C)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Variant {
+    pub id: String,
+    pub overrides: Value,
+    pub variant_type: VariantType,
+}
+
+pub type Variants = Vec<Variant>;
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Experiment {
+    pub variants: Variants,
+    pub name: String,
+    pub id: String,
+    pub traffic_percentage: u8,
+    pub context: Value,
+    pub status: ExperimentStatusType,
+    #[serde(default)]
+    pub override_keys: Vec<String>,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+}
+
+/// Namespace the experiment server assigns to experiments created without one.
+const DEFAULT_NAMESPACE: &str = "default";
+
+fn default_namespace() -> String {
+    String::from(DEFAULT_NAMESPACE)
+}
+
+impl Experiment {
+    /// Returns the override of `key` in the variant `variant_id`, if the
+    /// experiment has that variant and the variant overrides the key.
+    pub fn variant_override_for(&self, variant_id: &str, key: &str) -> Option<&Value> {
+        self.variants
+            .iter()
+            .find(|variant| variant.id == variant_id)
+            .and_then(|variant| variant.overrides.get(key))
+    }
+
+    /// Picks the variant `toss` falls into, see [`crate::decide_variant`].
+    pub fn decide_variant(&self, toss: i8) -> Option<&Variant> {
+        crate::decide_variant(self.traffic_percentage, &self.variants, toss, |v| {
+            v.variant_type == VariantType::EXPERIMENTAL
+        })
+    }
+}
+
+pub type Experiments = Vec<Experiment>;
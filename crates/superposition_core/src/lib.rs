@@ -0,0 +1,76 @@
+//! Pure computations shared by the superposition clients, usable without `std`.
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+mod experiment;
+
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+pub use experiment::{
+    Experiment, ExperimentStatusType, Experiments, Variant, VariantType, Variants,
+};
+
+/// Upper bounds (exclusive) of the toss range assigned to each variant,
+/// saturating at `i8::MAX` for out of range traffic.
+pub fn variant_buckets(traffic: u8, variant_count: u8) -> Vec<i8> {
+    (1..=variant_count)
+        .map(|i| (traffic as i32 * i as i32).min(i8::MAX as i32) as i8)
+        .collect::<Vec<i8>>()
+}
+
+/// Decides which of `variants` a `toss` falls into for an experiment running
+/// at `traffic` percent per variant. A negative toss forces the first
+/// experimental variant, as identified by `is_experimental`.
+pub fn decide_variant<T>(
+    traffic: u8,
+    variants: &[T],
+    toss: i8,
+    is_experimental: impl Fn(&T) -> bool,
+) -> Option<&T> {
+    if toss < 0 {
+        if let Some(variant) = variants.iter().find(|v| is_experimental(v)) {
+            return Some(variant);
+        }
+    }
+    let variant_count = variants.len().min(u8::MAX as usize) as u8;
+    let range = traffic as i32 * variant_count as i32;
+    if (toss as i32) >= range {
+        return None;
+    }
+    let index = variant_buckets(traffic, variant_count)
+        .into_iter()
+        .position(|x| toss < x)?;
+    variants.get(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_buckets() {
+        assert_eq!(variant_buckets(25, 2), [25, 50]);
+    }
+
+    #[test]
+    fn test_decide_variant() {
+        let variants = ["control", "experimental"];
+        let is_experimental = |v: &&str| *v == "experimental";
+
+        assert_eq!(
+            decide_variant(25, &variants, 10, is_experimental),
+            Some(&"control")
+        );
+        assert_eq!(
+            decide_variant(25, &variants, 30, is_experimental),
+            Some(&"experimental")
+        );
+        assert_eq!(decide_variant(25, &variants, 50, is_experimental), None);
+        assert_eq!(
+            decide_variant(25, &variants, -1, is_experimental),
+            Some(&"experimental")
+        );
+    }
+}
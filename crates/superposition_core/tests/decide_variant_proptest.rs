@@ -0,0 +1,37 @@
+use proptest::prelude::*;
+use superposition_core::decide_variant;
+
+proptest! {
+    #[test]
+    fn decide_variant_picks_an_applicable_variant(
+        traffic in prop_oneof![Just(0u8), Just(100u8), any::<u8>()],
+        num_variants in 0usize..10,
+        toss in prop_oneof![Just(0i8), Just(i8::MAX), Just(-1i8), any::<i8>()],
+    ) {
+        // the last variant is the experimental one, the rest are controls
+        let variants: Vec<usize> = (0..num_variants).collect();
+        let is_experimental = |v: &usize| *v + 1 == num_variants;
+
+        let decided = decide_variant(traffic, &variants, toss, is_experimental);
+
+        if let Some(variant) = decided {
+            prop_assert!(variants.contains(variant));
+        }
+        if toss as i32 >= traffic as i32 * num_variants as i32 {
+            prop_assert_eq!(decided, None);
+        }
+        if toss < 0 && num_variants > 0 {
+            prop_assert_eq!(decided, variants.last());
+        }
+    }
+
+    #[test]
+    fn single_variant_experiments_only_match_within_traffic(
+        traffic in 0u8..=100,
+        toss in 0i8..100,
+    ) {
+        let variants = ["experimental"];
+        let decided = decide_variant(traffic, &variants, toss, |_| true);
+        prop_assert_eq!(decided.is_some(), (toss as u8) < traffic);
+    }
+}
@@ -0,0 +1,143 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use serde_json::{json, Map, Value};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, Token, Type,
+};
+
+struct ConfigKey {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for ConfigKey {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(ConfigKey { name, ty })
+    }
+}
+
+/// `MyConfig { my_key: bool, max_retries: u8 }`
+struct ConfigDefinition {
+    name: Ident,
+    keys: Punctuated<ConfigKey, Token![,]>,
+}
+
+impl Parse for ConfigDefinition {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let keys = content.parse_terminated(ConfigKey::parse, Token![,])?;
+        Ok(ConfigDefinition { name, keys })
+    }
+}
+
+/// JSON schema of a config value of type `ty`, as accepted by the default
+/// config API.
+fn json_schema(ty: &Type) -> syn::Result<Value> {
+    let type_name = match ty {
+        // the last segment, so that `std::string::String` is a `String`
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+    let integer = |minimum: Value, maximum: Value| json!({ "type": "integer", "minimum": minimum, "maximum": maximum });
+    let schema = match type_name.as_deref() {
+        Some("bool") => json!({ "type": "boolean" }),
+        Some("String") => json!({ "type": "string" }),
+        Some("f32" | "f64") => json!({ "type": "number" }),
+        Some("u8") => integer(json!(0), json!(u8::MAX)),
+        Some("u16") => integer(json!(0), json!(u16::MAX)),
+        Some("u32") => integer(json!(0), json!(u32::MAX)),
+        Some("u64") => integer(json!(0), json!(u64::MAX)),
+        Some("i8") => integer(json!(i8::MIN), json!(i8::MAX)),
+        Some("i16") => integer(json!(i16::MIN), json!(i16::MAX)),
+        Some("i32") => integer(json!(i32::MIN), json!(i32::MAX)),
+        Some("i64") => integer(json!(i64::MIN), json!(i64::MAX)),
+        Some("Value") => json!({}),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "unsupported config type, expected one of bool, String, f32, f64, \
+                 u8, u16, u32, u64, i8, i16, i32, i64 or serde_json::Value",
+            ))
+        }
+    };
+    Ok(schema)
+}
+
+/// `MyConfig` becomes `MY_CONFIG`.
+fn screaming_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (idx, ch) in name.char_indices() {
+        if ch.is_uppercase() && idx > 0 {
+            result.push('_');
+        }
+        result.push(ch.to_ascii_uppercase());
+    }
+    result
+}
+
+/// Declares the config keys an application reads, generating
+///
+/// - a struct with one typed field per key, filled in by
+///   `cac_client::Client::get_typed_config`
+/// - a `<NAME>_SCHEMA` constant holding the JSON schema of every key, keyed by
+///   config key, which can be pushed to the server at startup
+///
+/// ```ignore
+/// cac_client::superposition_config! {
+///     MyConfig { my_key: bool, max_retries: u8 }
+/// }
+///
+/// let config: MyConfig = client.get_typed_config(&json!({ "os": "android" }))?;
+/// ```
+#[proc_macro]
+pub fn superposition_config(input: TokenStream) -> TokenStream {
+    let ConfigDefinition { name, keys } = parse_macro_input!(input as ConfigDefinition);
+
+    let mut schema = Map::new();
+    for key in keys.iter() {
+        match json_schema(&key.ty) {
+            Ok(key_schema) => {
+                schema.insert(key.name.to_string(), key_schema);
+            }
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+    let schema = Value::Object(schema).to_string();
+    let schema_const = Ident::new(
+        &format!("{}_SCHEMA", screaming_snake_case(&name.to_string())),
+        Span::call_site(),
+    );
+    let field_names = keys.iter().map(|key| &key.name).collect::<Vec<_>>();
+    let field_types = keys.iter().map(|key| &key.ty);
+    let key_names = field_names.iter().map(|name| name.to_string());
+
+    quote! {
+        #[derive(Clone, Debug, ::cac_client::__private::serde::Deserialize)]
+        #[serde(crate = "::cac_client::__private::serde")]
+        pub struct #name {
+            #(pub #field_names: #field_types,)*
+        }
+
+        pub const #schema_const: &str = #schema;
+
+        impl ::cac_client::TypedConfig for #name {
+            const KEYS: &'static [&'static str] = &[#(#key_names),*];
+            const SCHEMA: &'static str = #schema_const;
+        }
+    }
+    .into()
+}
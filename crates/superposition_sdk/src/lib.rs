@@ -0,0 +1,168 @@
+//! One shared library carrying the C FFI of both the config client, as the
+//! `cac_*` functions, and the experimentation client, as the `expt_*`
+//! functions. Each client keeps its own last error, read it back with the
+//! matching `*_last_error_message`.
+#![allow(clippy::missing_safety_doc, clippy::not_unsafe_ptr_arg_deref)]
+
+use std::{
+    ffi::{c_char, c_int, c_short, c_ulong},
+    sync::Arc,
+};
+
+use cac_client::interface as cac;
+use experimentation_client::interface as expt;
+
+/// Opaque handle to a config client, an `Arc<cac_client::Client>` behind the
+/// pointer.
+pub struct CacClient {
+    _private: [u8; 0],
+}
+
+/// Opaque handle to an experimentation client, an
+/// `Arc<experimentation_client::Client>` behind the pointer.
+pub struct ExptClient {
+    _private: [u8; 0],
+}
+
+/// Frees a string returned by any `cac_*` or `expt_*` function.
+#[no_mangle]
+pub unsafe extern "C" fn superposition_free_string(s: *mut c_char) {
+    cac::free_string(s)
+}
+
+/********** Config client **********/
+
+#[no_mangle]
+pub extern "C" fn cac_last_error_length() -> c_int {
+    cac::last_error_length()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cac_last_error_message() -> *const c_char {
+    cac::last_error_message()
+}
+
+#[no_mangle]
+pub extern "C" fn cac_new_client(
+    tenant: *const c_char,
+    update_frequency: c_ulong,
+    hostname: *const c_char,
+) -> c_int {
+    cac::new_client(tenant, update_frequency, hostname)
+}
+
+#[no_mangle]
+pub extern "C" fn cac_start_polling_update(tenant: *const c_char) {
+    cac::start_polling_update(tenant)
+}
+
+#[no_mangle]
+pub extern "C" fn cac_free_client(ptr: *mut CacClient) {
+    cac::free_client(ptr as *mut Arc<cac_client::Client>)
+}
+
+#[no_mangle]
+pub extern "C" fn cac_get_client(tenant: *const c_char) -> *mut CacClient {
+    cac::get_client(tenant) as *mut CacClient
+}
+
+#[no_mangle]
+pub extern "C" fn cac_get_last_modified(client: *mut CacClient) -> *const c_char {
+    cac::get_last_modified(client as *mut Arc<cac_client::Client>)
+}
+
+#[no_mangle]
+pub extern "C" fn cac_get_config(
+    client: *mut CacClient,
+    query: *const c_char,
+) -> *const c_char {
+    cac::get_config(client as *mut Arc<cac_client::Client>, query)
+}
+
+#[no_mangle]
+pub extern "C" fn cac_get_resolved_config(
+    client: *mut CacClient,
+    query: *const c_char,
+    filter_keys: *const c_char,
+    merge_strategy: *const c_char,
+) -> *const c_char {
+    cac::get_resolved_config(
+        client as *mut Arc<cac_client::Client>,
+        query,
+        filter_keys,
+        merge_strategy,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn cac_get_default_config(
+    client: *mut CacClient,
+    filter_keys: *const c_char,
+) -> *const c_char {
+    cac::get_default_config(client as *mut Arc<cac_client::Client>, filter_keys)
+}
+
+/********** Experimentation client **********/
+
+#[no_mangle]
+pub extern "C" fn expt_last_error_length() -> c_int {
+    expt::last_error_length()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn expt_last_error_message() -> *const c_char {
+    expt::last_error_message()
+}
+
+#[no_mangle]
+pub extern "C" fn expt_new_client(
+    tenant: *const c_char,
+    update_frequency: c_ulong,
+    hostname: *const c_char,
+) -> c_int {
+    expt::new_client(tenant, update_frequency, hostname)
+}
+
+#[no_mangle]
+pub extern "C" fn expt_start_polling_update(tenant: *const c_char) {
+    expt::start_polling_update(tenant)
+}
+
+#[no_mangle]
+pub extern "C" fn expt_free_client(ptr: *mut ExptClient) {
+    expt::free_client(ptr as *mut Arc<experimentation_client::Client>)
+}
+
+#[no_mangle]
+pub extern "C" fn expt_get_client(tenant: *const c_char) -> *mut ExptClient {
+    expt::get_client(tenant) as *mut ExptClient
+}
+
+#[no_mangle]
+pub extern "C" fn expt_get_applicable_variant(
+    client: *mut ExptClient,
+    c_context: *const c_char,
+    toss: c_short,
+) -> *mut c_char {
+    expt::get_applicable_variant(
+        client as *mut Arc<experimentation_client::Client>,
+        c_context,
+        toss,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn expt_get_satisfied_experiments(
+    client: *mut ExptClient,
+    c_context: *const c_char,
+) -> *mut c_char {
+    expt::get_satisfied_experiments(
+        client as *mut Arc<experimentation_client::Client>,
+        c_context,
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn expt_get_running_experiments(client: *mut ExptClient) -> *mut c_char {
+    expt::get_running_experiments(client as *mut Arc<experimentation_client::Client>)
+}
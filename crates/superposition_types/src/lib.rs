@@ -36,6 +36,56 @@ impl SuperpositionUser for User {
     }
 }
 
+/// Serializes the user without its auth token, with the email given by
+/// `get_recorded_email`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for User {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut user = serializer.serialize_struct("User", 3)?;
+        user.serialize_field("email", &self.get_recorded_email())?;
+        user.serialize_field("username", &self.username)?;
+        user.serialize_field("auth_type", &self.auth_type)?;
+        user.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl User {
+    /// The email to store or log for this user. With `REDACT_USER_PII=true` the
+    /// local part of the email is replaced by a hash, keeping its domain.
+    pub fn get_recorded_email(&self) -> String {
+        use std::sync::OnceLock;
+
+        static REDACT_USER_PII: OnceLock<bool> = OnceLock::new();
+        let redact = *REDACT_USER_PII.get_or_init(|| {
+            std::env::var("REDACT_USER_PII").map_or(false, |value| value == "true")
+        });
+        recorded_email(&self.email, redact)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn recorded_email(email: &str, redact: bool) -> String {
+    if redact {
+        redact_email(email)
+    } else {
+        email.to_string()
+    }
+}
+
+/// Replaces the local part of `email` with a prefix of its blake3 hash, so the
+/// same user keeps the same redacted email.
+#[cfg(feature = "serde")]
+fn redact_email(email: &str) -> String {
+    let hash_of = |value: &str| blake3::hash(value.as_bytes()).to_hex()[..16].to_string();
+    match email.rsplit_once('@') {
+        Some((local, domain)) => format!("{}@{domain}", hash_of(local)),
+        None => hash_of(email),
+    }
+}
+
 impl Default for User {
     fn default() -> Self {
         Self {
@@ -73,3 +123,27 @@ impl FromRequest for User {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_email_keeps_domain() {
+        let redacted = redact_email("jane.doe@superposition.io");
+        let (local, domain) = redacted.split_once('@').unwrap();
+        assert_eq!(domain, "superposition.io");
+        assert_eq!(local.len(), 16);
+        assert_ne!(local, "jane.doe");
+        assert_eq!(redacted, redact_email("jane.doe@superposition.io"));
+        assert_eq!(redact_email("not-an-email").len(), 16);
+    }
+
+    #[test]
+    fn test_recorded_email() {
+        let email = "jane.doe@superposition.io";
+        assert_eq!(recorded_email(email, false), email);
+        assert_eq!(recorded_email(email, true), redact_email(email));
+        assert!(!recorded_email(email, true).contains("jane.doe"));
+    }
+}
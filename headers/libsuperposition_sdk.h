@@ -0,0 +1,62 @@
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Opaque handle to a config client, an `Arc<cac_client::Client>` behind the
+ * pointer.
+ */
+typedef struct CacClient CacClient;
+
+/**
+ * Opaque handle to an experimentation client, an
+ * `Arc<experimentation_client::Client>` behind the pointer.
+ */
+typedef struct ExptClient ExptClient;
+
+/**
+ * Frees a string returned by any `cac_*` or `expt_*` function.
+ */
+void superposition_free_string(char *s);
+
+int cac_last_error_length(void);
+
+const char *cac_last_error_message(void);
+
+int cac_new_client(const char *tenant, unsigned long update_frequency, const char *hostname);
+
+void cac_start_polling_update(const char *tenant);
+
+void cac_free_client(struct CacClient *ptr);
+
+struct CacClient *cac_get_client(const char *tenant);
+
+const char *cac_get_last_modified(struct CacClient *client);
+
+const char *cac_get_config(struct CacClient *client, const char *query);
+
+const char *cac_get_resolved_config(struct CacClient *client,
+                                    const char *query,
+                                    const char *filter_keys,
+                                    const char *merge_strategy);
+
+const char *cac_get_default_config(struct CacClient *client, const char *filter_keys);
+
+int expt_last_error_length(void);
+
+const char *expt_last_error_message(void);
+
+int expt_new_client(const char *tenant, unsigned long update_frequency, const char *hostname);
+
+void expt_start_polling_update(const char *tenant);
+
+void expt_free_client(struct ExptClient *ptr);
+
+struct ExptClient *expt_get_client(const char *tenant);
+
+char *expt_get_applicable_variant(struct ExptClient *client, const char *c_context, short toss);
+
+char *expt_get_satisfied_experiments(struct ExptClient *client, const char *c_context);
+
+char *expt_get_running_experiments(struct ExptClient *client);
@@ -0,0 +1,33 @@
+const host = pm.variables.get("host");
+
+function add_non_nullable_default_config() {
+    const options = {
+        'method': 'PUT',
+        'url': `${host}/default-config/key4`,
+        'header': {
+            'x-tenant': 'test',
+            'Content-Type': 'application/json'
+        },
+        "body": {
+            "mode": "raw",
+            "raw": JSON.stringify({
+                "value": "value1",
+                // the schema allows null, so only nullable rejects it
+                "schema": {
+                    "type": ["string", "null"]
+                },
+                "nullable": false
+            })
+        }
+    };
+    pm.sendRequest(options, function (error, response) {
+        if (error) {
+            console.log(`Error creating default config: key4`);
+            console.log(error);
+            return;
+        }
+        console.log(`created default config: key4`);
+    });
+}
+
+add_non_nullable_default_config();
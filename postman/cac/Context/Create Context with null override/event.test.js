@@ -0,0 +1,8 @@
+pm.test("422 check", function () {
+    pm.response.to.have.status(422);
+});
+
+pm.test("Null override for a non-nullable key is rejected", function () {
+    const response = pm.response.json();
+    pm.expect(response.message).to.be.eq("key4 is not nullable, provide a non-null value");
+});
@@ -0,0 +1,55 @@
+const host = pm.variables.get("host");
+
+function merge_patch(key, patch) {
+    return {
+        'method': 'PATCH',
+        'url': `${host}/default-config/${key}`,
+        'header': {
+            'x-tenant': 'test',
+            'Content-Type': 'application/merge-patch+json'
+        },
+        "body": {
+            "mode": "raw",
+            "raw": JSON.stringify(patch)
+        }
+    };
+}
+
+function add_default_config_and_patch() {
+    const options = {
+        'method': 'PUT',
+        'url': `${host}/default-config/key3`,
+        'header': {
+            'x-tenant': 'test',
+            'Content-Type': 'application/json'
+        },
+        "body": {
+            "mode": "raw",
+            "raw": JSON.stringify({
+                "value": { "a": 1 },
+                "schema": {
+                    "type": "object"
+                }
+            })
+        }
+    };
+    pm.sendRequest(options, function (error, response) {
+        if (error) {
+            console.log(`Error creating default config: key3`);
+            console.log(error);
+            return;
+        }
+        console.log(`created default config: key3`);
+        // sent together, so the read-modify-write of each overlaps the other
+        for (const patch of [{ "b": 2 }, { "c": 3 }]) {
+            pm.sendRequest(merge_patch("key3", patch), function (error, response) {
+                if (error) {
+                    console.log(`Error patching default config: key3`);
+                    console.log(error);
+                }
+            });
+        }
+    });
+}
+
+add_default_config_and_patch();
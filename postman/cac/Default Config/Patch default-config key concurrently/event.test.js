@@ -0,0 +1,7 @@
+pm.test("200 check", function () {
+    pm.response.to.have.status(200);
+})
+
+pm.test("Check that no concurrent patch was lost", function () {
+    pm.expect(pm.response.json().value).to.deep.eq({ "a": 1, "b": 2, "c": 3, "d": 4 });
+});
@@ -0,0 +1,41 @@
+const host = pm.environment.get("host");
+
+pm.test("200 OK", function () {
+    pm.response.to.have.status(200);
+});
+
+pm.test("Test fetched variant", function () {
+    const experiment_id = pm.environment.get("experiment_id");
+    const variant = pm.response.json();
+    const expected_variant = {
+        "id": `${experiment_id}-test1`,
+        "variant_type": "EXPERIMENTAL",
+        "overrides": {
+            "pmTestKey1": "value2-test",
+            "pmTestKey2": "value2-test"
+        }
+    };
+
+    console.log("Actual Variant:", JSON.stringify(variant, null, 4));
+    console.log("Expected Variant:", JSON.stringify(expected_variant, null, 4));
+    pm.expect(JSON.stringify(variant)).to.be.eq(JSON.stringify(expected_variant));
+});
+
+pm.test("404 for an unknown variant", function () {
+    const experiment_id = pm.environment.get("experiment_id");
+    const options = {
+        'method': 'GET',
+        'url': `${host}/experiments/${experiment_id}/variant/${experiment_id}-unknown`,
+        'header': {
+            'x-tenant': 'test',
+        }
+    };
+
+    pm.sendRequest(options, function (error, response) {
+        if (error) {
+            console.log("Failed to fetch variant");
+            throw error;
+        }
+        pm.expect(response.code).to.be.eq(404);
+    });
+});